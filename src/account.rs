@@ -0,0 +1,320 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::setup::JournalFlavor;
+use jiff::Zoned;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Root accounts of beancount
+const BEANCOUNT_ROOTS: [&str; 5] = ["Assets", "Liabilities", "Equity", "Income", "Expenses"];
+
+/// Words for padding of long account names
+const FILLER: [&str; 8] = [
+    "Operating",
+    "Consolidated",
+    "Subsidiary",
+    "International",
+    "Department",
+    "Reconciliation",
+    "Intercompany",
+    "Allocation",
+];
+
+/// Style of account names
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AccountStyle {
+    /// Assets:Ay2024:Am01, Expenses:Ey2024:Em01:Ed01
+    #[default]
+    Default,
+    /// Names are 50 - 200 characters long
+    Long,
+    /// Names have 11 - 12 levels
+    Deep,
+    /// Finnish and CJK names, and emoji if flavor allows them
+    Unicode,
+    /// Names with spaces
+    Spaces,
+    /// Numeric segments: Assets:2024:01, Expenses:2024:01:01
+    Numeric,
+}
+
+impl AccountStyle {
+    pub const DEFAULT: &'static str = "default";
+    pub const LONG: &'static str = "long";
+    pub const DEEP: &'static str = "deep";
+    pub const UNICODE: &'static str = "unicode";
+    pub const SPACES: &'static str = "spaces";
+    pub const NUMERIC: &'static str = "numeric";
+
+    /// Is account style supported by flavor
+    ///
+    /// Names with spaces are supported only by (h)ledger.
+    pub fn supported(&self, flavor: &JournalFlavor) -> bool {
+        match self {
+            AccountStyle::Spaces => *flavor == JournalFlavor::Ledger,
+            _ => true,
+        }
+    }
+}
+
+impl TryFrom<&str> for AccountStyle {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::DEFAULT => Ok(AccountStyle::Default),
+            Self::LONG => Ok(AccountStyle::Long),
+            Self::DEEP => Ok(AccountStyle::Deep),
+            Self::UNICODE => Ok(AccountStyle::Unicode),
+            Self::SPACES => Ok(AccountStyle::Spaces),
+            Self::NUMERIC => Ok(AccountStyle::Numeric),
+            _ => Err(format!(
+                "Unknown account style: {}, supported styles are: {}, {}, {}, {}, {}, {}",
+                value,
+                Self::DEFAULT,
+                Self::LONG,
+                Self::DEEP,
+                Self::UNICODE,
+                Self::SPACES,
+                Self::NUMERIC
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for AccountStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountStyle::Default => write!(f, "{}", Self::DEFAULT),
+            AccountStyle::Long => write!(f, "{}", Self::LONG),
+            AccountStyle::Deep => write!(f, "{}", Self::DEEP),
+            AccountStyle::Unicode => write!(f, "{}", Self::UNICODE),
+            AccountStyle::Spaces => write!(f, "{}", Self::SPACES),
+            AccountStyle::Numeric => write!(f, "{}", Self::NUMERIC),
+        }
+    }
+}
+
+/// Asset and expense accounts of txn
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxnAccounts {
+    pub assets: String,
+    pub expenses: String,
+}
+
+/// Generator of account names
+///
+/// Asset account is based on the month, and expense account
+/// is based on the day of txn. All names are validated
+/// against the naming rules of flavor.
+#[derive(Debug, Clone, Default)]
+pub struct AccountGen {
+    style: AccountStyle,
+    flavor: JournalFlavor,
+}
+
+impl AccountGen {
+    pub fn try_new(style: AccountStyle, flavor: JournalFlavor) -> Result<Self, Box<dyn Error>> {
+        if !style.supported(&flavor) {
+            let msg =
+                format!("Account style '{style}' is not supported for journal flavor '{flavor}'");
+            return Err(msg.into());
+        }
+        Ok(AccountGen { style, flavor })
+    }
+
+    /// Accounts of txn
+    pub fn accounts(&self, ts: &Zoned) -> Result<TxnAccounts, Box<dyn Error>> {
+        let y = ts.year();
+        let m = ts.month();
+        let d = ts.day();
+
+        let (assets, expenses) = match self.style {
+            AccountStyle::Default => (
+                format!("Assets:Ay{y:0>4}:Am{m:0>2}"),
+                format!("Expenses:Ey{y:0>4}:Em{m:0>2}:Ed{d:0>2}"),
+            ),
+            AccountStyle::Long => (
+                long_name(
+                    format!("Assets:Ay{y:0>4}:Am{m:0>2}"),
+                    50 + (m as usize * 13) % 151,
+                ),
+                long_name(
+                    format!("Expenses:Ey{y:0>4}:Em{m:0>2}:Ed{d:0>2}"),
+                    50 + ((m as usize * 31 + d as usize) * 7) % 151,
+                ),
+            ),
+            AccountStyle::Deep => {
+                let h = (m - 1) / 6 + 1;
+                let q = (m - 1) / 3 + 1;
+                let w = ts.date().iso_week_date().week();
+                (
+                    format!(
+                        "Assets:Ay{y:0>4}:Ah{h}:Aq{q}:Am{m:0>2}:Bank:Branch:Office:Desk:Drawer:Wallet"
+                    ),
+                    format!(
+                        "Expenses:Ey{y:0>4}:Eh{h}:Eq{q}:Em{m:0>2}:Ew{w:0>2}:Ed{d:0>2}:Region:Department:Team:Project:Task"
+                    ),
+                )
+            }
+            AccountStyle::Unicode => {
+                let leaf = match self.flavor {
+                    JournalFlavor::Tackler => "Kahvi",
+                    _ => "☕",
+                };
+                (
+                    format!("Assets:Säästötili-{y:0>4}:月{m:0>2}"),
+                    format!("Expenses:Kulut-Äy{y:0>4}:月{m:0>2}:日{d:0>2}:{leaf}"),
+                )
+            }
+            AccountStyle::Spaces => (
+                format!("Assets:Bank Account {y:0>4}:Month {m:0>2}"),
+                format!("Expenses:Office Supplies {y:0>4}:Month {m:0>2}:Day {d:0>2}"),
+            ),
+            AccountStyle::Numeric => (
+                format!("Assets:{y:0>4}:{m:0>2}"),
+                format!("Expenses:{y:0>4}:{m:0>2}:{d:0>2}"),
+            ),
+        };
+        validate(&self.flavor, &assets)?;
+        validate(&self.flavor, &expenses)?;
+        Ok(TxnAccounts { assets, expenses })
+    }
+}
+
+/// Pad the last segment of account with words, up to `len` characters
+fn long_name(account: String, len: usize) -> String {
+    let mut name = account;
+    let mut i = 0;
+    while name.len() < len {
+        name.push('-');
+        name.push_str(FILLER[i % FILLER.len()]);
+        i += 1;
+    }
+    name.truncate(len);
+    if name.ends_with('-') {
+        name.pop();
+        name.push('X');
+    }
+    name
+}
+
+/// Validate account name against the naming rules of flavor
+///
+/// - all:       segments are not empty, and there is no tab or double space
+/// - ledger:    segments may have single spaces
+/// - tackler:   letters, digits and `-`, `_`, `.`, `·`, and root starts with letter
+/// - beancount: root is one of Assets, Liabilities, Equity, Income or Expenses,
+///              segments start with capital letter, digit or non-ASCII character,
+///              and other characters are ASCII letters, digits, `-` or non-ASCII
+#[allow(clippy::doc_overindented_list_items)]
+pub fn validate(flavor: &JournalFlavor, account: &str) -> Result<(), Box<dyn Error>> {
+    let invalid = |reason: &str| -> Result<(), Box<dyn Error>> {
+        Err(format!("Invalid account name for {flavor}: '{account}' ({reason})").into())
+    };
+    if account.contains('\t') || account.contains("  ") {
+        return invalid("tab or double space");
+    }
+    let segments: Vec<&str> = account.split(':').collect();
+    if segments.iter().any(|s| s.is_empty() || s.trim() != *s) {
+        return invalid("empty segment");
+    }
+    match flavor {
+        JournalFlavor::Ledger => {}
+        JournalFlavor::Tackler => {
+            let valid_char = |c: char| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '·');
+            if !account.chars().all(|c| c == ':' || valid_char(c)) {
+                return invalid("invalid character");
+            }
+            if !segments[0].starts_with(char::is_alphabetic) {
+                return invalid("root must start with letter");
+            }
+        }
+        JournalFlavor::Beancount => {
+            if !BEANCOUNT_ROOTS.contains(&segments[0]) {
+                return invalid("unknown root account");
+            }
+            for segment in &segments[1..] {
+                let mut chars = segment.chars();
+                let first = chars.next().unwrap_or(' ');
+                if !(first.is_ascii_uppercase() || first.is_ascii_digit() || !first.is_ascii()) {
+                    return invalid("segment must start with capital letter or digit");
+                }
+                if !chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || !c.is_ascii()) {
+                    return invalid("invalid character");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::account::{AccountGen, AccountStyle, validate};
+    use crate::setup::JournalFlavor;
+    use jiff::Zoned;
+
+    #[test]
+    fn test_styles() {
+        let ts: Zoned = "2024-12-31T12:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let accs = AccountGen::default().accounts(&ts).unwrap(/*:test:*/);
+        assert_eq!(accs.assets, "Assets:Ay2024:Am12");
+        assert_eq!(accs.expenses, "Expenses:Ey2024:Em12:Ed31");
+
+        for flavor in [
+            JournalFlavor::Tackler,
+            JournalFlavor::Ledger,
+            JournalFlavor::Beancount,
+        ] {
+            for style in [
+                AccountStyle::Default,
+                AccountStyle::Long,
+                AccountStyle::Deep,
+                AccountStyle::Unicode,
+                AccountStyle::Spaces,
+                AccountStyle::Numeric,
+            ] {
+                match AccountGen::try_new(style.clone(), flavor.clone()) {
+                    Ok(acc_gen) => {
+                        let accs = acc_gen.accounts(&ts).unwrap(/*:test:*/);
+                        match style {
+                            AccountStyle::Long => {
+                                assert!((50..=200).contains(&accs.expenses.len()));
+                            }
+                            AccountStyle::Deep => {
+                                assert!(accs.expenses.split(':').count() >= 10);
+                            }
+                            _ => {}
+                        }
+                    }
+                    Err(_) => assert_eq!(style, AccountStyle::Spaces),
+                }
+            }
+        }
+        let acc_gen =
+            AccountGen::try_new(AccountStyle::Unicode, JournalFlavor::Ledger).unwrap(/*:test:*/);
+        let accs = acc_gen.accounts(&ts).unwrap(/*:test:*/);
+        assert_eq!(accs.expenses, "Expenses:Kulut-Äy2024:月12:日31:☕");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&JournalFlavor::Ledger, "Assets:Bank Account").is_ok());
+        assert!(validate(&JournalFlavor::Ledger, "Assets:Bank  Account").is_err());
+        assert!(validate(&JournalFlavor::Ledger, "Assets::Bank").is_err());
+
+        assert!(validate(&JournalFlavor::Tackler, "a:ay2024:am01").is_ok());
+        assert!(validate(&JournalFlavor::Tackler, "Assets:Bank Account").is_err());
+        assert!(validate(&JournalFlavor::Tackler, "Expenses:☕").is_err());
+        assert!(validate(&JournalFlavor::Tackler, "1Assets:Bank").is_err());
+
+        assert!(validate(&JournalFlavor::Beancount, "Assets:2024:01").is_ok());
+        assert!(validate(&JournalFlavor::Beancount, "Expenses:Kulut:日01:☕").is_ok());
+        assert!(validate(&JournalFlavor::Beancount, "a:ay2024").is_err());
+        assert!(validate(&JournalFlavor::Beancount, "Assets:bank").is_err());
+        assert!(validate(&JournalFlavor::Beancount, "Assets:Bank_Account").is_err());
+    }
+}
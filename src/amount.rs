@@ -0,0 +1,598 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::prng::Prng;
+use crate::setup::JournalFlavor;
+use jiff::Zoned;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Maximum count of digits of amount (integer and decimal digits)
+const MAX_DIGITS: u32 = 38;
+
+/// Largest units and scale of amounts (and their sums) for the flavor
+///
+/// Tackler's decimals have 96-bit integer with scale of at most 28, and beancount's
+/// arithmetic is done with precision of 28 significant digits. (h)ledger have
+/// arbitrary precision, so they are limited only by `MAX_DIGITS`.
+fn limits(flavor: &JournalFlavor) -> (i128, u32) {
+    match flavor {
+        JournalFlavor::Tackler => ((1 << 96) - 1, 28),
+        JournalFlavor::Beancount => (10i128.pow(28) - 1, MAX_DIGITS),
+        JournalFlavor::Ledger => (10i128.pow(MAX_DIGITS) - 1, MAX_DIGITS),
+    }
+}
+
+/// Largest integer, which is exact with f64 (2^53)
+const F64_EXACT: f64 = 9_007_199_254_740_992.0;
+
+/// Fixed-point decimal amount: `units * 10^-scale`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount {
+    pub units: i128,
+    pub scale: u32,
+}
+
+impl Amount {
+    pub fn new(units: i128, scale: u32) -> Amount {
+        Amount { units, scale }
+    }
+
+    /// Integer value with scale, `None` if it's too large for the scale
+    pub fn from_int(value: i64, scale: u32) -> Option<Amount> {
+        10i128
+            .checked_pow(scale)
+            .and_then(|s| s.checked_mul(i128::from(value)))
+            .map(|units| Amount { units, scale })
+    }
+
+    /// Sum of amounts, `None` in case of overflow
+    ///
+    /// Amounts must have the same scale.
+    pub fn checked_add(&self, other: &Amount) -> Option<Amount> {
+        debug_assert_eq!(self.scale, other.scale);
+        self.units
+            .checked_add(other.units)
+            .map(|units| Amount::new(units, self.scale))
+    }
+
+    /// Product of amounts, `None` in case of overflow
+    ///
+    /// Scale of product is the sum of scales.
+    pub fn checked_mul(&self, other: &Amount) -> Option<Amount> {
+        self.units
+            .checked_mul(other.units)
+            .map(|units| Amount::new(units, self.scale + other.scale))
+    }
+
+    /// Round amount to scale, half away from zero
+    pub fn round(&self, scale: u32) -> Amount {
+        if scale >= self.scale {
+            return *self;
+        }
+        let s = 10i128.pow(self.scale - scale);
+        let q = self.units / s;
+        let r = self.units % s;
+        let units = if r.abs() * 2 >= s { q + r.signum() } else { q };
+        Amount::new(units, scale)
+    }
+
+    pub fn neg(&self) -> Amount {
+        Amount::new(-self.units, self.scale)
+    }
+
+    /// Amount with larger scale, `None` in case of overflow
+    pub fn rescale(&self, scale: u32) -> Option<Amount> {
+        if scale <= self.scale {
+            return Some(*self);
+        }
+        10i128
+            .checked_pow(scale - self.scale)
+            .and_then(|s| self.units.checked_mul(s))
+            .map(|units| Amount::new(units, scale))
+    }
+
+    /// Amount without trailing decimal zeros
+    pub fn normalize(&self) -> Amount {
+        let mut a = *self;
+        while a.scale > 0 && a.units % 10 == 0 {
+            a = Amount::new(a.units / 10, a.scale - 1);
+        }
+        a
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.units < 0 { "-" } else { "" };
+        let abs = self.units.unsigned_abs();
+        if self.scale == 0 {
+            write!(f, "{sign}{abs}")
+        } else {
+            let s = 10u128.pow(self.scale);
+            write!(
+                f,
+                "{sign}{}.{:0>width$}",
+                abs / s,
+                abs % s,
+                width = self.scale as usize
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AmountDist {
+    #[default]
+    Day,
+    Uniform,
+    LogNormal,
+    Pareto,
+}
+
+impl AmountDist {
+    pub const DAY: &'static str = "day";
+    pub const UNIFORM: &'static str = "uniform";
+    pub const LOG_NORMAL: &'static str = "log-normal";
+    pub const PARETO: &'static str = "pareto";
+}
+
+impl TryFrom<&str> for AmountDist {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::DAY => Ok(AmountDist::Day),
+            Self::UNIFORM => Ok(AmountDist::Uniform),
+            Self::LOG_NORMAL => Ok(AmountDist::LogNormal),
+            Self::PARETO => Ok(AmountDist::Pareto),
+            _ => Err(format!(
+                "Unknown amount distribution: {}, supported distributions are: {}, {}, {}, {}",
+                value,
+                Self::DAY,
+                Self::UNIFORM,
+                Self::LOG_NORMAL,
+                Self::PARETO
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for AmountDist {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AmountDist::Day => write!(f, "{}", Self::DAY),
+            AmountDist::Uniform => write!(f, "{}", Self::UNIFORM),
+            AmountDist::LogNormal => write!(f, "{}", Self::LOG_NORMAL),
+            AmountDist::Pareto => write!(f, "{}", Self::PARETO),
+        }
+    }
+}
+
+/// Generator of posting amounts
+///
+/// Amounts are deterministic: the same seed and txn index will
+/// always produce the same amount.
+///
+/// With `AmountDist::Day` amount is `<day of month>.000...1`,
+/// and min and max are not used.
+#[derive(Debug, Clone)]
+pub struct AmountGen {
+    pub dist: AmountDist,
+    pub min: f64,
+    pub max: f64,
+    pub scale: u32,
+    pub negative_ratio: f64,
+    pub zero_ratio: f64,
+    pub seed: u64,
+}
+
+impl Default for AmountGen {
+    fn default() -> Self {
+        AmountGen {
+            dist: AmountDist::Day,
+            min: 1.0,
+            max: 1000.0,
+            scale: 7,
+            negative_ratio: 0.0,
+            zero_ratio: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+impl AmountGen {
+    /// PRNG stream for amounts
+    const STREAM: u64 = 1;
+    /// Shape of Pareto distribution (80-20 rule)
+    const PARETO_ALPHA: f64 = 1.16;
+
+    pub fn try_new(
+        dist: AmountDist,
+        min: f64,
+        max: f64,
+        scale: u32,
+        negative_ratio: f64,
+        zero_ratio: f64,
+        seed: u64,
+    ) -> Result<AmountGen, Box<dyn Error>> {
+        if !(min.is_finite() && max.is_finite() && 0.0 <= min && min <= max) {
+            let msg = format!("Invalid amount range: min = {min}, max = {max}");
+            return Err(msg.into());
+        }
+        if !(0.0..=1.0).contains(&negative_ratio)
+            || !(0.0..=1.0).contains(&zero_ratio)
+            || negative_ratio + zero_ratio > 1.0
+        {
+            let msg = format!(
+                "Invalid amount ratios: negative = {negative_ratio}, zero = {zero_ratio}, \
+                 ratios must be between 0 and 1, and their sum at most 1"
+            );
+            return Err(msg.into());
+        }
+        let int_digits = if dist == AmountDist::Day {
+            2
+        } else {
+            int_digits(max)
+        };
+        if int_digits + scale > MAX_DIGITS {
+            let msg = format!(
+                "Amount is too large: {int_digits} integer digits with scale {scale}, \
+                 total count of digits must be at most {MAX_DIGITS}"
+            );
+            return Err(msg.into());
+        }
+        let amount_gen = AmountGen {
+            dist,
+            min,
+            max,
+            scale,
+            negative_ratio,
+            zero_ratio,
+            seed,
+        };
+        if amount_gen.dist != AmountDist::Day {
+            let (lo, hi) = amount_gen.bounds();
+            if lo > hi {
+                let msg = format!(
+                    "Invalid amount range: there are no amounts with scale {scale} \
+                     between min = {min} and max = {max}"
+                );
+                return Err(msg.into());
+            }
+        }
+        Ok(amount_gen)
+    }
+
+    /// Check that amounts and their sums are within precision of the flavor
+    ///
+    /// The largest sum is the sum of `txns` largest amounts (e.g. balance of an account,
+    /// or total of a balance report), and it must have the same limits as amounts.
+    pub fn for_flavor(
+        self,
+        flavor: &JournalFlavor,
+        txns: u32,
+    ) -> Result<AmountGen, Box<dyn Error>> {
+        let (max_units, max_scale) = limits(flavor);
+        if self.scale > max_scale {
+            let msg = format!(
+                "Scale of amounts is too large for {flavor}: {}, maximum scale is {max_scale}",
+                self.scale
+            );
+            return Err(msg.into());
+        }
+        let largest = self.largest_units();
+        let sum = largest.checked_mul(i128::from(txns.max(1)));
+        if sum.is_none_or(|sum| sum > max_units) {
+            let msg = format!(
+                "Amounts are too large for {flavor}: sum of {txns} amounts up to {} \
+                 must be at most {}",
+                Amount::new(largest, self.scale),
+                Amount::new(max_units, self.scale)
+            );
+            return Err(msg.into());
+        }
+        Ok(self)
+    }
+
+    /// Units of the largest amount
+    fn largest_units(&self) -> i128 {
+        match self.dist {
+            AmountDist::Day => {
+                let last = if self.scale == 0 { 0 } else { 1 };
+                31 * 10i128.pow(self.scale) + last
+            }
+            _ => self.bounds().1,
+        }
+    }
+
+    /// Range of units with scale, which are inside of [min, max]
+    fn bounds(&self) -> (i128, i128) {
+        (
+            units_of(self.min, self.scale, true),
+            units_of(self.max, self.scale, false),
+        )
+    }
+
+    /// Amount of txn
+    pub fn amount(&self, ts: &Zoned, index: u32) -> Amount {
+        let mut rng = Prng::new(self.seed, Self::STREAM, u64::from(index));
+
+        let sign = if self.negative_ratio > 0.0 || self.zero_ratio > 0.0 {
+            let r = rng.next_f64();
+            if r < self.zero_ratio {
+                return Amount::new(0, self.scale);
+            }
+            if r < self.zero_ratio + self.negative_ratio {
+                -1
+            } else {
+                1
+            }
+        } else {
+            1
+        };
+
+        let s = 10i128.pow(self.scale);
+        let units = match self.dist {
+            AmountDist::Day => {
+                let last = if self.scale == 0 { 0 } else { 1 };
+                i128::from(ts.day()) * s + last
+            }
+            _ => {
+                let value = self.value(&mut rng);
+                let scaled = value * s as f64;
+                let units = if scaled < F64_EXACT {
+                    scaled.round() as i128
+                } else {
+                    // decimals are beyond precision of f64, so they are random
+                    let frac = if self.scale == 0 {
+                        0
+                    } else {
+                        rng.next_below(s as u128) as i128
+                    };
+                    value.trunc() as i128 * s + frac
+                };
+                // keep amount inside of range, also after rounding and with random decimals
+                let (lo, hi) = self.bounds();
+                units.clamp(lo, hi)
+            }
+        };
+        Amount::new(sign * units, self.scale)
+    }
+
+    /// Random value based on distribution, in range [min, max]
+    fn value(&self, rng: &mut Prng) -> f64 {
+        let v = match self.dist {
+            AmountDist::Day | AmountDist::Uniform => {
+                self.min + rng.next_f64() * (self.max - self.min)
+            }
+            AmountDist::LogNormal => {
+                let lo = self.min.max(1.0).ln();
+                let hi = self.max.max(1.0).ln();
+                let mu = (lo + hi) / 2.0;
+                let sigma = (hi - lo) / 6.0;
+                // Box-Muller transform
+                let u1 = 1.0 - rng.next_f64();
+                let u2 = rng.next_f64();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (mu + sigma * z).exp()
+            }
+            AmountDist::Pareto => {
+                let lo = self.min.max(1.0);
+                let u = 1.0 - rng.next_f64();
+                lo / u.powf(1.0 / Self::PARETO_ALPHA)
+            }
+        };
+        v.clamp(self.min, self.max)
+    }
+}
+
+/// Count of integer digits of non-negative `value`, `0.x` has no integer digits
+fn int_digits(value: f64) -> u32 {
+    if value < 1.0 {
+        0
+    } else {
+        value.log10().floor() as u32 + 1
+    }
+}
+
+/// Units of non-negative `value` with scale, rounded up or down to the scale
+///
+/// Value is converted via its shortest decimal representation, so that
+/// e.g. `0.1` is exactly one tenth.
+fn units_of(value: f64, scale: u32, up: bool) -> i128 {
+    let text = format!("{value}");
+    let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+    let (frac, rest) = frac.split_at(frac.len().min(scale as usize));
+    let digits = format!("{int}{frac:0<width$}", width = scale as usize);
+    let units: i128 = digits.parse().expect("IE: amount is not a number");
+    if up && rest.bytes().any(|b| b != b'0') {
+        units + 1
+    } else {
+        units
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::{Amount, AmountDist, AmountGen, limits, units_of};
+    use crate::setup::JournalFlavor;
+    use jiff::Timestamp;
+    use jiff::tz::TimeZone;
+
+    #[test]
+    fn test_amount_display() {
+        assert_eq!(Amount::new(310_000_001, 7).to_string(), "31.0000001");
+        assert_eq!(Amount::new(-10_000_001, 7).to_string(), "-1.0000001");
+        assert_eq!(Amount::new(-1, 2).to_string(), "-0.01");
+        assert_eq!(Amount::new(123, 0).to_string(), "123");
+        assert_eq!(
+            Amount::new(1, 28).to_string(),
+            "0.0000000000000000000000000001"
+        );
+        assert_eq!(
+            Amount::from_int(1000, 7),
+            Some(Amount::new(10_000_000_000, 7))
+        );
+        assert_eq!(Amount::from_int(1000, 38), None);
+    }
+
+    #[test]
+    fn test_amount_arithmetic() {
+        let a = Amount::new(310_000_001, 7);
+        let rate = Amount::new(912_345, 6);
+        assert_eq!(
+            a.checked_mul(&rate).unwrap(/*:test:*/).to_string(),
+            "28.2826950912345"
+        );
+        assert_eq!(
+            a.checked_mul(&rate).unwrap(/*:test:*/).round(2).to_string(),
+            "28.28"
+        );
+        assert_eq!(Amount::new(-125, 3).round(2).to_string(), "-0.13");
+        assert_eq!(Amount::new(124, 3).round(2).to_string(), "0.12");
+        assert_eq!(Amount::new(124, 3).round(4).to_string(), "0.124");
+        assert_eq!(Amount::new(i128::MAX, 0).checked_mul(&rate), None);
+        assert_eq!(
+            Amount::new(-125, 2).rescale(4),
+            Some(Amount::new(-12500, 4))
+        );
+        assert_eq!(Amount::new(i128::MAX, 0).rescale(1), None);
+        assert_eq!(Amount::new(-12500, 4).normalize(), Amount::new(-125, 2));
+        assert_eq!(Amount::new(0, 4).normalize(), Amount::new(0, 0));
+    }
+
+    #[test]
+    fn test_day_amount() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+
+        let amount_gen = AmountGen::default();
+        assert_eq!(amount_gen.amount(&ts, 1).to_string(), "31.0000001");
+
+        let amount_gen = AmountGen {
+            scale: 0,
+            ..AmountGen::default()
+        };
+        assert_eq!(amount_gen.amount(&ts, 1).to_string(), "31");
+    }
+
+    #[test]
+    fn test_distributions() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+
+        for dist in [
+            AmountDist::Uniform,
+            AmountDist::LogNormal,
+            AmountDist::Pareto,
+        ] {
+            let amount_gen =
+                AmountGen::try_new(dist, 10.0, 1e20, 4, 0.1, 0.1, 1).unwrap(/*:test:*/);
+            let mut negatives = 0;
+            let mut zeros = 0;
+            for idx in 1..=1000 {
+                let a = amount_gen.amount(&ts, idx);
+                assert_eq!(a, amount_gen.amount(&ts, idx));
+                assert_eq!(a.scale, 4);
+                if a.units == 0 {
+                    zeros += 1;
+                } else {
+                    let abs = a.units.unsigned_abs();
+                    assert!((100_000..=10u128.pow(24)).contains(&abs));
+                }
+                if a.units < 0 {
+                    negatives += 1;
+                }
+            }
+            assert!((50..150).contains(&negatives));
+            assert!((50..150).contains(&zeros));
+        }
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(AmountGen::try_new(AmountDist::Uniform, 10.0, 1.0, 2, 0.0, 0.0, 0).is_err());
+        assert!(AmountGen::try_new(AmountDist::Uniform, 1.0, 10.0, 2, 0.6, 0.6, 0).is_err());
+        assert!(AmountGen::try_new(AmountDist::Uniform, 1.0, 1e12, 28, 0.0, 0.0, 0).is_err());
+        assert!(AmountGen::try_new(AmountDist::Uniform, 1.0, 1e9, 28, 0.0, 0.0, 0).is_ok());
+        // there is no amount with scale 1 between 0.11 and 0.19
+        assert!(AmountGen::try_new(AmountDist::Uniform, 0.11, 0.19, 1, 0.0, 0.0, 0).is_err());
+
+        let large = AmountGen::try_new(AmountDist::Uniform, 1.0, 1e9, 28, 0.0, 0.0, 0);
+        let large = large.unwrap(/*:test:*/);
+        assert!(large.clone().for_flavor(&JournalFlavor::Ledger, 1).is_ok());
+        assert!(
+            large
+                .clone()
+                .for_flavor(&JournalFlavor::Tackler, 1)
+                .is_err()
+        );
+        assert!(large.for_flavor(&JournalFlavor::Beancount, 1).is_err());
+        let day = AmountGen::default();
+        assert!(
+            day.clone()
+                .for_flavor(&JournalFlavor::Tackler, 1_000_000)
+                .is_ok()
+        );
+        let day = AmountGen { scale: 20, ..day };
+        assert!(
+            day.clone()
+                .for_flavor(&JournalFlavor::Tackler, 10_000)
+                .is_ok()
+        );
+        assert!(
+            day.for_flavor(&JournalFlavor::Tackler, 100_000_000)
+                .is_err()
+        );
+
+        // 0.x has only decimals, and the largest amount of tackler has 29 digits
+        let small = AmountGen::try_new(AmountDist::Uniform, 0.1, 0.9, 28, 0.0, 0.0, 0);
+        let small = small.unwrap(/*:test:*/);
+        assert!(small.clone().for_flavor(&JournalFlavor::Tackler, 1).is_ok());
+        assert!(
+            small
+                .clone()
+                .for_flavor(&JournalFlavor::Tackler, 100)
+                .is_err()
+        );
+        assert!(small.for_flavor(&JournalFlavor::Beancount, 1).is_ok());
+        assert!(12345678901234567890123456789 <= limits(&JournalFlavor::Tackler).0);
+        let scale = AmountGen::try_new(AmountDist::Uniform, 0.0, 1e-9, 29, 0.0, 0.0, 0);
+        let scale = scale.unwrap(/*:test:*/);
+        assert!(
+            scale
+                .clone()
+                .for_flavor(&JournalFlavor::Tackler, 1)
+                .is_err()
+        );
+        assert!(scale.for_flavor(&JournalFlavor::Beancount, 1).is_ok());
+    }
+
+    #[test]
+    fn test_range_boundaries() {
+        assert_eq!(units_of(0.25, 1, true), 3);
+        assert_eq!(units_of(0.25, 1, false), 2);
+        assert_eq!(units_of(0.2, 3, true), 200);
+        assert_eq!(units_of(1e20, 0, false), 10i128.pow(20));
+
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+        for (min, max, scale) in [(0.25, 0.75, 1), (0.123, 0.129, 3), (1.5, 2.5, 0)] {
+            for dist in [
+                AmountDist::Uniform,
+                AmountDist::LogNormal,
+                AmountDist::Pareto,
+            ] {
+                let amount_gen =
+                    AmountGen::try_new(dist, min, max, scale, 0.0, 0.0, 1).unwrap(/*:test:*/);
+                for idx in 1..=1000 {
+                    let a = amount_gen.amount(&ts, idx);
+                    let value: f64 = a.to_string().parse().unwrap(/*:test:*/);
+                    assert!((min..=max).contains(&value), "{min} <= {a} <= {max}");
+                }
+            }
+        }
+    }
+}
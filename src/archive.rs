@@ -0,0 +1,237 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Archives and compressed journals with deterministic metadata
+//!
+//! All entries have the same metadata (mtime is zero, owner is root,
+//! there are no user or group names, and permissions are `0644` for files
+//! and `0755` for directories), so the same set produces always
+//! the same archive, bit by bit.
+
+use crate::setup::Archive;
+use flate2::{Compression, GzBuilder};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path};
+use std::rc::Rc;
+use tar::{EntryType, Header};
+
+const FILE_MODE: u32 = 0o644;
+const DIR_MODE: u32 = 0o755;
+
+/// Writer of compressed (or plain) output file
+pub enum Compressor {
+    Plain(BufWriter<File>),
+    Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Compressor {
+    /// Create output file with compression of the archive format
+    pub fn create(path: &Path, archive: &Archive) -> Result<Compressor, Box<dyn Error>> {
+        let f = File::create_new(path).map_err(|err| format!("{}: '{}'", err, path.display()))?;
+        let bw = BufWriter::new(f);
+        Ok(match archive {
+            Archive::Tar => Compressor::Plain(bw),
+            Archive::TarGz | Archive::Gz => Compressor::Gzip(
+                // mtime and OS are fixed, and there is no file name
+                GzBuilder::new()
+                    .mtime(0)
+                    .operating_system(255)
+                    .write(bw, Compression::default()),
+            ),
+            Archive::TarZst => Compressor::Zstd(zstd::Encoder::new(bw, 0)?),
+        })
+    }
+
+    /// Finish compression and flush the output file
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        let mut bw = match self {
+            Compressor::Plain(bw) => bw,
+            Compressor::Gzip(gz) => gz.finish()?,
+            Compressor::Zstd(zst) => zst.finish()?,
+        };
+        Ok(bw.flush()?)
+    }
+}
+
+impl Write for Compressor {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Compressor::Plain(w) => w.write(buf),
+            Compressor::Gzip(w) => w.write(buf),
+            Compressor::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Compressor::Plain(w) => w.flush(),
+            Compressor::Gzip(w) => w.flush(),
+            Compressor::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Writer, which is shared with its owner (e.g. compressed journal)
+pub struct Shared<W: Write>(pub Rc<RefCell<W>>);
+
+impl<W: Write> Write for Shared<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// Writer of ustar archive
+///
+/// Entries are written in the order they are appended, and
+/// parent directories of a file are written before the file.
+pub struct TarWriter<W: Write> {
+    builder: tar::Builder<W>,
+    dirs: BTreeSet<String>,
+}
+
+impl<W: Write> TarWriter<W> {
+    pub fn new(out: W) -> TarWriter<W> {
+        TarWriter {
+            builder: tar::Builder::new(out),
+            dirs: BTreeSet::new(),
+        }
+    }
+
+    /// Append file, `path` is relative path of the file inside the archive
+    pub fn append(&mut self, path: &Path, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let mut name = String::new();
+        for c in path.components() {
+            let Component::Normal(c) = c else {
+                let msg = format!("Invalid path for archive: '{}'", path.display());
+                return Err(msg.into());
+            };
+            if !name.is_empty() {
+                let dir = format!("{name}/");
+                if !self.dirs.contains(&dir) {
+                    let mut h = header(0, EntryType::Directory, DIR_MODE);
+                    self.builder.append_data(&mut h, &dir, io::empty())?;
+                    self.dirs.insert(dir);
+                }
+                name.push('/');
+            }
+            name.push_str(&c.to_string_lossy());
+        }
+        let mut h = header(data.len() as u64, EntryType::Regular, FILE_MODE);
+        Ok(self.builder.append_data(&mut h, &name, data)?)
+    }
+
+    /// Write end of archive, and return the underlying writer
+    pub fn finish(self) -> Result<W, Box<dyn Error>> {
+        Ok(self.builder.into_inner()?)
+    }
+}
+
+/// Header of ustar entry, path and checksum are set when the entry is appended
+fn header(size: u64, kind: EntryType, mode: u32) -> Header {
+    let mut h = Header::new_ustar();
+    h.set_size(size);
+    h.set_entry_type(kind);
+    h.set_mode(mode);
+    h.set_uid(0);
+    h.set_gid(0);
+    h.set_mtime(0);
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::archive::TarWriter;
+    use std::io::Read;
+    use std::path::Path;
+
+    /// Entries of archive: path, mode, size and data
+    fn entries(bytes: &[u8]) -> Vec<(String, u32, u64, String)> {
+        let mut archive = tar::Archive::new(bytes);
+        let mut entries = Vec::new();
+        for e in archive.entries().unwrap(/*:test:*/) {
+            let mut e = e.unwrap(/*:test:*/);
+            let h = e.header();
+            assert_eq!(h.mtime().unwrap(/*:test:*/), 0);
+            assert_eq!(h.uid().unwrap(/*:test:*/), 0);
+            assert_eq!(h.gid().unwrap(/*:test:*/), 0);
+            assert_eq!(h.username().unwrap(/*:test:*/), Some(""));
+            let (mode, size) = (h.mode().unwrap(/*:test:*/), h.size().unwrap(/*:test:*/));
+            let path = String::from_utf8_lossy(&e.path_bytes()).to_string();
+            let mut data = String::new();
+            e.read_to_string(&mut data).unwrap(/*:test:*/);
+            entries.push((path, mode, size, data));
+        }
+        entries
+    }
+
+    #[test]
+    fn test_tar() {
+        let mut tar = TarWriter::new(Vec::new());
+        tar.append(Path::new("set/txns/a.txn"), b"txn-a\n").unwrap(/*:test:*/);
+        tar.append(Path::new("set/txns/b.txn"), b"txn-b\n").unwrap(/*:test:*/);
+        tar.append(Path::new("set.toml"), b"").unwrap(/*:test:*/);
+        let bytes = tar.finish().unwrap(/*:test:*/);
+
+        // dirs: set/, set/txns/, files: a, b, set.toml, data: a, b, end: two blocks
+        assert_eq!(bytes.len(), 9 * 512);
+        assert_eq!(&bytes[257..263], b"ustar\0");
+        assert_eq!(&bytes[3 * 512..3 * 512 + 6], b"txn-a\n");
+        assert_eq!(
+            entries(&bytes),
+            vec![
+                ("set/".to_string(), 0o755, 0, String::new()),
+                ("set/txns/".to_string(), 0o755, 0, String::new()),
+                (
+                    "set/txns/a.txn".to_string(),
+                    0o644,
+                    6,
+                    "txn-a\n".to_string()
+                ),
+                (
+                    "set/txns/b.txn".to_string(),
+                    0o644,
+                    6,
+                    "txn-b\n".to_string()
+                ),
+                ("set.toml".to_string(), 0o644, 0, String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tar_long_name() {
+        let (d, e, f) = ("d".repeat(60), "e".repeat(60), "f".repeat(90));
+        let long = format!("set/{d}/{e}/{f}.txn");
+        let name = "f".repeat(101);
+        let mut tar = TarWriter::new(Vec::new());
+        tar.append(Path::new(&long), b"a").unwrap(/*:test:*/);
+        tar.append(Path::new(&name), b"b").unwrap(/*:test:*/);
+        assert!(tar.append(Path::new("../x.txn"), b"").is_err());
+        let bytes = tar.finish().unwrap(/*:test:*/);
+
+        // long path is split to prefix and name of ustar header
+        assert_eq!(&bytes[3 * 512 + 345..3 * 512 + 349], b"set/");
+        let paths: Vec<_> = entries(&bytes).into_iter().map(|e| e.0).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "set/".to_string(),
+                format!("set/{d}/"),
+                format!("set/{d}/{e}/"),
+                long,
+                name
+            ]
+        );
+    }
+}
@@ -0,0 +1,426 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::setup::{JournalFlavor, JournalSetup};
+use std::error::Error;
+use std::fmt::Write;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+pub mod compare;
+pub mod results;
+
+/// PTA tool and its command line
+///
+/// Command is a template, which could have following variables:
+/// - `{config}`:  path of tackler config
+/// - `{journal}`: path of journal of the flavor
+/// - `{set}`:     set size, e.g. `1e3`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tool {
+    pub name: String,
+    pub flavor: JournalFlavor,
+    pub command: String,
+}
+
+impl Tool {
+    /// Default tools, the same as used by `benchmark/justfile`
+    pub fn defaults() -> Vec<Tool> {
+        [
+            (
+                "tackler",
+                JournalFlavor::Tackler,
+                "tackler --config {config}",
+            ),
+            ("ledger", JournalFlavor::Ledger, "ledger -f {journal} bal"),
+            ("hledger", JournalFlavor::Ledger, "hledger -f {journal} bal"),
+            (
+                "rledger",
+                JournalFlavor::Beancount,
+                "rledger report {journal} balances",
+            ),
+            (
+                "bean-query",
+                JournalFlavor::Beancount,
+                "bean-query {journal} 'balances from year = 2024'",
+            ),
+        ]
+        .into_iter()
+        .map(|(name, flavor, command)| Tool {
+            name: name.to_string(),
+            flavor,
+            command: command.to_string(),
+        })
+        .collect()
+    }
+
+    /// Command line with template variables expanded
+    pub fn args(&self, setup: &mut JournalSetup) -> Result<Vec<String>, Box<dyn Error>> {
+        let config = setup.config_path().1;
+        let journal = setup.journal_path(&jiff::Zoned::now(), 0).1;
+        let words = split_command(&self.command)?;
+        Ok(words
+            .iter()
+            .map(|w| {
+                w.replace("{config}", &config.to_string_lossy())
+                    .replace("{journal}", &journal.to_string_lossy())
+                    .replace("{set}", setup.txn_set.str())
+            })
+            .collect())
+    }
+}
+
+impl TryFrom<&str> for Tool {
+    type Error = Box<dyn Error>;
+    /// Tool definition: `NAME:FLAVOR:COMMAND`
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut parts = value.splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(name), Some(flavor), Some(command))
+                if !name.is_empty() && !command.trim().is_empty() =>
+            {
+                Ok(Tool {
+                    name: name.to_string(),
+                    flavor: JournalFlavor::try_from(flavor)?,
+                    command: command.trim().to_string(),
+                })
+            }
+            _ => Err(
+                format!("Invalid tool: '{value}', expected format is: NAME:FLAVOR:COMMAND").into(),
+            ),
+        }
+    }
+}
+
+/// Split command line into words, words could be quoted with `'` or `"`
+pub fn split_command(command: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_default().push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_default().push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("Unterminated quote in command: '{command}'").into());
+    }
+    words.extend(word.take());
+    if words.is_empty() {
+        return Err("Empty command".into());
+    }
+    Ok(words)
+}
+
+/// Measurement of single run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    /// Wall time, in seconds
+    pub wall: f64,
+    /// User and system CPU time, in seconds
+    pub cpu: Option<f64>,
+    /// Maximum resident set size, in kilobytes
+    pub max_rss: Option<u64>,
+    pub success: bool,
+}
+
+/// Version of tool, the first line of output of `PROGRAM --version`
+///
+/// Version is missing, if the tool is not installed or it fails.
+pub fn version(program: &str) -> Option<String> {
+    let output = Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let first_line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+    first_line(&output.stdout).or_else(|| first_line(&output.stderr))
+}
+
+/// Run command and measure its resource usage
+///
+/// Output of command is discarded. CPU time and maximum RSS are
+/// available only on unix. On Linux, maximum RSS includes RSS of
+/// the generator at the time of fork.
+pub fn measure(args: &[String]) -> std::io::Result<Run> {
+    let start = Instant::now();
+    let child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let (success, cpu, max_rss) = wait(child)?;
+    Ok(Run {
+        wall: start.elapsed().as_secs_f64(),
+        cpu,
+        max_rss,
+        success,
+    })
+}
+
+#[cfg(unix)]
+fn wait(child: std::process::Child) -> std::io::Result<(bool, Option<f64>, Option<u64>)> {
+    let pid = child.id() as libc::pid_t;
+    let mut status = 0;
+    // SAFETY: rusage is plain old data, and all-zero value is valid for it
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: pid is our own child, which has not been waited,
+    // and pointers are valid for the duration of the call
+    if unsafe { libc::wait4(pid, &mut status, 0, &mut usage) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let secs = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1e6;
+    let cpu = secs(usage.ru_utime) + secs(usage.ru_stime);
+    // ru_maxrss is in bytes on macOS, and in kilobytes elsewhere
+    let max_rss = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as u64 / 1024
+    } else {
+        usage.ru_maxrss as u64
+    };
+    let success = libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0;
+    Ok((success, Some(cpu), Some(max_rss)))
+}
+
+#[cfg(not(unix))]
+fn wait(mut child: std::process::Child) -> std::io::Result<(bool, Option<f64>, Option<u64>)> {
+    Ok((child.wait()?.success(), None, None))
+}
+
+/// Status of benchmarked tool
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Ok,
+    /// Some of the runs failed
+    Failed,
+    /// Tool is not installed
+    NotFound,
+}
+
+impl Status {
+    pub const OK: &'static str = "ok";
+    pub const FAILED: &'static str = "failed";
+    pub const NOT_FOUND: &'static str = "not-found";
+
+    pub fn str(&self) -> &'static str {
+        match self {
+            Status::Ok => Self::OK,
+            Status::Failed => Self::FAILED,
+            Status::NotFound => Self::NOT_FOUND,
+        }
+    }
+}
+
+impl TryFrom<&str> for Status {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::OK => Ok(Status::Ok),
+            Self::FAILED => Ok(Status::Failed),
+            Self::NOT_FOUND => Ok(Status::NotFound),
+            _ => Err(format!(
+                "Unknown status: {}, supported statuses are: {}, {}, {}",
+                value,
+                Self::OK,
+                Self::FAILED,
+                Self::NOT_FOUND
+            )
+            .into()),
+        }
+    }
+}
+
+/// Runs of tool with a set
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub set: String,
+    pub tool: String,
+    /// Output of `--version` of the tool
+    pub version: Option<String>,
+    pub flavor: JournalFlavor,
+    pub command: String,
+    pub status: Status,
+    pub runs: Vec<Run>,
+}
+
+impl BenchResult {
+    /// Minimum, median and maximum of wall times
+    pub fn wall_stats(&self) -> Option<(f64, f64, f64)> {
+        let mut walls: Vec<f64> = self.runs.iter().map(|r| r.wall).collect();
+        walls.sort_by(f64::total_cmp);
+        let median = median(&walls)?;
+        Some((walls[0], median, walls[walls.len() - 1]))
+    }
+
+    /// Maximum of RSS over runs, in kilobytes
+    pub fn max_rss(&self) -> Option<u64> {
+        self.runs.iter().filter_map(|r| r.max_rss).max()
+    }
+
+    /// CPU usage of all runs, as percentage of wall time
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let cpu: Option<f64> = self.runs.iter().map(|r| r.cpu).sum();
+        let wall: f64 = self.runs.iter().map(|r| r.wall).sum();
+        cpu.filter(|_| wall > 0.0).map(|cpu| 100.0 * cpu / wall)
+    }
+}
+
+/// Median of sorted values
+pub fn median(sorted: &[f64]) -> Option<f64> {
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+    }
+}
+
+/// Results as text table
+pub fn table(results: &[BenchResult]) -> Result<String, Box<dyn Error>> {
+    let width = results
+        .iter()
+        .map(|r| r.tool.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let mut t = String::new();
+    writeln!(
+        t,
+        "{:<5}  {:<width$}  {:>4}  {:>10}  {:>10}  {:>10}  {:>12}  {:>5}  Status",
+        "Set", "Tool", "Runs", "Wall min", "Wall med", "Wall max", "Max RSS", "CPU"
+    )?;
+    for r in results {
+        let (min, med, max) = r.wall_stats().map_or(
+            ("-".to_string(), "-".to_string(), "-".to_string()),
+            |(min, med, max)| {
+                (
+                    format!("{min:.3} s"),
+                    format!("{med:.3} s"),
+                    format!("{max:.3} s"),
+                )
+            },
+        );
+        let rss = r.max_rss().map_or("-".to_string(), |kb| format!("{kb} KB"));
+        let cpu = r
+            .cpu_percent()
+            .map_or("-".to_string(), |p| format!("{p:.0}%"));
+        writeln!(
+            t,
+            "{:<5}  {:<width$}  {:>4}  {min:>10}  {med:>10}  {max:>10}  {rss:>12}  {cpu:>5}  {}",
+            r.set,
+            r.tool,
+            r.runs.len(),
+            r.status.str()
+        )?;
+    }
+    Ok(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::benchmark::{BenchResult, Run, Status, Tool, median, split_command};
+    use crate::setup::{JournalFlavor, JournalSetup, SetSize, ShardType};
+    use std::path::Path;
+
+    #[test]
+    fn test_tool() {
+        let tool = Tool::try_from("hledger:ledger:hledger -f {journal} bal").unwrap(/*:test:*/);
+        assert_eq!(tool.name, "hledger");
+        assert_eq!(tool.flavor, JournalFlavor::Ledger);
+
+        let mut setup = JournalSetup::try_new(
+            JournalFlavor::Ledger,
+            Path::new("data/comm"),
+            SetSize::Sz1e3,
+            ShardType::Single,
+        )
+        .unwrap(/*:test:*/);
+        assert_eq!(
+            tool.args(&mut setup).unwrap(/*:test:*/),
+            vec![
+                "hledger",
+                "-f",
+                "data/comm/set-1e3-single/txns/1e3.journal",
+                "bal"
+            ]
+        );
+        assert!(Tool::try_from("hledger:ledger").is_err());
+        assert!(Tool::try_from("hledger:foo:hledger").is_err());
+        assert_eq!(Tool::defaults().len(), 5);
+    }
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(
+            split_command("bean-query {journal} 'balances from year = 2024'").unwrap(/*:test:*/),
+            vec!["bean-query", "{journal}", "balances from year = 2024"]
+        );
+        assert_eq!(
+            split_command(r#"  a "" "b c"d  "#).unwrap(/*:test:*/),
+            vec!["a", "", "b cd"]
+        );
+        assert!(split_command("a 'b").is_err());
+        assert!(split_command("  ").is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[1.0, 2.0, 4.0]), Some(2.0));
+        assert_eq!(median(&[1.0, 2.0, 4.0, 8.0]), Some(3.0));
+
+        let run = |wall, cpu, max_rss| Run {
+            wall,
+            cpu: Some(cpu),
+            max_rss: Some(max_rss),
+            success: true,
+        };
+        let result = BenchResult {
+            set: "1e3".to_string(),
+            tool: "tool \"x\"".to_string(),
+            version: None,
+            flavor: JournalFlavor::Tackler,
+            command: "tool".to_string(),
+            status: Status::Ok,
+            runs: vec![run(2.0, 1.0, 100), run(1.0, 0.5, 300), run(3.0, 1.5, 200)],
+        };
+        assert_eq!(result.wall_stats(), Some((1.0, 2.0, 3.0)));
+        assert_eq!(result.max_rss(), Some(300));
+        assert_eq!(result.cpu_percent(), Some(50.0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_measure() {
+        let run = crate::benchmark::measure(&["sh".to_string(), "-c".to_string(), "exit 0".to_string()])
+            .unwrap(/*:test:*/);
+        assert!(run.success);
+        assert!(run.max_rss.is_some_and(|kb| kb > 0));
+
+        let run = crate::benchmark::measure(&["sh".to_string(), "-c".to_string(), "exit 1".to_string()])
+            .unwrap(/*:test:*/);
+        assert!(!run.success);
+        assert!(crate::benchmark::measure(&["pta-generator-no-such-tool".to_string()]).is_err());
+
+        assert!(crate::benchmark::version("cargo").is_some_and(|v| v.starts_with("cargo ")));
+        assert_eq!(
+            crate::benchmark::version("pta-generator-no-such-tool"),
+            None
+        );
+    }
+}
@@ -0,0 +1,454 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::benchmark::results::ResultSet;
+use crate::benchmark::{BenchResult, Status, median};
+use std::error::Error;
+use std::fmt::{Display, Write};
+
+/// z-value of 95% confidence level
+const Z_95: f64 = 1.96;
+
+/// Measurement which is compared
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Metric {
+    /// Wall time, in seconds
+    #[default]
+    Wall,
+    /// CPU time, in seconds
+    Cpu,
+    /// Maximum RSS, in kilobytes
+    Rss,
+}
+
+impl Metric {
+    pub const WALL: &'static str = "wall";
+    pub const CPU: &'static str = "cpu";
+    pub const RSS: &'static str = "rss";
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Metric::Wall | Metric::Cpu => "s",
+            Metric::Rss => "KB",
+        }
+    }
+
+    fn values(&self, result: &BenchResult) -> Vec<f64> {
+        result
+            .runs
+            .iter()
+            .filter_map(|r| match self {
+                Metric::Wall => Some(r.wall),
+                Metric::Cpu => r.cpu,
+                Metric::Rss => r.max_rss.map(|kb| kb as f64),
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for Metric {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::WALL => Ok(Metric::Wall),
+            Self::CPU => Ok(Metric::Cpu),
+            Self::RSS => Ok(Metric::Rss),
+            _ => Err(format!(
+                "Unknown metric: {}, supported metrics are: {}, {}, {}",
+                value,
+                Self::WALL,
+                Self::CPU,
+                Self::RSS
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Metric::Wall => write!(f, "{}", Self::WALL),
+            Metric::Cpu => write!(f, "{}", Self::CPU),
+            Metric::Rss => write!(f, "{}", Self::RSS),
+        }
+    }
+}
+
+/// Median and median absolute deviation of runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub median: f64,
+    pub mad: f64,
+    pub n: usize,
+}
+
+impl Stats {
+    pub fn new(values: &[f64]) -> Option<Stats> {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mid = median(&sorted)?;
+        let mut deviations: Vec<f64> = sorted.iter().map(|v| (v - mid).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        Some(Stats {
+            median: mid,
+            mad: median(&deviations)?,
+            n: sorted.len(),
+        })
+    }
+
+    /// Standard error of median
+    ///
+    /// Standard deviation is estimated with MAD (1.4826 * MAD),
+    /// and standard error of median is 1.2533 * sd / sqrt(n).
+    fn std_error(&self) -> f64 {
+        1.2533 * 1.4826 * self.mad / (self.n as f64).sqrt()
+    }
+}
+
+/// Result of comparison
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// Slower (or bigger), beyond threshold and significant
+    Regression,
+    /// Faster (or smaller), beyond threshold and significant
+    Improvement,
+    NoChange,
+    /// Tool is missing or it has failed runs
+    NotAvailable,
+}
+
+impl Verdict {
+    pub const REGRESSION: &'static str = "regression";
+    pub const IMPROVEMENT: &'static str = "improvement";
+    pub const NO_CHANGE: &'static str = "no-change";
+    pub const NOT_AVAILABLE: &'static str = "n/a";
+
+    pub fn str(&self) -> &'static str {
+        match self {
+            Verdict::Regression => Self::REGRESSION,
+            Verdict::Improvement => Self::IMPROVEMENT,
+            Verdict::NoChange => Self::NO_CHANGE,
+            Verdict::NotAvailable => Self::NOT_AVAILABLE,
+        }
+    }
+}
+
+/// Change of median between baseline and candidate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub delta: f64,
+    /// Delta as percentage of baseline median
+    pub percent: f64,
+    /// 95% confidence interval of delta
+    pub ci: (f64, f64),
+}
+
+/// Comparison of tool with set
+#[derive(Debug, Clone)]
+pub struct Delta {
+    pub set: String,
+    pub tool: String,
+    pub baseline: String,
+    pub candidate: String,
+    /// Versions of the tool in baseline and candidate
+    pub versions: (Option<String>, Option<String>),
+    pub base: Option<Stats>,
+    pub cand: Option<Stats>,
+    pub change: Option<Change>,
+    pub verdict: Verdict,
+}
+
+fn stats(result: Option<&BenchResult>, metric: &Metric) -> Option<Stats> {
+    result
+        .filter(|r| r.status == Status::Ok)
+        .and_then(|r| Stats::new(&metric.values(r)))
+}
+
+/// Compare each result set with the first one (baseline)
+///
+/// Results are matched by set and tool. Change is significant,
+/// when its confidence interval doesn't include zero.
+pub fn compare(sets: &[ResultSet], metric: &Metric, threshold: f64) -> Vec<Delta> {
+    let Some((baseline, candidates)) = sets.split_first() else {
+        return Vec::new();
+    };
+    let mut deltas = Vec::new();
+    for candidate in candidates {
+        for base_result in &baseline.results {
+            let cand_result = candidate
+                .results
+                .iter()
+                .find(|r| r.set == base_result.set && r.tool == base_result.tool);
+            let base = stats(Some(base_result), metric);
+            let cand = stats(cand_result, metric);
+
+            let change = match (&base, &cand) {
+                (Some(b), Some(c)) if b.median > 0.0 => {
+                    let delta = c.median - b.median;
+                    let se = (b.std_error().powi(2) + c.std_error().powi(2)).sqrt();
+                    Some(Change {
+                        delta,
+                        percent: 100.0 * delta / b.median,
+                        ci: (delta - Z_95 * se, delta + Z_95 * se),
+                    })
+                }
+                _ => None,
+            };
+            let verdict = match &change {
+                Some(c) if c.percent > threshold && c.ci.0 > 0.0 => Verdict::Regression,
+                Some(c) if c.percent < -threshold && c.ci.1 < 0.0 => Verdict::Improvement,
+                Some(_) => Verdict::NoChange,
+                None => Verdict::NotAvailable,
+            };
+            deltas.push(Delta {
+                set: base_result.set.clone(),
+                tool: base_result.tool.clone(),
+                baseline: baseline.label.clone(),
+                candidate: candidate.label.clone(),
+                versions: (
+                    base_result.version.clone(),
+                    cand_result.and_then(|r| r.version.clone()),
+                ),
+                base,
+                cand,
+                change,
+                verdict,
+            });
+        }
+    }
+    deltas
+}
+
+/// Report rows, the first row is header
+fn rows(deltas: &[Delta], metric: &Metric) -> Vec<Vec<String>> {
+    let unit = metric.unit();
+    let num = |v: f64| match metric {
+        Metric::Rss => format!("{v:.0}"),
+        _ => format!("{v:.3}"),
+    };
+    let stat = |s: &Option<Stats>| {
+        s.as_ref().map_or(("-".to_string(), "-".to_string()), |s| {
+            (num(s.median), num(s.mad))
+        })
+    };
+    let mut rows = vec![vec![
+        "Set".to_string(),
+        "Tool".to_string(),
+        "Baseline".to_string(),
+        "Candidate".to_string(),
+        "Base version".to_string(),
+        "Cand version".to_string(),
+        format!("Base median ({unit})"),
+        format!("Base MAD ({unit})"),
+        format!("Cand median ({unit})"),
+        format!("Cand MAD ({unit})"),
+        format!("Delta ({unit})"),
+        "Delta (%)".to_string(),
+        format!("95% CI ({unit})"),
+        "Verdict".to_string(),
+    ]];
+    for d in deltas {
+        let (base_median, base_mad) = stat(&d.base);
+        let (cand_median, cand_mad) = stat(&d.cand);
+        let (delta, percent, ci) =
+            d.change
+                .as_ref()
+                .map_or(("-".to_string(), "-".to_string(), "-".to_string()), |c| {
+                    (
+                        if c.delta >= 0.0 {
+                            format!("+{}", num(c.delta))
+                        } else {
+                            num(c.delta)
+                        },
+                        format!("{:+.1}", c.percent),
+                        format!("[{}, {}]", num(c.ci.0), num(c.ci.1)),
+                    )
+                });
+        rows.push(vec![
+            d.set.clone(),
+            d.tool.clone(),
+            d.baseline.clone(),
+            d.candidate.clone(),
+            d.versions.0.clone().unwrap_or("-".to_string()),
+            d.versions.1.clone().unwrap_or("-".to_string()),
+            base_median,
+            base_mad,
+            cand_median,
+            cand_mad,
+            delta,
+            percent,
+            ci,
+            d.verdict.str().to_string(),
+        ]);
+    }
+    rows
+}
+
+/// Report as Markdown table
+pub fn markdown(deltas: &[Delta], metric: &Metric) -> Result<String, Box<dyn Error>> {
+    let rows = rows(deltas, metric);
+    let mut md = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row.iter().map(|c| c.replace('|', "\\|")).collect();
+        writeln!(md, "| {} |", cells.join(" | "))?;
+        if i == 0 {
+            writeln!(md, "|{}", "---|".repeat(row.len()))?;
+        }
+    }
+    Ok(md)
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Report as HTML document, regressions are highlighted
+pub fn html(deltas: &[Delta], metric: &Metric, title: &str) -> Result<String, Box<dyn Error>> {
+    let rows = rows(deltas, metric);
+    let mut h = String::new();
+    writeln!(h, "<!DOCTYPE html>")?;
+    writeln!(h, "<html>")?;
+    writeln!(h, "<head>")?;
+    writeln!(h, "<meta charset=\"utf-8\">")?;
+    writeln!(h, "<title>{}</title>", html_escape(title))?;
+    writeln!(
+        h,
+        "<style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #999; padding: 2px 6px; }} \
+         .regression {{ background: #fcc; }} .improvement {{ background: #cfc; }}</style>"
+    )?;
+    writeln!(h, "</head>")?;
+    writeln!(h, "<body>")?;
+    writeln!(h, "<h1>{}</h1>", html_escape(title))?;
+    writeln!(h, "<table>")?;
+    for (i, row) in rows.iter().enumerate() {
+        let (tag, class) = match i {
+            0 => ("th", ""),
+            _ => match deltas[i - 1].verdict {
+                Verdict::Regression => ("td", " class=\"regression\""),
+                Verdict::Improvement => ("td", " class=\"improvement\""),
+                _ => ("td", ""),
+            },
+        };
+        let cells: Vec<String> = row
+            .iter()
+            .map(|c| format!("<{tag}>{}</{tag}>", html_escape(c)))
+            .collect();
+        writeln!(h, "<tr{class}>{}</tr>", cells.join(""))?;
+    }
+    writeln!(h, "</table>")?;
+    writeln!(h, "</body>")?;
+    writeln!(h, "</html>")?;
+    Ok(h)
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Report as CSV
+pub fn csv(deltas: &[Delta], metric: &Metric) -> Result<String, Box<dyn Error>> {
+    let mut c = String::new();
+    for row in rows(deltas, metric) {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        writeln!(c, "{}", fields.join(","))?;
+    }
+    Ok(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::benchmark::compare::{Metric, Stats, Verdict, compare, csv, html, markdown};
+    use crate::benchmark::results::{Meta, ResultSet};
+    use crate::benchmark::{BenchResult, Run, Status};
+    use crate::setup::JournalFlavor;
+
+    fn result_set(label: &str, tool: &str, walls: &[f64], status: Status) -> ResultSet {
+        ResultSet {
+            label: label.to_string(),
+            meta: Meta::default(),
+            results: vec![BenchResult {
+                set: "1e3".to_string(),
+                tool: tool.to_string(),
+                version: (status != Status::NotFound).then(|| format!("{tool} 1.0")),
+                flavor: JournalFlavor::Ledger,
+                command: tool.to_string(),
+                status,
+                runs: walls
+                    .iter()
+                    .map(|w| Run {
+                        wall: *w,
+                        cpu: None,
+                        max_rss: Some(1000),
+                        success: true,
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let s = Stats::new(&[1.0, 3.0, 2.0, 10.0, 2.5]).unwrap(/*:test:*/);
+        assert_eq!(s.median, 2.5);
+        assert_eq!(s.mad, 0.5);
+        assert_eq!(s.n, 5);
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let base = result_set("base", "a,b", &[1.0, 1.01, 0.99, 1.02, 0.98], Status::Ok);
+        let slow = result_set("slow", "a,b", &[1.5, 1.51, 1.49, 1.52, 1.48], Status::Ok);
+        let fast = result_set("fast", "a,b", &[0.5, 0.51, 0.49, 0.52, 0.48], Status::Ok);
+        let same = result_set("same", "a,b", &[1.0, 1.2, 0.8, 1.1, 0.9], Status::Ok);
+        let gone = result_set("gone", "a,b", &[], Status::NotFound);
+        let noisy = result_set("noisy", "a,b", &[1.2, 2.0, 0.6, 1.7, 0.7], Status::Ok);
+
+        let sets = [base, slow, fast, same, gone, noisy];
+        let deltas = compare(&sets, &Metric::Wall, 10.0);
+        let verdicts: Vec<&Verdict> = deltas.iter().map(|d| &d.verdict).collect();
+        assert_eq!(
+            verdicts,
+            vec![
+                &Verdict::Regression,
+                &Verdict::Improvement,
+                &Verdict::NoChange,
+                &Verdict::NotAvailable,
+                &Verdict::NoChange,
+            ]
+        );
+        let change = deltas[0].change.as_ref().unwrap(/*:test:*/);
+        assert!((change.percent - 50.0).abs() < 1e-9);
+        assert!(change.ci.0 < 0.5 && 0.5 < change.ci.1);
+
+        let md = markdown(&deltas, &Metric::Wall).unwrap(/*:test:*/);
+        assert!(md.starts_with("| Set | Tool | Baseline |"));
+        assert!(md.contains("| 1e3 | a,b | base | slow | a,b 1.0 | a,b 1.0 | 1.000 |"));
+        assert!(md.contains("| 1e3 | a,b | base | gone | a,b 1.0 | - | 1.000 |"));
+        assert!(md.contains("| +0.500 | +50.0 |"));
+
+        let c = csv(&deltas, &Metric::Wall).unwrap(/*:test:*/);
+        assert!(c.contains(
+            "1e3,\"a,b\",base,slow,\"a,b 1.0\",\"a,b 1.0\",1.000,0.010,1.500,0.010,+0.500,+50.0,"
+        ));
+
+        let rss = compare(&sets[..2], &Metric::Rss, 10.0);
+        let c = csv(&rss, &Metric::Rss).unwrap(/*:test:*/);
+        assert!(c.contains(
+            "1e3,\"a,b\",base,slow,\"a,b 1.0\",\"a,b 1.0\",1000,0,1000,0,+0,+0.0,\"[0, 0]\",no-change\n"
+        ));
+
+        let h = html(&deltas, &Metric::Wall, "a < b").unwrap(/*:test:*/);
+        assert!(h.contains("<title>a &lt; b</title>"));
+        assert!(h.contains("<tr class=\"regression\">"));
+    }
+}
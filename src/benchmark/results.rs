@@ -0,0 +1,221 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::benchmark::{BenchResult, Run, Status};
+use crate::setup::JournalFlavor;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+/// Metadata of one `bench` run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Meta {
+    /// Name and version of generator
+    pub generator: String,
+    pub timestamp: String,
+    pub os: String,
+    pub arch: String,
+    /// Count of runs per tool and set
+    pub runs: u32,
+}
+
+/// Benchmark results of one `bench` run
+#[derive(Debug, Clone)]
+pub struct ResultSet {
+    /// Name of the result file, without extension
+    pub label: String,
+    pub meta: Meta,
+    pub results: Vec<BenchResult>,
+}
+
+/// Layout of results JSON
+#[derive(Serialize, Deserialize)]
+struct ResultsJson {
+    #[serde(flatten)]
+    meta: Meta,
+    results: Vec<ResultJson>,
+}
+
+/// Runs of tool with a set, with per-run measurements
+#[derive(Serialize, Deserialize)]
+struct ResultJson {
+    set: String,
+    tool: String,
+    /// Output of `--version` of the tool
+    #[serde(default)]
+    version: Option<String>,
+    flavor: String,
+    command: String,
+    status: String,
+    wall_s: Vec<f64>,
+    cpu_s: Vec<Option<f64>>,
+    max_rss_kb: Vec<Option<u64>>,
+}
+
+impl From<&BenchResult> for ResultJson {
+    fn from(r: &BenchResult) -> Self {
+        ResultJson {
+            set: r.set.clone(),
+            tool: r.tool.clone(),
+            version: r.version.clone(),
+            flavor: r.flavor.to_string(),
+            command: r.command.clone(),
+            status: r.status.str().to_string(),
+            wall_s: r.runs.iter().map(|run| run.wall).collect(),
+            cpu_s: r.runs.iter().map(|run| run.cpu).collect(),
+            max_rss_kb: r.runs.iter().map(|run| run.max_rss).collect(),
+        }
+    }
+}
+
+impl TryFrom<ResultJson> for BenchResult {
+    type Error = Box<dyn Error>;
+    fn try_from(r: ResultJson) -> Result<Self, Self::Error> {
+        if r.cpu_s.len() != r.wall_s.len() || r.max_rss_kb.len() != r.wall_s.len() {
+            return Err("counts of per-run measurements differ".into());
+        }
+        let status = Status::try_from(r.status.as_str())?;
+        let runs = r
+            .wall_s
+            .iter()
+            .zip(r.cpu_s.iter().zip(r.max_rss_kb.iter()))
+            .map(|(wall, (cpu, max_rss))| Run {
+                wall: *wall,
+                cpu: *cpu,
+                max_rss: *max_rss,
+                success: status != Status::Failed,
+            })
+            .collect();
+        Ok(BenchResult {
+            set: r.set,
+            tool: r.tool,
+            version: r.version,
+            flavor: JournalFlavor::try_from(r.flavor.as_str())?,
+            command: r.command,
+            status,
+            runs,
+        })
+    }
+}
+
+/// Results as JSON, with per-run measurements
+pub fn to_json(meta: &Meta, results: &[BenchResult]) -> Result<String, Box<dyn Error>> {
+    let json = ResultsJson {
+        meta: meta.clone(),
+        results: results.iter().map(ResultJson::from).collect(),
+    };
+    Ok(serde_json::to_string_pretty(&json)? + "\n")
+}
+
+/// Read results JSON, which is written by `bench`
+pub fn read(path: &Path) -> Result<ResultSet, Box<dyn Error>> {
+    let label = path.file_stem().map_or(path.display().to_string(), |s| {
+        s.to_string_lossy().to_string()
+    });
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read results '{}': {err}", path.display()))?;
+    parse(label, &text).map_err(|err| format!("Invalid results '{}': {err}", path.display()).into())
+}
+
+/// Parse results JSON
+pub fn parse(label: String, text: &str) -> Result<ResultSet, Box<dyn Error>> {
+    let json: ResultsJson = serde_json::from_str(text)?;
+    Ok(ResultSet {
+        label,
+        meta: json.meta,
+        results: json
+            .results
+            .into_iter()
+            .map(BenchResult::try_from)
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::benchmark::results::{Meta, parse, to_json};
+    use crate::benchmark::{BenchResult, Run, Status};
+    use crate::setup::JournalFlavor;
+
+    #[test]
+    fn test_roundtrip() {
+        let meta = Meta {
+            generator: "pta-generator 1.0".to_string(),
+            os: "linux".to_string(),
+            runs: 2,
+            ..Meta::default()
+        };
+        let result = BenchResult {
+            set: "1e3".to_string(),
+            tool: "tool \"x\"\t\\☕\u{1}".to_string(),
+            version: Some("tool 1.2.3\n(build \"abc\")".to_string()),
+            flavor: JournalFlavor::Beancount,
+            command: "tool {journal} 'a \\ b'".to_string(),
+            status: Status::Ok,
+            runs: vec![
+                Run {
+                    wall: 1.5,
+                    cpu: Some(1.25),
+                    max_rss: Some(1024),
+                    success: true,
+                },
+                Run {
+                    wall: 2.5,
+                    cpu: None,
+                    max_rss: None,
+                    success: true,
+                },
+            ],
+        };
+        let text = to_json(&meta, std::slice::from_ref(&result)).unwrap(/*:test:*/);
+        assert!(text.contains("\"tool\": \"tool \\\"x\\\"\\t\\\\☕\\u0001\","));
+        assert!(text.contains("\"runs\": 2,"));
+
+        let set = parse("bench".to_string(), &text).unwrap(/*:test:*/);
+        assert_eq!(set.label, "bench");
+        assert_eq!(set.meta, meta);
+        assert_eq!(set.results.len(), 1);
+        let r = &set.results[0];
+        assert_eq!(r.tool, result.tool);
+        assert_eq!(r.version, result.version);
+        assert_eq!(r.command, result.command);
+        assert_eq!(r.flavor, JournalFlavor::Beancount);
+        assert_eq!(r.runs, result.runs);
+        // Written again, it's the same
+        assert_eq!(to_json(&set.meta, &set.results).unwrap(/*:test:*/), text);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let meta = r#""generator": "g", "timestamp": "t", "os": "o", "arch": "a", "runs": 3"#;
+        assert!(parse("x".to_string(), "[]").is_err());
+        assert!(parse("x".to_string(), &format!("{{{meta}, \"results\": [")).is_err());
+        assert!(parse("x".to_string(), &format!("{{{meta}, \"results\": []}} x")).is_err());
+        let result = r#"{"set": "1e3"}"#;
+        assert!(
+            parse(
+                "x".to_string(),
+                &format!("{{{meta}, \"results\": [{result}]}}")
+            )
+            .is_err()
+        );
+        let result = r#"{"set": "1e3", "tool": "t", "flavor": "ledger", "command": "t",
+            "status": "ok", "wall_s": [1.0], "cpu_s": [], "max_rss_kb": [null]}"#;
+        assert!(
+            parse(
+                "x".to_string(),
+                &format!("{{{meta}, \"results\": [{result}]}}")
+            )
+            .is_err()
+        );
+
+        // Version is missing in older results
+        let result = r#"{"set": "1e3", "tool": "t", "flavor": "ledger", "command": "t",
+            "status": "ok", "wall_s": [1.0], "cpu_s": [null], "max_rss_kb": [null]}"#;
+        let set = parse("x".to_string(), &format!("{{{meta}, \"results\": [{result}]}}"))
+            .unwrap(/*:test:*/);
+        assert_eq!(set.meta.runs, 3);
+        assert_eq!(set.results[0].version, None);
+    }
+}
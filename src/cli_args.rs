@@ -3,7 +3,16 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::setup::{JournalFlavor, ShardType};
+use crate::account::{AccountGen, AccountStyle};
+use crate::amount::{AmountDist, AmountGen};
+use crate::benchmark::compare::Metric;
+use crate::commodity::{CommodityAssign, CommodityGen, CommodityNaming};
+use crate::setup::template::ShardTemplate;
+use crate::setup::{
+    Archive, ErrorKind, IndentStyle, JournalFlavor, JournalFormat, LineEnding, Output,
+    OutputPolicy, SetSize, ShardOptions, ShardType, TimeFormat,
+};
+use crate::timetrack::MAX_PER_DAY;
 use clap::builder::PossibleValue;
 use clap::{Parser, Subcommand};
 use jiff::Zoned;
@@ -47,6 +56,9 @@ pub enum Commands {
             ]),
         )]
         flavor: Option<String>,
+
+        #[clap(flatten)]
+        amount_args: AmountArgs,
     },
 
     /// Generate journal in the simplest form
@@ -67,6 +79,12 @@ pub enum Commands {
             ]),
         )]
         flavor: Option<String>,
+
+        #[clap(flatten)]
+        amount_args: AmountArgs,
+
+        #[clap(flatten)]
+        append_args: AppendArgs,
     },
 
     /// Generate journal with commodities
@@ -87,9 +105,688 @@ pub enum Commands {
             ]),
         )]
         flavor: Option<String>,
+
+        #[clap(flatten)]
+        amount_args: AmountArgs,
+
+        #[clap(flatten)]
+        commodity_args: CommodityArgs,
+
+        #[clap(flatten)]
+        account_args: AccountArgs,
+
+        #[clap(flatten)]
+        equity_args: EquityArgs,
+
+        #[clap(flatten)]
+        beancount_args: BeancountArgs,
+
+        #[clap(flatten)]
+        append_args: AppendArgs,
+    },
+
+    /// Generate journal with currency conversions
+    ///
+    /// Transactions convert foreign currencies to EUR with deterministic
+    /// daily exchange rates. Conversions alternate between unit price (`@`),
+    /// total price (`@@`, tackler: `=`) and unit price with explicit
+    /// EUR posting. The explicit EUR posting is rounded to cents, so it
+    /// leaves a rounding residual, which is balanced by tolerance of the tool.
+    /// Tackler has no balancing tolerance, so its explicit postings are exact.
+    ///
+    /// (h)ledger and beancount journals have price directives for the rates.
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Fx {
+        #[clap(flatten)]
+        global_args: GlobalArgs,
+
+        /// Flavor of Journal
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+
+        #[clap(flatten)]
+        amount_args: AmountArgs,
+    },
+
+    /// Generate personal finance journal
+    ///
+    /// Household with salary income, rent and utilities, everyday purchases
+    /// with debit and credit card, credit card payments, savings transfers and
+    /// a loan with interest, between `start` and `stop`.
+    ///
+    /// Monthly transactions are always generated, and the set is filled
+    /// up to set size with everyday purchases.
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Personal {
+        #[clap(flatten)]
+        global_args: GlobalArgs,
+
+        /// Flavor of Journal
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+
+        #[clap(flatten)]
+        seed_args: SeedArgs,
+    },
+
+    /// Generate small business journal
+    ///
+    /// Small business with sales invoices and purchase bills with VAT
+    /// at several rates, receivables and payables with their payments,
+    /// rent, payroll, tax payments and depreciation, between `start` and `stop`.
+    ///
+    /// Monthly transactions are always generated, and the set is filled
+    /// up to set size with invoices, bills and their payments.
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Business {
+        #[clap(flatten)]
+        global_args: GlobalArgs,
+
+        /// Flavor of Journal
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+
+        #[clap(flatten)]
+        seed_args: SeedArgs,
+    },
+
+    /// Generate inventory journal
+    ///
+    /// Trading company with stock receipts and sales of goods
+    /// (items, kilograms and hours) with per-unit costs, cost of goods sold
+    /// and monthly stock count, between `start` and `stop`.
+    ///
+    /// Names of goods are quoted for (h)ledger (e.g. "Widget A"),
+    /// and they are commodity symbols for other flavors (e.g. WIDGET_A).
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Inventory {
+        #[clap(flatten)]
+        global_args: GlobalArgs,
+
+        /// Flavor of Journal
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+
+        #[clap(flatten)]
+        seed_args: SeedArgs,
+    },
+
+    /// Generate time tracking journal
+    ///
+    /// Time tracking sessions of projects, between `start` and `stop`.
+    /// Each day has the same count of sessions, and days are spread
+    /// over the time range.
+    ///
+    /// - tackler:  sessions are txns with sub-second timestamps
+    /// - (h)ledger: timeclock (`i`/`o` entries) or timedot file
+    ///
+    /// Supported flavors: tackler, (h)ledger
+    #[allow(clippy::doc_overindented_list_items)]
+    Timeclock {
+        #[clap(flatten)]
+        global_args: GlobalArgs,
+
+        /// Flavor of Journal
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+            ]),
+        )]
+        flavor: Option<String>,
+
+        /// Format of time tracking file
+        ///
+        /// Supported flavors: (h)ledger
+        #[arg(long, value_name = "FORMAT",
+            value_parser([
+                PossibleValue::new(TimeFormat::TIMECLOCK),
+                PossibleValue::new(TimeFormat::TIMEDOT),
+            ]),
+        )]
+        time_format: Option<String>,
+
+        /// Count of sessions per day
+        ///
+        /// Default is the smallest count, which fits
+        /// all sessions into the time range.
+        #[arg(long, value_name = "COUNT",
+            value_parser = clap::value_parser!(u32).range(1..=i64::from(MAX_PER_DAY)))]
+        sessions: Option<u32>,
+
+        /// Count of projects
+        #[arg(long, value_name = "COUNT", default_value_t = 5,
+            value_parser = clap::value_parser!(u32).range(1..=99))]
+        projects: u32,
+
+        #[clap(flatten)]
+        seed_args: SeedArgs,
+    },
+
+    /// Generate journal with deliberate errors
+    ///
+    /// Journal with commodities and charts of accounts and commodities,
+    /// but with injected errors. Positions of errors are written into
+    /// `expected-errors-<flavor>.txt` file at the root of txn set,
+    /// in format `<path>:<line>: <error kind>`.
+    ///
+    /// Tackler set is using strict and audit mode, and (h)ledger set
+    /// must be checked in strict (pedantic) mode.
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Invalid {
+        #[clap(flatten)]
+        global_args: GlobalArgs,
+
+        /// Flavor of Journal
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+
+        /// Kinds of injected errors
+        ///
+        /// Default is all kinds supported by flavor.
+        /// 'duplicate-uuid' is supported only by tackler.
+        ///
+        /// 'truncated-file' is injected at the end of journal file, so for
+        /// shard types 'single' and 'month' it can be injected only once.
+        #[arg(long, value_name = "KIND", value_delimiter = ',',
+            value_parser([
+                PossibleValue::new(ErrorKind::UNBALANCED),
+                PossibleValue::new(ErrorKind::UNKNOWN_ACCOUNT),
+                PossibleValue::new(ErrorKind::UNDECLARED_COMMODITY),
+                PossibleValue::new(ErrorKind::MALFORMED_DATE),
+                PossibleValue::new(ErrorKind::DUPLICATE_UUID),
+                PossibleValue::new(ErrorKind::BAD_INDENTATION),
+                PossibleValue::new(ErrorKind::TRUNCATED_FILE),
+            ]),
+        )]
+        errors: Vec<String>,
+
+        /// How many errors of each kind are injected
+        #[arg(long, value_name = "COUNT", default_value_t = 1)]
+        error_count: u32,
+
+        #[clap(flatten)]
+        amount_args: AmountArgs,
+    },
+
+    /// Generate fuzzing corpus
+    ///
+    /// Many small journal files covering edge cases (extreme amounts,
+    /// high scale, Unicode account names, empty descriptions,
+    /// comment-only files, etc.). Each case is also written with BOM,
+    /// CRLF line endings and with both of those.
+    ///
+    /// Corpus has also grammar-based mutations of a valid txn,
+    /// where one syntax element (date, posting, amount, indent or comment)
+    /// is changed at a time.
+    ///
+    /// Corpus is located at `PATH/fuzz/<flavor>`.
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Fuzz {
+        /// Path to output directory
+        #[arg(long = "path", value_name = "path/to/output_directory")]
+        data_path: PathBuf,
+
+        /// Flavor of Journal
+        ///
+        /// Default is all flavors
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+    },
+
+    /// Verify generated test set
+    ///
+    /// Journals of the set are checked with a lightweight internal checker,
+    /// so that no PTA tools are needed: dates, indentation, account names,
+    /// balancing of txns, and that includes resolve. If accounts or
+    /// commodities are declared, postings must use only declared ones.
+    /// Closed beancount accounts must not be used after their close date.
+    ///
+    /// Errors are listed as `path:line: kind: detail`, and the kinds match
+    /// the expected errors of invalid test sets.
+    ///
+    /// Supported flavors: tackler, (h)ledger, beancount
+    Verify {
+        /// Path to test set directory (e.g. `data/comm/set-1e3-single`)
+        #[arg(long = "path", value_name = "path/to/set_directory")]
+        set_path: PathBuf,
+
+        /// Flavor of Journal
+        ///
+        /// Default is all flavors, which have journals in the set
+        #[arg(long,
+            value_parser([
+                PossibleValue::new(JournalFlavor::TACKLER),
+                PossibleValue::new(JournalFlavor::LEDGER),
+                PossibleValue::new(JournalFlavor::BEANCOUNT),
+            ]),
+        )]
+        flavor: Option<String>,
+    },
+
+    /// Benchmark PTA tools with generated test sets
+    ///
+    /// Commodity test sets (`PATH/comm/set-SET-single`) are generated,
+    /// if they don't exist yet. Each tool is run several times with each set,
+    /// and wall time, maximum RSS and CPU time of the tool are measured.
+    /// Tools which are not installed are skipped.
+    ///
+    /// Results are written as text table and JSON into `PATH/bench`,
+    /// named by the time of run, and existing results are never overwritten.
+    Bench {
+        /// Path to data directory
+        #[arg(long = "path", value_name = "path/to/output_directory")]
+        data_path: PathBuf,
+
+        /// Set sizes to benchmark, comma separated (e.g. `1e3,1e4`)
+        #[arg(
+            long,
+            value_name = "SETS",
+            value_delimiter = ',',
+            default_value = "1e3"
+        )]
+        set_size: Vec<String>,
+
+        /// How many times each tool is run with each set
+        #[arg(long, value_name = "COUNT", default_value_t = 3,
+            value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+
+        /// Tool to benchmark: NAME:FLAVOR:COMMAND (could be repeated)
+        ///
+        /// Command could use variables {config}, {journal} and {set},
+        /// for example: "hledger:ledger:hledger -f {journal} bal"
+        ///
+        /// Default tools are tackler, ledger, hledger, rledger and bean-query
+        #[arg(long = "tool", value_name = "TOOL", verbatim_doc_comment)]
+        tools: Vec<String>,
+    },
+
+    /// Compare benchmark results
+    ///
+    /// The first results file is the baseline, and other files are compared
+    /// with it, by set and tool. Medians are compared, and change is significant
+    /// when its 95% confidence interval (estimated with MAD) doesn't include zero.
+    ///
+    /// Report is written as Markdown, HTML and CSV. Exit code is non-zero,
+    /// if there are significant regressions beyond the threshold.
+    Compare {
+        /// Results JSON files of `bench`, the first one is baseline
+        #[arg(value_name = "RESULTS", required = true, num_args = 2..)]
+        results: Vec<PathBuf>,
+
+        /// Measurement to compare
+        #[arg(long, default_value = Metric::WALL,
+            value_parser([
+                PossibleValue::new(Metric::WALL),
+                PossibleValue::new(Metric::CPU),
+                PossibleValue::new(Metric::RSS),
+            ]),
+        )]
+        metric: String,
+
+        /// Regression threshold, in percent of baseline median
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        threshold: f64,
+
+        /// Output directory of reports
+        ///
+        /// Default is the directory of baseline results
+        #[arg(long, value_name = "path/to/output_directory")]
+        output: Option<PathBuf>,
     },
 }
 
+/// Posting amount options
+#[derive(Debug, Clone, clap::Args)]
+#[command(next_help_heading = "Amounts")]
+pub(crate) struct AmountArgs {
+    /// Distribution of posting amounts
+    ///
+    /// 'day' is the day of month of txn with the smallest
+    /// possible decimal (e.g. 31.0000001), and it is not random.
+    /// Other distributions are between min and max.
+    #[arg(long, value_name = "DIST", default_value = AmountDist::DAY,
+        value_parser([
+            PossibleValue::new(AmountDist::DAY),
+            PossibleValue::new(AmountDist::UNIFORM),
+            PossibleValue::new(AmountDist::LOG_NORMAL),
+            PossibleValue::new(AmountDist::PARETO),
+        ]),
+    )]
+    amount_dist: String,
+
+    /// Minimum magnitude of amount
+    #[arg(long, value_name = "MIN", default_value_t = 1.0)]
+    amount_min: f64,
+
+    /// Maximum magnitude of amount (e.g. 1e20)
+    ///
+    /// Sum of all amounts of the set must fit into 96-bit integer with scale
+    /// for tackler, and it must have at most 28 digits (integer digits and scale)
+    /// for beancount and 38 digits for (h)ledger.
+    #[arg(long, value_name = "MAX", default_value_t = 1000.0)]
+    amount_max: f64,
+
+    /// Scale of amounts (count of decimals)
+    ///
+    /// Scale is at most 28 for tackler. Scale of tackler reports is from
+    /// min(2, SCALE) to SCALE.
+    #[arg(long, value_name = "SCALE", default_value_t = 7)]
+    pub amount_scale: u32,
+
+    /// Ratio of negative amounts, between 0 and 1
+    #[arg(long, value_name = "RATIO", default_value_t = 0.0)]
+    negative_ratio: f64,
+
+    /// Ratio of zero amounts, between 0 and 1
+    ///
+    /// Zero postings are rejected by some tools.
+    #[arg(long, value_name = "RATIO", default_value_t = 0.0)]
+    zero_ratio: f64,
+
+    /// Seed for random amounts
+    ///
+    /// The same seed will produce the same amounts.
+    /// It's also used for other random data of the mode
+    /// (e.g. commodities and exchange rates).
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+impl AmountArgs {
+    /// Amount generator for the set, see `AmountGen::for_flavor`
+    pub fn amount_gen(
+        &self,
+        flavor: &JournalFlavor,
+        set: &SetSize,
+    ) -> Result<AmountGen, Box<dyn std::error::Error>> {
+        AmountGen::try_new(
+            AmountDist::try_from(self.amount_dist.as_str())?,
+            self.amount_min,
+            self.amount_max,
+            self.amount_scale,
+            self.negative_ratio,
+            self.zero_ratio,
+            self.seed,
+        )?
+        .for_flavor(flavor, set.size())
+    }
+}
+
+impl Default for AmountArgs {
+    /// The same as defaults of command line options
+    fn default() -> Self {
+        AmountArgs {
+            amount_dist: AmountDist::DAY.to_string(),
+            amount_min: 1.0,
+            amount_max: 1000.0,
+            amount_scale: 7,
+            negative_ratio: 0.0,
+            zero_ratio: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Seed of modes without amount options
+#[derive(Debug, Clone, clap::Args)]
+pub(crate) struct SeedArgs {
+    /// Seed for random test data
+    ///
+    /// The same seed will produce the same test data.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+/// Commodity options
+#[allow(clippy::doc_overindented_list_items)]
+#[derive(Debug, Clone, clap::Args)]
+#[command(next_help_heading = "Commodities")]
+pub(crate) struct CommodityArgs {
+    /// Count of commodities
+    #[arg(long, value_name = "COUNT", default_value_t = 31,
+        value_parser = clap::value_parser!(u32).range(1..=i64::from(CommodityGen::MAX_COUNT)))]
+    commodity_count: u32,
+
+    /// Naming scheme of commodities
+    ///
+    /// - iso:    three letter codes (CAA, CAB, ..., EUR, AAA, AAB, ...)
+    /// - ticker: codes with two or more letters (AA, AB, ..., ZZ, AAA, ...)
+    /// - quoted: quoted names with spaces and digits ("Fund 1 A"),
+    ///           supported only by (h)ledger
+    #[arg(long, value_name = "NAMING", verbatim_doc_comment, default_value = CommodityNaming::ISO,
+        value_parser([
+            PossibleValue::new(CommodityNaming::ISO),
+            PossibleValue::new(CommodityNaming::TICKER),
+            PossibleValue::new(CommodityNaming::QUOTED),
+        ]),
+    )]
+    commodity_naming: String,
+
+    /// How commodities are assigned to transactions
+    ///
+    /// - day:         based on day of month of txn, or on days
+    ///                since 1970-01-01 with over 31 commodities
+    /// - round-robin: based on txn index
+    /// - random:      random commodity for each txn
+    /// - per-account: each expense account has always the same commodity
+    #[arg(long, value_name = "ASSIGN", verbatim_doc_comment, default_value = CommodityAssign::DAY,
+        value_parser([
+            PossibleValue::new(CommodityAssign::DAY),
+            PossibleValue::new(CommodityAssign::ROUND_ROBIN),
+            PossibleValue::new(CommodityAssign::RANDOM),
+            PossibleValue::new(CommodityAssign::PER_ACCOUNT),
+        ]),
+    )]
+    commodity_assign: String,
+}
+
+impl CommodityArgs {
+    pub fn commodity_gen(
+        &self,
+        flavor: &JournalFlavor,
+        seed: u64,
+    ) -> Result<CommodityGen, Box<dyn std::error::Error>> {
+        let naming = CommodityNaming::try_from(self.commodity_naming.as_str())?;
+        if !naming.supported(flavor) {
+            let msg = format!(
+                "Commodity naming '{naming}' is not supported for journal flavor '{flavor}'"
+            );
+            return Err(msg.into());
+        }
+        CommodityGen::try_new(
+            self.commodity_count,
+            naming,
+            CommodityAssign::try_from(self.commodity_assign.as_str())?,
+            seed,
+        )
+    }
+}
+
+impl Default for CommodityArgs {
+    /// The same as defaults of command line options
+    fn default() -> Self {
+        CommodityArgs {
+            commodity_count: 31,
+            commodity_naming: CommodityNaming::ISO.to_string(),
+            commodity_assign: CommodityAssign::DAY.to_string(),
+        }
+    }
+}
+
+/// Account options
+#[allow(clippy::doc_overindented_list_items)]
+#[derive(Debug, Clone, clap::Args)]
+#[command(next_help_heading = "Accounts")]
+pub(crate) struct AccountArgs {
+    /// Style of account names
+    ///
+    /// - default: Assets:Ay2024:Am01, Expenses:Ey2024:Em01:Ed01
+    /// - long:    names with 50 - 200 characters
+    /// - deep:    names with 11 - 12 levels
+    /// - unicode: Finnish and CJK names, and emoji
+    ///            for (h)ledger and beancount
+    /// - spaces:  names with spaces, supported only by (h)ledger
+    /// - numeric: numeric segments (Assets:2024:01)
+    ///
+    /// Supported modes: comm
+    #[arg(long, value_name = "STYLE", verbatim_doc_comment, default_value = AccountStyle::DEFAULT,
+        value_parser([
+            PossibleValue::new(AccountStyle::DEFAULT),
+            PossibleValue::new(AccountStyle::LONG),
+            PossibleValue::new(AccountStyle::DEEP),
+            PossibleValue::new(AccountStyle::UNICODE),
+            PossibleValue::new(AccountStyle::SPACES),
+            PossibleValue::new(AccountStyle::NUMERIC),
+        ]),
+    )]
+    account_style: String,
+}
+
+impl AccountArgs {
+    pub fn account_gen(
+        &self,
+        flavor: &JournalFlavor,
+    ) -> Result<AccountGen, Box<dyn std::error::Error>> {
+        AccountGen::try_new(
+            AccountStyle::try_from(self.account_style.as_str())?,
+            flavor.clone(),
+        )
+    }
+}
+
+impl Default for AccountArgs {
+    /// The same as defaults of command line options
+    fn default() -> Self {
+        AccountArgs {
+            account_style: AccountStyle::DEFAULT.to_string(),
+        }
+    }
+}
+
+/// Equity options
+#[derive(Debug, Clone, Default, clap::Args)]
+#[command(next_help_heading = "Equity")]
+pub(crate) struct EquityArgs {
+    /// Generate opening balance transaction at start
+    ///
+    /// Each asset account of the set has opening balance
+    /// for each of its commodities from `Equity:Opening-Balances`.
+    ///
+    /// Supported modes: comm
+    #[arg(long)]
+    pub opening_balances: bool,
+
+    /// Generate year-end closing transactions
+    ///
+    /// Expense accounts are closed into `Equity:Retained-Earnings`
+    /// at the end of each year, which ends before stop.
+    ///
+    /// Supported modes: comm
+    #[arg(long)]
+    pub close_years: bool,
+}
+
+/// Beancount specific options
+#[derive(Debug, Clone, Default, clap::Args)]
+#[command(next_help_heading = "Beancount")]
+pub(crate) struct BeancountArgs {
+    /// Generate also non-transaction directives
+    ///
+    /// Options, plugin, pad, balance, note, document, event,
+    /// query, custom and close directives are generated
+    /// in addition of transactions.
+    ///
+    /// Supported flavors: beancount
+    #[arg(long)]
+    pub directives: bool,
+
+    /// Close accounts after their last use
+    ///
+    /// Account is closed on the next day after its last use.
+    ///
+    /// Supported flavors: beancount
+    #[arg(long)]
+    pub close_accounts: bool,
+
+    /// Inject deliberate usage of closed accounts
+    ///
+    /// This many transactions are generated which use
+    /// account after it has been closed. These are errors,
+    /// and could be used to test error reporting.
+    ///
+    /// Supported flavors: beancount
+    #[arg(long, value_name = "COUNT", requires = "close_accounts")]
+    pub post_close_usage: Option<u32>,
+}
+
+/// Append options
+#[derive(Debug, Clone, Default, clap::Args)]
+#[command(next_help_heading = "Append")]
+pub(crate) struct AppendArgs {
+    /// Append txns to an existing txn set
+    ///
+    /// The set is selected by `--path`, `--set-size` and `--shard-type`,
+    /// and it is continued from its last timestamp and index, which are read
+    /// from the manifest of the set, with the same time between txns.
+    /// Journals are appended (missing shards are created), and charts,
+    /// config and references are updated.
+    ///
+    /// Other options should be the same as with the original set.
+    #[arg(long, value_name = "COUNT", conflicts_with_all = ["start", "overwrite", "skip_existing", "archive"],
+        value_parser = clap::value_parser!(u32).range(1..))]
+    pub append: Option<u32>,
+}
+
+impl BeancountArgs {
+    pub fn is_used(&self) -> bool {
+        self.directives || self.close_accounts || self.post_close_usage.is_some()
+    }
+}
+
 #[allow(clippy::doc_overindented_list_items)]
 #[derive(Debug, Clone, clap::Args)]
 pub(crate) struct GlobalArgs {
@@ -106,19 +803,74 @@ pub(crate) struct GlobalArgs {
     ///           path(tackler):   'PATH/set-SET-single/txns/SET-journal.txn
     ///           path(h/ledger):  'PATH/set-SET-single/txns/SET.journal
     ///           path(beancount): 'PATH/set-SET-single/txns/SET.beancount
-    /// - month:  Use 12 sub journals (shards) based on month of txn
+    /// - year:   Use sub journals (shards) based on year of txn
+    ///           path: 'PATH/set-SET-year/txns/YYYY/YYYY.txn
+    /// - month:  Use sub journals (shards) based on year and month of txn
     ///           path: 'PATH/set-SET-month/txns/YYYY/MM/YYYY-MM.txn
+    /// - week:   Use sub journals (shards) based on ISO week of txn
+    ///           path: 'PATH/set-SET-week/txns/YYYY/Www/YYYY-Www.txn
+    /// - day:    Use sub journals (shards) based on date of txn
+    ///           path: 'PATH/set-SET-day/txns/YYYY/MM/DD/YYYY-MM-DD.txn
     /// - txn:    Use own file for each transaction, shard is based on txn timestamp
     ///           path: 'PATH/set-SET-txn/txns/YYYY/MM/DD/YYYYMMDDTHHMMSS-IDX.txn'
+    /// - template: Use shard path of '--shard-template'
+    ///           path: 'PATH/set-SET-template/TEMPLATE'
+    /// - count:  Use sub journals with '--shard-txns' txns
+    ///           path: 'PATH/set-SET-count/txns/NNNNNN.txn'
+    /// - size:   Use sub journals with maximum size of '--shard-bytes'
+    ///           path: 'PATH/set-SET-size/txns/NNNNNN.txn'
+    /// - hash:   Use own file for each transaction, in '--shard-buckets' buckets by hash of txn uuid
+    ///           path: 'PATH/set-SET-hash/txns/BUCKET/UUID.txn'
     #[arg(long, verbatim_doc_comment,
         value_parser([
                 PossibleValue::new(ShardType::SINGLE),
+                PossibleValue::new(ShardType::YEAR),
                 PossibleValue::new(ShardType::MONTH),
+                PossibleValue::new(ShardType::WEEK),
+                PossibleValue::new(ShardType::DAY),
                 PossibleValue::new(ShardType::TXN),
+                PossibleValue::new(ShardType::TEMPLATE),
+                PossibleValue::new(ShardType::COUNT),
+                PossibleValue::new(ShardType::SIZE),
+                PossibleValue::new(ShardType::HASH),
             ]),
     )]
     pub shard_type: String,
 
+    /// Shard path template of shard type 'template'
+    ///
+    /// Template is relative to the set, under 'txns' and with extension '.txn',
+    /// and txns with the same path are in the same journal, e.g.
+    ///   txns/{year}/Q{quarter}/{account_top}/{uuid}.txn
+    ///
+    /// Placeholders:
+    ///   {year}, {quarter}, {month}, {day}: date of txn
+    ///   {week_year}, {week}:               ISO week date of txn
+    ///   {index}:                           index of txn
+    ///   {uuid}:                            uuid of txn
+    ///   {account}:                         account of the first posting (sub-accounts are directories)
+    ///   {account_top}:                     top account of the first posting
+    ///   {commodity}:                       commodity of the first posting ('none' if there is none)
+    ///
+    /// Date parts must be unique over the set: {quarter} and {month} require {year},
+    /// {day} requires {month} and {week} requires {week_year}.
+    #[arg(long, verbatim_doc_comment, value_name = "TEMPLATE")]
+    shard_template: Option<String>,
+
+    /// Count of txns per journal of shard type 'count'
+    #[arg(long, value_name = "TXNS", value_parser = clap::value_parser!(u32).range(1..))]
+    shard_txns: Option<u32>,
+
+    /// Maximum size of journal in bytes of shard type 'size'
+    ///
+    /// Journal has always at least one txn, even if the txn is larger than this.
+    #[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(u64).range(1..))]
+    shard_bytes: Option<u64>,
+
+    /// Count of buckets (directories) of shard type 'hash'
+    #[arg(long, value_name = "BUCKETS", value_parser = clap::value_parser!(u32).range(1..))]
+    shard_buckets: Option<u32>,
+
     /// How many transactions to generate (1e1, 1e2, ...)
     ///
     /// Supported set sizes are:
@@ -146,9 +898,129 @@ pub(crate) struct GlobalArgs {
     /// If used, must be with `start`
     #[arg(long, value_name = "STOP_TIMESTAMP", requires = "start")]
     stop: Option<String>,
+
+    /// Line ending of journal files
+    #[arg(long, help_heading = "Output Format", default_value = LineEnding::LF,
+        value_parser([
+            PossibleValue::new(LineEnding::LF),
+            PossibleValue::new(LineEnding::CRLF),
+        ]),
+    )]
+    line_ending: String,
+
+    /// Indentation style of postings
+    ///
+    /// 'mixed' will indent every other posting with tab
+    #[arg(long, help_heading = "Output Format", default_value = IndentStyle::SPACES,
+        value_parser([
+            PossibleValue::new(IndentStyle::SPACES),
+            PossibleValue::new(IndentStyle::TABS),
+            PossibleValue::new(IndentStyle::MIXED),
+        ]),
+    )]
+    indent: String,
+
+    /// Count of spaces for indentation with spaces
+    #[arg(long, help_heading = "Output Format", value_name = "SPACES", default_value_t = 2,
+        value_parser = clap::value_parser!(u8).range(1..))]
+    indent_width: u8,
+
+    /// Write UTF-8 BOM at the beginning of journal files
+    #[arg(long, help_heading = "Output Format")]
+    bom: bool,
+
+    /// Replace existing txn set
+    ///
+    /// The new set is written into a staging directory, and the existing set
+    /// is replaced only after the new set is complete.
+    #[arg(long, help_heading = "Output", conflicts_with = "skip_existing")]
+    overwrite: bool,
+
+    /// Keep existing txn set, and generate the set only if it's missing
+    ///
+    /// Sets are moved into place only after they are complete, so an
+    /// interrupted run doesn't leave a partial set behind. Interrupted run
+    /// is not continued: the set is generated again from the start.
+    #[arg(long, help_heading = "Output")]
+    skip_existing: bool,
+
+    /// Write txn set as archive, or with gzip-compressed journal
+    ///
+    /// Archive is written at the data path (e.g. 'set-1e3-month-tackler.tar.gz'),
+    /// and it has the same files as the set, with fixed timestamps
+    /// and permissions, so the same set produces always the same archive.
+    ///
+    /// 'gz' compresses the journal of single shard set (e.g. '1e3.journal.gz'),
+    /// and other files of the set are written as they are.
+    #[arg(long, help_heading = "Output", value_name = "FORMAT",
+        value_parser([
+            PossibleValue::new(Archive::TAR),
+            PossibleValue::new(Archive::TAR_GZ),
+            PossibleValue::new(Archive::TAR_ZST),
+            PossibleValue::new(Archive::GZ),
+        ]),
+    )]
+    archive: Option<String>,
 }
 
 impl GlobalArgs {
+    /// Global options with defaults of command line options
+    pub fn new(data_path: PathBuf, set_size: &SetSize, shard_type: &ShardType) -> GlobalArgs {
+        GlobalArgs {
+            data_path,
+            shard_type: shard_type.to_string(),
+            shard_template: None,
+            shard_txns: None,
+            shard_bytes: None,
+            shard_buckets: None,
+            set_size: set_size.str().to_string(),
+            start: None,
+            stop: None,
+            line_ending: LineEnding::LF.to_string(),
+            indent: IndentStyle::SPACES.to_string(),
+            indent_width: 2,
+            bom: false,
+            overwrite: false,
+            skip_existing: false,
+            archive: None,
+        }
+    }
+
+    pub fn journal_format(&self) -> Result<JournalFormat, Box<dyn std::error::Error>> {
+        Ok(JournalFormat {
+            line_ending: LineEnding::try_from(self.line_ending.as_str())?,
+            indent_style: IndentStyle::try_from(self.indent.as_str())?,
+            indent_width: usize::from(self.indent_width),
+            bom: self.bom,
+        })
+    }
+
+    pub fn output(&self) -> Result<Output, Box<dyn std::error::Error>> {
+        let policy = if self.overwrite {
+            OutputPolicy::Overwrite
+        } else if self.skip_existing {
+            OutputPolicy::SkipExisting
+        } else {
+            OutputPolicy::Create
+        };
+        let archive = self.archive.as_deref().map(Archive::try_from).transpose()?;
+        let template = self
+            .shard_template
+            .as_deref()
+            .map(ShardTemplate::try_from)
+            .transpose()?;
+        Ok(Output {
+            policy,
+            archive,
+            shards: ShardOptions {
+                template,
+                txns: self.shard_txns,
+                bytes: self.shard_bytes,
+                buckets: self.shard_buckets,
+            },
+        })
+    }
+
     pub fn start_ts(&self) -> Result<Zoned, jiff::Error> {
         self.start.as_ref().map_or(
             date(2024, 1, 1).at(0, 0, 0, 0).to_zoned(TimeZone::UTC),
@@ -168,3 +1040,83 @@ fn verify_cli() {
     use clap::CommandFactory;
     Cli::command().debug_assert();
 }
+
+#[test]
+fn verify_comm_options() {
+    let args = |mode: &str, opt: &[&str]| {
+        let argv = ["pta-generator", mode, "--path", "data", "--set-size", "1e2"];
+        let argv = argv.iter().chain(&["--shard-type", "single"]);
+        Cli::try_parse_from(argv.chain(opt))
+    };
+    let opts: [&[&str]; 3] = [
+        &["--opening-balances"],
+        &["--close-years"],
+        &["--account-style", "long"],
+    ];
+    for opt in opts {
+        assert!(args("comm", opt).is_ok());
+        // Equity and account options are rejected by modes without them
+        for mode in ["audit", "plain", "fx", "invalid"] {
+            assert!(args(mode, &[]).is_ok(), "{mode}");
+            assert!(args(mode, opt).is_err(), "{mode} {opt:?}");
+        }
+    }
+}
+
+#[test]
+fn verify_comm_defaults() {
+    let argv = [
+        "pta-generator",
+        "comm",
+        "--path",
+        "data",
+        "--set-size",
+        "1e2",
+    ];
+    let cli =
+        Cli::try_parse_from(argv.iter().chain(&["--shard-type", "single"])).unwrap(/*:test:*/);
+    let Commands::Comm {
+        global_args,
+        amount_args,
+        commodity_args,
+        account_args,
+        equity_args,
+        beancount_args,
+        append_args,
+        ..
+    } = cli.cmd()
+    else {
+        panic!("comm command");
+    };
+    // Defaults must be the same as defaults of command line options
+    let global = GlobalArgs::new(
+        PathBuf::from("data"),
+        &SetSize::try_from("1e2").unwrap(/*:test:*/),
+        &ShardType::Single,
+    );
+    assert_eq!(format!("{global:?}"), format!("{global_args:?}"));
+    assert_eq!(
+        format!("{:?}", AmountArgs::default()),
+        format!("{amount_args:?}")
+    );
+    assert_eq!(
+        format!("{:?}", CommodityArgs::default()),
+        format!("{commodity_args:?}")
+    );
+    assert_eq!(
+        format!("{:?}", AccountArgs::default()),
+        format!("{account_args:?}")
+    );
+    assert_eq!(
+        format!("{:?}", EquityArgs::default()),
+        format!("{equity_args:?}")
+    );
+    assert_eq!(
+        format!("{:?}", BeancountArgs::default()),
+        format!("{beancount_args:?}")
+    );
+    assert_eq!(
+        format!("{:?}", AppendArgs::default()),
+        format!("{append_args:?}")
+    );
+}
@@ -3,5 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 pub mod audit;
+pub mod bench;
 pub mod comm;
+pub mod compare;
+pub mod fuzz;
+pub mod fx;
+pub mod invalid;
 pub mod plain;
+pub mod scenario;
+pub mod timeclock;
+pub mod verify;
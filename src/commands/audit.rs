@@ -3,9 +3,11 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::cli_args::GlobalArgs;
+use crate::cli_args::{AmountArgs, GlobalArgs};
 use crate::generators::tackler::Tackler;
-use crate::generators::{AuditTxnGenerator, ChartOfAccGenerator, ChartOfCommGenerator};
+use crate::generators::{
+    Accounts, AuditTxnGenerator, ChartOfAccGenerator, ChartOfCommGenerator, record_account,
+};
 use crate::setup::{JournalFlavor, SetSize, ShardType};
 use crate::writers::JournalWriter;
 use jiff::{Span, Unit};
@@ -14,33 +16,41 @@ use std::error::Error;
 use std::fmt::Write;
 use std::ops::Add;
 
-pub fn exec(args: GlobalArgs) -> Result<Option<String>, Box<dyn Error>> {
+pub fn exec(args: GlobalArgs, amount_args: AmountArgs) -> Result<Option<String>, Box<dyn Error>> {
     let flavor = JournalFlavor::default();
     let set = SetSize::try_from(args.set_size.as_str())?;
     let shard_type = ShardType::try_from(args.shard_type.as_str())?;
     let ts_start = args.start_ts()?;
     let ts_end = args.stop_ts()?;
+    let amount_gen = amount_args.amount_gen(&flavor, &set)?;
 
     let span_secs = (&ts_end - &ts_start).total(Unit::Second)? as u32;
     let step = Span::new().seconds(span_secs / set.size());
 
-    let mut writer = JournalWriter::try_new(
+    let Some(mut writer) = JournalWriter::try_new(
         flavor,
         args.data_path.join("audit").as_path(),
         set.clone(),
         shard_type,
-    )?;
-    let mut accounts = BTreeSet::new();
+        args.output()?,
+    )?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    writer.set_format(args.journal_format()?);
+    writer.track_postings();
+    let mut accounts = Accounts::new();
     let mut commodities = BTreeSet::new();
 
     let mut ts = ts_start;
     for idx in 1..=set.size() {
-        let txn = Tackler::audit_txn(&set, &ts, idx)?;
+        let amount = amount_gen.amount(&ts, idx);
+        let txn = Tackler::audit_txn(&set, &ts, idx, &amount)?;
 
         writer.write_txn(&ts, idx, txn.0.as_str())?;
 
         for a in txn.1.accounts {
-            accounts.insert(a);
+            record_account(&mut accounts, a, ts.date());
         }
         for a in txn.1.commodities {
             commodities.insert(a);
@@ -52,7 +62,8 @@ pub fn exec(args: GlobalArgs) -> Result<Option<String>, Box<dyn Error>> {
     writer.write_chart_of_accounts(Tackler::chart_of_accounts(&accounts)?.as_str())?;
     writer.write_chart_of_commodities(Tackler::chart_of_commodities(&commodities)?.as_str())?;
 
-    writer.write_config(Tackler::config(true, true, &writer.setup)?.as_str())?;
+    writer.write_config(Tackler::config(true, true, amount_gen.scale, &writer.setup)?.as_str())?;
+    writer.write_audit_references(amount_gen.scale)?;
 
     let mut msg = String::new();
     writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
@@ -68,5 +79,6 @@ pub fn exec(args: GlobalArgs) -> Result<Option<String>, Box<dyn Error>> {
         writer.setup.config_path().1.display()
     )?;
 
-    Ok(Some(msg))
+    writer.commit()?;
+    Ok(Some(writer.archived_msg(msg)))
 }
@@ -0,0 +1,156 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::benchmark::results::{Meta, to_json};
+use crate::benchmark::{BenchResult, Status, Tool, measure, table, version};
+use crate::cli_args::{
+    AccountArgs, AmountArgs, AppendArgs, BeancountArgs, CommodityArgs, EquityArgs, GlobalArgs,
+};
+use crate::commands::comm;
+use crate::setup::{JournalFlavor, JournalSetup, SetSize, ShardType};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Generate commodity test set with default options
+fn generate(data_path: &Path, set: &SetSize, flavor: &JournalFlavor) -> Result<(), Box<dyn Error>> {
+    comm::exec(
+        GlobalArgs::new(data_path.to_path_buf(), set, &ShardType::Single),
+        Some(flavor.to_string()),
+        AmountArgs::default(),
+        CommodityArgs::default(),
+        AccountArgs::default(),
+        EquityArgs::default(),
+        BeancountArgs::default(),
+        AppendArgs::default(),
+    )
+    .map(|_| ())
+}
+
+/// Write new file, which must not exist already
+fn write_new(path: &Path, content: &str) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|err| format!("Failed to write results '{}': {err}", path.display()))?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+pub fn exec(
+    data_path: PathBuf,
+    set_sizes: Vec<String>,
+    runs: u32,
+    tools: Vec<String>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let sets = set_sizes
+        .iter()
+        .map(|s| SetSize::try_from(s.as_str()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let tools = if tools.is_empty() {
+        Tool::defaults()
+    } else {
+        tools
+            .iter()
+            .map(|t| Tool::try_from(t.as_str()))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut msg = String::new();
+    let mut generated = BTreeSet::new();
+    let mut versions = BTreeMap::new();
+    let mut results = Vec::new();
+    for set in &sets {
+        for tool in &tools {
+            let mut setup = JournalSetup::try_new(
+                tool.flavor.clone(),
+                data_path.join("comm").as_path(),
+                set.clone(),
+                ShardType::Single,
+            )?;
+            let args = tool.args(&mut setup)?;
+
+            let journal = setup.journal_path(&jiff::Zoned::now(), 0).1;
+            let key = (set.str(), tool.flavor.to_string());
+            if !generated.contains(&key) && !journal.is_file() {
+                generate(&data_path, set, &tool.flavor)?;
+                writeln!(
+                    msg,
+                    "Generated {} test set for {}: {}",
+                    set,
+                    tool.flavor,
+                    setup.txn_set_path().display()
+                )?;
+            }
+            generated.insert(key);
+
+            let mut status = Status::Ok;
+            let mut measured = Vec::new();
+            for _ in 0..runs {
+                match measure(&args) {
+                    Ok(run) => {
+                        if !run.success {
+                            status = Status::Failed;
+                        }
+                        measured.push(run);
+                    }
+                    Err(err) if err.kind() == ErrorKind::NotFound => {
+                        status = Status::NotFound;
+                        break;
+                    }
+                    Err(err) => {
+                        let msg = format!("Failed to run tool '{}': {err}", tool.name);
+                        return Err(msg.into());
+                    }
+                }
+            }
+            let version = match status {
+                Status::NotFound => None,
+                _ => versions
+                    .entry(args[0].clone())
+                    .or_insert_with(|| version(&args[0]))
+                    .clone(),
+            };
+            results.push(BenchResult {
+                set: set.str().to_string(),
+                tool: tool.name.clone(),
+                version,
+                flavor: tool.flavor.clone(),
+                command: args.join(" "),
+                status,
+                runs: measured,
+            });
+        }
+    }
+
+    let now = jiff::Zoned::now();
+    let meta = Meta {
+        generator: format!("pta-generator {}", env!("VERSION")),
+        timestamp: now.timestamp().to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        runs,
+    };
+    let table = table(&results)?;
+
+    let bench_dir = data_path.join("bench");
+    std::fs::create_dir_all(&bench_dir)?;
+    // Results of earlier runs are never overwritten
+    let name = format!("bench-{}", now.strftime("%Y%m%dT%H%M%S%.6f"));
+    let table_path = bench_dir.join(format!("{name}.txt"));
+    let json_path = bench_dir.join(format!("{name}.json"));
+    write_new(&table_path, &table)?;
+    write_new(&json_path, &to_json(&meta, &results)?)?;
+
+    writeln!(msg, "\n{table}")?;
+    writeln!(msg, "Results:")?;
+    writeln!(msg, "   {}", table_path.display())?;
+    write!(msg, "   {}", json_path.display())?;
+    Ok(Some(msg))
+}
@@ -2,50 +2,97 @@
  * PTA-Generator 2025
  * SPDX-License-Identifier: Apache-2.0
  */
-use crate::cli_args::GlobalArgs;
-use crate::generators::beancount::Beancount;
+use crate::cli_args::{
+    AccountArgs, AmountArgs, AppendArgs, BeancountArgs, CommodityArgs, EquityArgs, GlobalArgs,
+};
+use crate::equity::{YearEndClosing, opening_txns};
+use crate::generators::beancount::{Beancount, BeancountDirectives};
 use crate::generators::ledger::Ledger;
 use crate::generators::tackler::Tackler;
-use crate::generators::{ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator};
-use crate::setup::{JournalFlavor, SetSize, ShardType};
+use crate::generators::{
+    Accounts, ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator,
+    ScenarioTxnGenerator, record_account,
+};
+use crate::manifest;
+use crate::scenarios::ScenarioTxn;
+use crate::setup::{JournalFlavor, SetSize};
 use crate::writers::JournalWriter;
-use jiff::{Span, Unit};
 use std::collections::BTreeSet;
 use std::fmt::Write;
 use std::ops::Add;
 
+const MODE: &str = "comm";
+
+#[allow(clippy::too_many_arguments)]
 pub fn exec(
     args: GlobalArgs,
     flavor: Option<String>,
+    amount_args: AmountArgs,
+    comm_args: CommodityArgs,
+    account_args: AccountArgs,
+    equity_args: EquityArgs,
+    bc_args: BeancountArgs,
+    append_args: AppendArgs,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     let flavor = flavor.map_or(Ok(JournalFlavor::default()), |f| {
         JournalFlavor::try_from(f.as_str())
     })?;
 
-    let set = SetSize::try_from(args.set_size.as_str())?;
-    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
-    let ts_start = args.start_ts()?;
-    let ts_end = args.stop_ts()?;
+    if bc_args.is_used() && flavor != JournalFlavor::Beancount {
+        let msg = format!(
+            "Beancount options are not supported for journal flavor '{}', use flavor '{}' instead",
+            flavor,
+            JournalFlavor::BEANCOUNT
+        );
+        return Err(msg.into());
+    }
+
+    if append_args.append.is_some()
+        && (flavor == JournalFlavor::Beancount
+            || equity_args.opening_balances
+            || equity_args.close_years)
+    {
+        let msg = format!(
+            "Append mode is not supported for journal flavor '{}' or with equity options",
+            JournalFlavor::BEANCOUNT
+        );
+        return Err(msg.into());
+    }
 
-    let span_secs = (&ts_end - &ts_start).total(Unit::Second)? as u32;
-    let step = Span::new().seconds(span_secs / set.size());
+    let ts_end = args.stop_ts()?;
+    let set_size = SetSize::try_from(args.set_size.as_str())?;
+    let amount_gen = amount_args.amount_gen(&flavor, &set_size)?;
+    let comm_gen = comm_args.commodity_gen(&flavor, amount_args.seed)?;
+    let account_gen = account_args.account_gen(&flavor)?;
 
-    let mut writer = JournalWriter::try_new(
-        flavor.clone(),
-        args.data_path.join("comm").as_path(),
-        set.clone(),
-        shard_type,
-    )?;
+    let Some((mut writer, range)) = manifest::open_set(MODE, &args, &flavor, append_args.append)?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    let set = writer.setup.txn_set.clone();
+    writer.set_format(args.journal_format()?);
+    writer.track_postings();
+    if range.append {
+        writer.read_journals()?;
+    }
 
     let txn_generator = match flavor {
         JournalFlavor::Tackler => Tackler::commodity_txn,
         JournalFlavor::Ledger => Ledger::commodity_txn,
         JournalFlavor::Beancount => Beancount::commodity_txn,
     };
+    let equity_generator = match flavor {
+        JournalFlavor::Tackler => Tackler::equity_txn,
+        JournalFlavor::Ledger => Ledger::equity_txn,
+        JournalFlavor::Beancount => Beancount::equity_txn,
+    };
 
-    let mut ts = ts_start;
+    let step = range.step_span();
+    let mut ts = range.ts.clone();
     match flavor {
         JournalFlavor::Tackler => {}
+        // Appended journal has already the include
+        JournalFlavor::Ledger if range.append => {}
         JournalFlavor::Ledger => {
             // Only single file shard mode is supported for ledger
             let journal_path = writer.setup.chart_of_accounts_path();
@@ -65,13 +112,73 @@ pub fn exec(
             writer.write_txn(&ts, 0, accs.as_str())?;
         }
     }
-    let mut accounts = BTreeSet::new();
+    let mut bc_directives = bc_args.directives.then(|| BeancountDirectives::new(&set));
+    if let Some(bc_dirs) = &bc_directives {
+        writer.write_txn(&ts, 0, bc_dirs.header(&ts)?.as_str())?;
+    }
+
+    let mut accounts = Accounts::new();
     let mut commodities = BTreeSet::new();
-    for idx in 1..=set.size() {
-        let txn = txn_generator(&set, &ts, idx)?;
+    if let Some(reference) = writer.reference().filter(|_| range.append) {
+        for a in &reference.accounts {
+            record_account(&mut accounts, a.clone(), ts.date());
+        }
+        commodities.extend(reference.commodities.iter().cloned());
+    }
+    // Opening txns have index 0, and closing txns the index after the last txn of the year
+    let write_equity_txn = |writer: &mut JournalWriter,
+                            accounts: &mut Accounts,
+                            commodities: &mut BTreeSet<String>,
+                            idx: u32,
+                            txn: &ScenarioTxn|
+     -> Result<(), Box<dyn std::error::Error>> {
+        for p in &txn.postings {
+            record_account(accounts, p.account.clone(), txn.ts.date());
+            commodities.insert(p.commodity.clone());
+        }
+        writer.write_txn(&txn.ts, idx, equity_generator(idx, txn)?.as_str())
+    };
+    if equity_args.opening_balances {
+        // Asset accounts and commodities of the set are known before txns are generated
+        let mut balances = BTreeSet::new();
+        let mut txn_ts = ts.clone();
+        for idx in range.indices.clone() {
+            let assets = account_gen.accounts(&txn_ts)?.assets;
+            balances.insert((assets, comm_gen.commodity(&txn_ts, idx).to_string()));
+            txn_ts = txn_ts.add(step);
+        }
+        for txn in opening_txns(&ts, &balances) {
+            write_equity_txn(&mut writer, &mut accounts, &mut commodities, 0, &txn)?;
+        }
+    }
+    let mut closing = equity_args.close_years.then(YearEndClosing::default);
+
+    for idx in range.indices.clone() {
+        let amount = amount_gen.amount(&ts, idx);
+        let commodity = comm_gen.commodity(&ts, idx);
+        let txn_accounts = account_gen.accounts(&ts)?;
+        let txn = txn_generator(&set, &ts, idx, &amount, commodity, &txn_accounts)?;
+
+        if let Some(closing) = &mut closing {
+            for closing_txn in closing.year_change(&ts)? {
+                write_equity_txn(
+                    &mut writer,
+                    &mut accounts,
+                    &mut commodities,
+                    idx,
+                    &closing_txn,
+                )?;
+            }
+            closing.record(&ts, &txn_accounts.expenses, &amount, commodity)?;
+        }
+
+        if let Some(bc_dirs) = &mut bc_directives {
+            let dirs = bc_dirs.txn_directives(&ts, idx, &txn.1, &amount)?;
+            writer.write_txn(&ts, idx, dirs.as_str())?;
+        }
 
         for a in txn.1.accounts {
-            accounts.insert(a);
+            record_account(&mut accounts, a, ts.date());
         }
         for a in txn.1.commodities {
             commodities.insert(a);
@@ -81,17 +188,35 @@ pub fn exec(
 
         ts = ts.add(step);
     }
+    if let Some(closing) = &mut closing {
+        let idx = range.indices.end() + 1;
+        for closing_txn in closing.finish(&ts_end)? {
+            write_equity_txn(
+                &mut writer,
+                &mut accounts,
+                &mut commodities,
+                idx,
+                &closing_txn,
+            )?;
+        }
+    }
+    writer.write_manifest(&range.manifest(MODE, &writer.setup)?)?;
 
     match flavor {
         JournalFlavor::Tackler => {
-            writer.write_config(Tackler::config(true, false, &writer.setup)?.as_str())?;
+            writer.write_config(
+                Tackler::config(true, false, amount_gen.scale, &writer.setup)?.as_str(),
+            )?;
+            writer.write_references(amount_gen.scale)?;
             writer.write_chart_of_accounts(Tackler::chart_of_accounts(&accounts)?.as_str())?;
             writer.write_chart_of_commodities(
                 Tackler::chart_of_commodities(&commodities)?.as_str(),
             )?;
 
+            writer.commit()?;
+
             let mut msg = String::new();
-            writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+            writeln!(msg, "{}", range.summary(&writer.setup.txn_set))?;
             writeln!(
                 msg,
                 "Test set is located at: {}",
@@ -103,31 +228,93 @@ pub fn exec(
                 "   tackler --config {}",
                 writer.setup.config_path().1.display()
             )?;
-            Ok(Some(msg))
+            Ok(Some(writer.archived_msg(msg)))
         }
         JournalFlavor::Ledger => {
             writer.write_chart_of_accounts(Ledger::chart_of_accounts(&accounts)?.as_str())?;
+            writer.write_references(amount_gen.scale)?;
+
+            writer.commit()?;
 
             let mut msg = String::new();
-            writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+            writeln!(msg, "{}", range.summary(&writer.setup.txn_set))?;
             writeln!(
                 msg,
                 "Test journal is located at: {}/txns",
                 writer.setup.txn_set_path().display()
             )?;
-            Ok(Some(msg))
+            Ok(Some(writer.archived_msg(msg)))
         }
         JournalFlavor::Beancount => {
+            if let Some(bc_dirs) = &mut bc_directives {
+                writer.write_txn(&ts, 0, bc_dirs.finish()?.as_str())?;
+                for (name, content) in bc_dirs.documents() {
+                    writer.write_document(name, content)?;
+                }
+                for (acc, date) in bc_dirs.closed_accounts() {
+                    if let Some(usage) = accounts.get_mut(acc) {
+                        usage.close = Some(*date);
+                    }
+                }
+                // Pads are on first txn dates of quarter's months
+                let first = accounts.values().map(|usage| usage.first).min();
+                let last = accounts.values().map(|usage| usage.last).max();
+                for date in first.into_iter().chain(last) {
+                    record_account(
+                        &mut accounts,
+                        BeancountDirectives::OPENING_ACCOUNT.to_string(),
+                        date,
+                    );
+                }
+            }
+            if bc_args.close_accounts {
+                for usage in accounts.values_mut() {
+                    if usage.close.is_none() {
+                        usage.close = Some(usage.last.tomorrow()?);
+                    }
+                }
+            }
+            let post_close_usage = bc_args.post_close_usage.unwrap_or(0);
+            let closed: Vec<_> = accounts
+                .iter()
+                .filter_map(|(acc, usage)| usage.close.map(|close| (acc.clone(), close)))
+                .collect();
+            if !closed.is_empty() {
+                for n in 1..=post_close_usage {
+                    let (acc, close) =
+                        &closed[(n - 1) as usize * closed.len() / post_close_usage as usize];
+                    let date = close.tomorrow()?;
+                    let txn = Beancount::post_close_txn(&set, date, n, acc)?;
+                    writer.write_txn(&ts, 0, txn.as_str())?;
+                    record_account(
+                        &mut accounts,
+                        Beancount::POST_CLOSE_ACCOUNT.to_string(),
+                        date,
+                    );
+                }
+            }
             writer.write_chart_of_accounts(Beancount::chart_of_accounts(&accounts)?.as_str())?;
+            // Post-close usages are deliberate errors, so there are no valid balances
+            if post_close_usage == 0 {
+                writer.write_references(amount_gen.scale)?;
+            }
+
+            writer.commit()?;
 
             let mut msg = String::new();
-            writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+            writeln!(msg, "{}", range.summary(&writer.setup.txn_set))?;
+            if post_close_usage > 0 {
+                writeln!(
+                    msg,
+                    "Injected {post_close_usage} deliberate post-close usages of accounts"
+                )?;
+            }
             writeln!(
                 msg,
                 "Test journal is located at: {}/txns",
                 writer.setup.txn_set_path().display()
             )?;
-            Ok(Some(msg))
+            Ok(Some(writer.archived_msg(msg)))
         }
     }
 }
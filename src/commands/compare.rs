@@ -0,0 +1,93 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::benchmark::compare::{Metric, Verdict, compare, csv, html, markdown};
+use crate::benchmark::results;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+pub fn exec(
+    result_paths: Vec<PathBuf>,
+    metric: String,
+    threshold: f64,
+    output: Option<PathBuf>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let metric = Metric::try_from(metric.as_str())?;
+    if !threshold.is_finite() || threshold < 0.0 {
+        let msg = format!("Invalid threshold: {threshold}, it must be zero or positive");
+        return Err(msg.into());
+    }
+    let sets = result_paths
+        .iter()
+        .map(|p| results::read(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let deltas = compare(&sets, &metric, threshold);
+
+    let output = output.unwrap_or_else(|| {
+        result_paths[0]
+            .parent()
+            .unwrap_or(Path::new("."))
+            .to_path_buf()
+    });
+    std::fs::create_dir_all(&output)?;
+    let name = format!(
+        "compare-{}-{}",
+        metric,
+        jiff::Zoned::now().strftime("%Y%m%dT%H%M%S")
+    );
+    let title = format!(
+        "Benchmark comparison ({metric}), baseline: {}",
+        sets[0].label
+    );
+    let report = markdown(&deltas, &metric)?;
+    let mut sources = String::new();
+    for set in &sets {
+        let meta = &set.meta;
+        writeln!(
+            sources,
+            "* {}: {}, {}/{}, {}",
+            set.label, meta.generator, meta.os, meta.arch, meta.timestamp
+        )?;
+        let mut versions = BTreeSet::new();
+        for r in &set.results {
+            versions.insert((&r.tool, r.version.as_deref().unwrap_or("-")));
+        }
+        for (tool, version) in versions {
+            writeln!(sources, "  * {tool}: {version}")?;
+        }
+    }
+    let reports = [
+        (
+            output.join(format!("{name}.md")),
+            format!("# {title}\n\n{sources}\n{report}"),
+        ),
+        (
+            output.join(format!("{name}.html")),
+            html(&deltas, &metric, &title)?,
+        ),
+        (output.join(format!("{name}.csv")), csv(&deltas, &metric)?),
+    ];
+    let mut msg = String::new();
+    writeln!(msg, "{report}")?;
+    writeln!(msg, "Reports:")?;
+    for (path, content) in &reports {
+        std::fs::write(path, content)?;
+        writeln!(msg, "   {}", path.display())?;
+    }
+
+    let regressions = deltas
+        .iter()
+        .filter(|d| d.verdict == Verdict::Regression)
+        .count();
+    if regressions > 0 {
+        writeln!(
+            msg,
+            "\nFound {regressions} regressions beyond threshold of {threshold}%"
+        )?;
+        return Err(msg.trim_end().into());
+    }
+    Ok(Some(msg.trim_end().to_string()))
+}
@@ -0,0 +1,64 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::generators::beancount::Beancount;
+use crate::generators::ledger::Ledger;
+use crate::generators::tackler::Tackler;
+use crate::generators::{corpus, mutations};
+use crate::setup::JournalFlavor;
+use crate::writers::{BOM, CorpusWriter};
+use std::error::Error;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+pub fn exec(data_path: PathBuf, flavor: Option<String>) -> Result<Option<String>, Box<dyn Error>> {
+    let flavors = match flavor {
+        Some(f) => vec![JournalFlavor::try_from(f.as_str())?],
+        None => vec![
+            JournalFlavor::Tackler,
+            JournalFlavor::Ledger,
+            JournalFlavor::Beancount,
+        ],
+    };
+
+    let mut msg = String::new();
+    for flavor in flavors {
+        let (cases, mutants) = match flavor {
+            JournalFlavor::Tackler => (corpus::<Tackler>(), mutations::<Tackler>()),
+            JournalFlavor::Ledger => (corpus::<Ledger>(), mutations::<Ledger>()),
+            JournalFlavor::Beancount => (corpus::<Beancount>(), mutations::<Beancount>()),
+        };
+
+        let mut writer = CorpusWriter::try_new(flavor, data_path.as_path())?;
+        let mut count = 0;
+        for (n, (name, journal)) in cases.iter().enumerate() {
+            let crlf = journal.replace('\n', "\r\n");
+            let variants = [
+                (String::new(), journal.clone()),
+                ("-bom".to_string(), format!("{BOM}{journal}")),
+                ("-crlf".to_string(), crlf.clone()),
+                ("-bom-crlf".to_string(), format!("{BOM}{crlf}")),
+            ];
+            for (suffix, content) in variants {
+                let case_name = format!("{:0>4}-{name}{suffix}", n + 1);
+                writer.write_case(case_name.as_str(), content.as_bytes())?;
+                count += 1;
+            }
+        }
+        // Mutations are only about syntax elements, so they don't have BOM and CRLF variants
+        for (n, (name, journal)) in mutants.iter().enumerate() {
+            let case_name = format!("{:0>4}-{name}", cases.len() + n + 1);
+            writer.write_case(case_name.as_str(), journal.as_bytes())?;
+            count += 1;
+        }
+        writeln!(
+            msg,
+            "Created {} corpus files for {} at: {}",
+            count,
+            writer.flavor,
+            writer.corpus_path.display()
+        )?;
+    }
+    Ok(Some(msg))
+}
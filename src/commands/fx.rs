@@ -0,0 +1,162 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::cli_args::{AmountArgs, GlobalArgs};
+use crate::exchange::ExchangeRates;
+use crate::generators::beancount::Beancount;
+use crate::generators::ledger::Ledger;
+use crate::generators::tackler::Tackler;
+use crate::generators::{
+    Accounts, ChartOfAccGenerator, ChartOfCommGenerator, FxTxnGenerator, record_account,
+};
+use crate::setup::{JournalFlavor, SetSize, ShardType};
+use crate::writers::JournalWriter;
+use jiff::{Span, Unit};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+use std::ops::Add;
+
+pub fn exec(
+    args: GlobalArgs,
+    flavor: Option<String>,
+    amount_args: AmountArgs,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let flavor = flavor.map_or(Ok(JournalFlavor::default()), |f| {
+        JournalFlavor::try_from(f.as_str())
+    })?;
+
+    let set = SetSize::try_from(args.set_size.as_str())?;
+    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
+    let ts_start = args.start_ts()?;
+    let ts_end = args.stop_ts()?;
+    let amount_gen = amount_args.amount_gen(&flavor, &set)?;
+    let rates = ExchangeRates::new(amount_args.seed);
+
+    let span_secs = (&ts_end - &ts_start).total(Unit::Second)? as u32;
+    let step = Span::new().seconds(span_secs / set.size());
+
+    let Some(mut writer) = JournalWriter::try_new(
+        flavor.clone(),
+        args.data_path.join("fx").as_path(),
+        set.clone(),
+        shard_type,
+        args.output()?,
+    )?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    writer.set_format(args.journal_format()?);
+    if flavor == JournalFlavor::Tackler {
+        writer.track_postings();
+    }
+
+    let txn_generator = match flavor {
+        JournalFlavor::Tackler => Tackler::fx_txn,
+        JournalFlavor::Ledger => Ledger::fx_txn,
+        JournalFlavor::Beancount => Beancount::fx_txn,
+    };
+    let price_generator = match flavor {
+        JournalFlavor::Tackler => Tackler::fx_price,
+        JournalFlavor::Ledger => Ledger::fx_price,
+        JournalFlavor::Beancount => Beancount::fx_price,
+    };
+    // Tackler doesn't have balancing tolerance, so its counter postings are exact
+    let exact_counter = flavor == JournalFlavor::Tackler;
+
+    let mut ts = ts_start;
+    match flavor {
+        JournalFlavor::Tackler => {}
+        JournalFlavor::Ledger => {
+            // Only single file shard mode is supported for ledger
+            let journal_path = writer.setup.chart_of_accounts_path();
+            let accs = format!(
+                "include ../conf/{}\n\n",
+                journal_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/)
+            );
+            writer.write_txn(&ts, 0, accs.as_str())?;
+        }
+        JournalFlavor::Beancount => {
+            // Only single file shard mode is supported for beancount
+            let journal_path = writer.setup.chart_of_accounts_path();
+            let accs = format!(
+                "include \"../conf/{}\"\n\n",
+                journal_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/)
+            );
+            writer.write_txn(&ts, 0, accs.as_str())?;
+        }
+    }
+
+    let mut accounts = Accounts::new();
+    let mut commodities = BTreeSet::new();
+    let mut prices = BTreeSet::new();
+    for idx in 1..=set.size() {
+        let amount = amount_gen.amount(&ts, idx);
+        let conversion = rates.conversion(&ts, idx, &amount, exact_counter)?;
+
+        if prices.insert((ts.date(), conversion.commodity)) {
+            let price = price_generator(ts.date(), conversion.commodity, &conversion.rate)?;
+            writer.write_txn(&ts, idx, price.as_str())?;
+        }
+
+        let txn = txn_generator(&set, &ts, idx, &conversion)?;
+
+        for a in txn.1.accounts {
+            record_account(&mut accounts, a, ts.date());
+        }
+        for a in txn.1.commodities {
+            commodities.insert(a);
+        }
+
+        writer.write_txn(&ts, idx, txn.0.as_str())?;
+
+        ts = ts.add(step);
+    }
+
+    let mut msg = String::new();
+    writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+    match flavor {
+        JournalFlavor::Tackler => {
+            let scale = amount_gen.scale + ExchangeRates::RATE_SCALE;
+            writer.write_config(Tackler::config(true, false, scale, &writer.setup)?.as_str())?;
+            writer.write_references(scale)?;
+            writer.write_chart_of_accounts(Tackler::chart_of_accounts(&accounts)?.as_str())?;
+            writer.write_chart_of_commodities(
+                Tackler::chart_of_commodities(&commodities)?.as_str(),
+            )?;
+
+            writeln!(
+                msg,
+                "Test set is located at: {}",
+                writer.setup.txn_set_path().display()
+            )?;
+            writeln!(msg, "You can test it with command:\n")?;
+            writeln!(
+                msg,
+                "   tackler --config {}",
+                writer.setup.config_path().1.display()
+            )?;
+        }
+        JournalFlavor::Ledger => {
+            writer.write_chart_of_accounts(Ledger::chart_of_accounts(&accounts)?.as_str())?;
+
+            writeln!(
+                msg,
+                "Test journal is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+        JournalFlavor::Beancount => {
+            writer.write_chart_of_accounts(Beancount::chart_of_accounts(&accounts)?.as_str())?;
+
+            writeln!(
+                msg,
+                "Test journal is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+    }
+    writer.commit()?;
+    Ok(Some(writer.archived_msg(msg)))
+}
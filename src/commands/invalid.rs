@@ -0,0 +1,274 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::cli_args::{AmountArgs, GlobalArgs};
+use crate::generators::beancount::Beancount;
+use crate::generators::ledger::Ledger;
+use crate::generators::tackler::Tackler;
+use crate::generators::{
+    Accounts, ChartOfAccGenerator, ChartOfCommGenerator, invalid_txn, record_account,
+};
+use crate::setup::{ErrorKind, JournalFlavor, SetSize, ShardType};
+use crate::writers::JournalWriter;
+use jiff::{Span, Unit};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::ops::Add;
+
+/// Positions (txn index) of injected errors
+///
+/// Errors are spread evenly over the set, and kinds are interleaved.
+/// For other shard types than `txn` and `hash`, truncated file is
+/// located at the last txn of the set.
+fn error_plan(
+    kinds: &[ErrorKind],
+    count: u32,
+    set: &SetSize,
+    shard_type: &ShardType,
+) -> Result<BTreeMap<u32, ErrorKind>, Box<dyn Error>> {
+    let mut plan = BTreeMap::new();
+
+    let at_end = !shard_type.txn_per_file() && kinds.contains(&ErrorKind::TruncatedFile);
+    if at_end && count > 1 {
+        let msg = format!(
+            "Error kind '{}' can be injected only once with shard type '{}'",
+            ErrorKind::TruncatedFile,
+            shard_type
+        );
+        return Err(msg.into());
+    }
+    let spread: Vec<_> = kinds
+        .iter()
+        .filter(|k| !(at_end && **k == ErrorKind::TruncatedFile))
+        .collect();
+
+    let total = spread.len() as u32 * count + u32::from(at_end);
+    if total >= set.size() {
+        let msg = format!("Too many errors ({}) for set size {}", total, set.size());
+        return Err(msg.into());
+    }
+
+    let spread_total = spread.len() as u32 * count;
+    for j in 0..spread_total {
+        let idx = (u64::from(j) + 1) * u64::from(set.size()) / (u64::from(spread_total) + 1);
+        plan.insert(idx as u32, spread[(j as usize) % spread.len()].clone());
+    }
+    if at_end {
+        plan.insert(set.size(), ErrorKind::TruncatedFile);
+    }
+    Ok(plan)
+}
+
+pub fn exec(
+    args: GlobalArgs,
+    flavor: Option<String>,
+    errors: Vec<String>,
+    error_count: u32,
+    amount_args: AmountArgs,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let flavor = flavor.map_or(Ok(JournalFlavor::default()), |f| {
+        JournalFlavor::try_from(f.as_str())
+    })?;
+
+    let kinds = if errors.is_empty() {
+        ErrorKind::supported(&flavor)
+    } else {
+        let mut kinds = Vec::new();
+        for e in errors {
+            let kind = ErrorKind::try_from(e.as_str())?;
+            if !ErrorKind::supported(&flavor).contains(&kind) {
+                let msg = format!(
+                    "Error kind '{}' is not supported for journal flavor '{}'",
+                    kind, flavor
+                );
+                return Err(msg.into());
+            }
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    };
+
+    let set = SetSize::try_from(args.set_size.as_str())?;
+    let amount_gen = amount_args.amount_gen(&flavor, &set)?;
+    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
+    let ts_start = args.start_ts()?;
+    let ts_end = args.stop_ts()?;
+
+    let plan = error_plan(&kinds, error_count, &set, &shard_type)?;
+
+    let span_secs = (&ts_end - &ts_start).total(Unit::Second)? as u32;
+    let step = Span::new().seconds(span_secs / set.size());
+
+    let Some(mut writer) = JournalWriter::try_new(
+        flavor.clone(),
+        args.data_path.join("invalid").as_path(),
+        set.clone(),
+        shard_type,
+        args.output()?,
+    )?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    writer.set_format(args.journal_format()?);
+    writer.track_lines();
+
+    let txn_generator = match flavor {
+        JournalFlavor::Tackler => invalid_txn::<Tackler>,
+        JournalFlavor::Ledger => invalid_txn::<Ledger>,
+        JournalFlavor::Beancount => invalid_txn::<Beancount>,
+    };
+
+    let mut ts = ts_start;
+    match flavor {
+        JournalFlavor::Tackler => {}
+        JournalFlavor::Ledger => {
+            // Only single file shard mode is supported for ledger
+            let accs_path = writer.setup.chart_of_accounts_path();
+            let comms_path = writer.setup.chart_of_commodities_path();
+            let includes = format!(
+                "include ../conf/{}\ninclude ../conf/{}\n\n",
+                accs_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/),
+                comms_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/)
+            );
+            writer.write_txn(&ts, 0, includes.as_str())?;
+        }
+        JournalFlavor::Beancount => {
+            // Only single file shard mode is supported for beancount
+            let accs_path = writer.setup.chart_of_accounts_path();
+            let comms_path = writer.setup.chart_of_commodities_path();
+            let includes = format!(
+                "include \"../conf/{}\"\ninclude \"../conf/{}\"\nplugin \"beancount.plugins.check_commodity\"\n\n",
+                accs_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/),
+                comms_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/)
+            );
+            writer.write_txn(&ts, 0, includes.as_str())?;
+        }
+    }
+
+    let mut expected_errors = String::new();
+    let mut accounts = Accounts::new();
+    let mut commodities = BTreeSet::new();
+    for idx in 1..=set.size() {
+        let error = plan.get(&idx);
+        let amount = amount_gen.amount(&ts, idx);
+        let txn = txn_generator(&set, &ts, idx, &amount, error)?;
+
+        for a in txn.1.accounts {
+            record_account(&mut accounts, a, ts.date());
+        }
+        for a in txn.1.commodities {
+            commodities.insert(a);
+        }
+
+        if let Some(kind) = error {
+            let (path, line) = writer.journal_position(&ts, idx, txn.0.as_str());
+            let rel_path = path
+                .strip_prefix(writer.setup.txn_set_path())
+                .unwrap_or(path.as_path());
+            writeln!(
+                expected_errors,
+                "{}:{}: {}",
+                rel_path.display(),
+                line + txn.2,
+                kind
+            )?;
+        }
+
+        writer.write_txn(&ts, idx, txn.0.as_str())?;
+
+        ts = ts.add(step);
+    }
+
+    writer.write_expected_errors(expected_errors.as_str())?;
+
+    let mut msg = String::new();
+    writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+    writeln!(msg, "Injected {} errors", plan.len())?;
+    writeln!(
+        msg,
+        "Expected errors are listed in: {}",
+        writer.setup.expected_errors_path().1.display()
+    )?;
+
+    match flavor {
+        JournalFlavor::Tackler => {
+            writer.write_config(
+                Tackler::config(true, true, amount_gen.scale, &writer.setup)?.as_str(),
+            )?;
+            writer.write_chart_of_accounts(Tackler::chart_of_accounts(&accounts)?.as_str())?;
+            writer.write_chart_of_commodities(
+                Tackler::chart_of_commodities(&commodities)?.as_str(),
+            )?;
+
+            writeln!(
+                msg,
+                "Test set is located at: {}",
+                writer.setup.txn_set_path().display()
+            )?;
+            writeln!(msg, "You can test it with command:\n")?;
+            writeln!(
+                msg,
+                "   tackler --config {}",
+                writer.setup.config_path().1.display()
+            )?;
+        }
+        JournalFlavor::Ledger => {
+            writer.write_chart_of_accounts(Ledger::chart_of_accounts(&accounts)?.as_str())?;
+            writer
+                .write_chart_of_commodities(Ledger::chart_of_commodities(&commodities)?.as_str())?;
+
+            writeln!(
+                msg,
+                "Test journal is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+        JournalFlavor::Beancount => {
+            writer.write_chart_of_accounts(Beancount::chart_of_accounts(&accounts)?.as_str())?;
+            writer.write_chart_of_commodities(
+                Beancount::chart_of_commodities(&commodities)?.as_str(),
+            )?;
+
+            writeln!(
+                msg,
+                "Test journal is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+    }
+    writer.commit()?;
+    Ok(Some(writer.archived_msg(msg)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_plan() {
+        let kinds = vec![ErrorKind::Unbalanced, ErrorKind::TruncatedFile];
+        let plan = error_plan(&kinds, 1, &SetSize::Sz1e1, &ShardType::Single).unwrap(/*:test:*/);
+        assert_eq!(
+            plan.into_iter().collect::<Vec<_>>(),
+            vec![(5, ErrorKind::Unbalanced), (10, ErrorKind::TruncatedFile)]
+        );
+
+        let plan = error_plan(&kinds, 2, &SetSize::Sz1e1, &ShardType::Txn).unwrap(/*:test:*/);
+        assert_eq!(
+            plan.into_iter().collect::<Vec<_>>(),
+            vec![
+                (2, ErrorKind::Unbalanced),
+                (4, ErrorKind::TruncatedFile),
+                (6, ErrorKind::Unbalanced),
+                (8, ErrorKind::TruncatedFile)
+            ]
+        );
+
+        assert!(error_plan(&kinds, 2, &SetSize::Sz1e1, &ShardType::Month).is_err());
+        assert!(error_plan(&kinds, 5, &SetSize::Sz1e1, &ShardType::Txn).is_err());
+    }
+}
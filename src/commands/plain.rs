@@ -3,38 +3,41 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::cli_args::GlobalArgs;
+use crate::cli_args::{AmountArgs, AppendArgs, GlobalArgs};
 use crate::generators::PlainTxnGenerator;
 use crate::generators::ledger::Ledger;
 use crate::generators::tackler::Tackler;
-use crate::setup::{JournalFlavor, SetSize, ShardType};
+use crate::manifest;
+use crate::setup::{JournalFlavor, SetSize};
 use crate::writers::JournalWriter;
-use jiff::{Span, Unit};
 use std::fmt::Write;
 use std::ops::Add;
 
+const MODE: &str = "plain";
+
 pub fn exec(
     args: GlobalArgs,
     flavor: Option<String>,
+    amount_args: AmountArgs,
+    append_args: AppendArgs,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     let flavor = flavor.map_or(Ok(JournalFlavor::default()), |f| {
         JournalFlavor::try_from(f.as_str())
     })?;
 
-    let set = SetSize::try_from(args.set_size.as_str())?;
-    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
-    let ts_start = args.start_ts()?;
-    let ts_end = args.stop_ts()?;
-
-    let span_secs = (&ts_end - &ts_start).total(Unit::Second)? as u32;
-    let step = Span::new().seconds(span_secs / set.size());
+    let set_size = SetSize::try_from(args.set_size.as_str())?;
+    let amount_gen = amount_args.amount_gen(&flavor, &set_size)?;
 
-    let mut writer = JournalWriter::try_new(
-        flavor.clone(),
-        args.data_path.join("plain").as_path(),
-        set.clone(),
-        shard_type,
-    )?;
+    let Some((mut writer, range)) = manifest::open_set(MODE, &args, &flavor, append_args.append)?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    let set = writer.setup.txn_set.clone();
+    writer.set_format(args.journal_format()?);
+    writer.track_postings();
+    if range.append {
+        writer.read_journals()?;
+    }
 
     let txn_generator = match flavor {
         JournalFlavor::Tackler => Tackler::plain_txn,
@@ -46,21 +49,29 @@ pub fn exec(
         }
     };
 
-    let mut ts = ts_start;
-    for idx in 1..=set.size() {
-        let txn = txn_generator(&set, &ts, idx)?;
+    let step = range.step_span();
+    let mut ts = range.ts.clone();
+    for idx in range.indices.clone() {
+        let amount = amount_gen.amount(&ts, idx);
+        let txn = txn_generator(&set, &ts, idx, &amount)?;
 
         writer.write_txn(&ts, idx, txn.as_str())?;
 
         ts = ts.add(step);
     }
+    writer.write_manifest(&range.manifest(MODE, &writer.setup)?)?;
 
     match flavor {
         JournalFlavor::Tackler => {
-            writer.write_config(Tackler::config(false, false, &writer.setup)?.as_str())?;
+            writer.write_config(
+                Tackler::config(false, false, amount_gen.scale, &writer.setup)?.as_str(),
+            )?;
+            writer.write_references(amount_gen.scale)?;
+
+            writer.commit()?;
 
             let mut msg = String::new();
-            writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+            writeln!(msg, "{}", range.summary(&writer.setup.txn_set))?;
             writeln!(
                 msg,
                 "Test set is located at: {}",
@@ -72,17 +83,21 @@ pub fn exec(
                 "   tackler --config {}",
                 writer.setup.config_path().1.display()
             )?;
-            Ok(Some(msg))
+            Ok(Some(writer.archived_msg(msg)))
         }
         JournalFlavor::Ledger => {
+            writer.write_references(amount_gen.scale)?;
+
+            writer.commit()?;
+
             let mut msg = String::new();
-            writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+            writeln!(msg, "{}", range.summary(&writer.setup.txn_set))?;
             writeln!(
                 msg,
                 "Test journal is located at: {}/txns",
                 writer.setup.txn_set_path().display()
             )?;
-            Ok(Some(msg))
+            Ok(Some(writer.archived_msg(msg)))
         }
         JournalFlavor::Beancount => {
             let msg =
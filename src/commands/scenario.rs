@@ -0,0 +1,153 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::cli_args::{GlobalArgs, SeedArgs};
+use crate::generators::beancount::Beancount;
+use crate::generators::ledger::Ledger;
+use crate::generators::tackler::Tackler;
+use crate::generators::{
+    Accounts, ChartOfAccGenerator, ChartOfCommGenerator, ScenarioTxnGenerator, record_account,
+};
+use crate::scenarios::{SCALE, Scenario, generate};
+use crate::setup::{JournalFlavor, SetSize, ShardType};
+use crate::writers::JournalWriter;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+
+pub fn exec(
+    args: GlobalArgs,
+    flavor: Option<String>,
+    seed_args: SeedArgs,
+    scenario: Scenario,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let flavor = flavor.map_or(Ok(JournalFlavor::default()), |f| {
+        JournalFlavor::try_from(f.as_str())
+    })?;
+
+    let set = SetSize::try_from(args.set_size.as_str())?;
+    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
+    let ts_start = args.start_ts()?;
+    let ts_end = args.stop_ts()?;
+
+    let txns = generate(&scenario, &ts_start, &ts_end, set.size(), seed_args.seed)?;
+
+    let Some(mut writer) = JournalWriter::try_new(
+        flavor.clone(),
+        args.data_path.join(scenario.to_string()).as_path(),
+        set.clone(),
+        shard_type,
+        args.output()?,
+    )?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    writer.set_format(args.journal_format()?);
+    if flavor == JournalFlavor::Tackler {
+        writer.track_postings();
+    }
+
+    let txn_generator = match flavor {
+        JournalFlavor::Tackler => Tackler::scenario_txn,
+        JournalFlavor::Ledger => Ledger::scenario_txn,
+        JournalFlavor::Beancount => Beancount::scenario_txn,
+    };
+    let commodity_name = match flavor {
+        JournalFlavor::Tackler => Tackler::scenario_commodity,
+        JournalFlavor::Ledger => Ledger::scenario_commodity,
+        JournalFlavor::Beancount => Beancount::scenario_commodity,
+    };
+
+    match flavor {
+        JournalFlavor::Tackler => {}
+        JournalFlavor::Ledger => {
+            // Only single file shard mode is supported for ledger
+            let accs_path = writer.setup.chart_of_accounts_path();
+            let comms_path = writer.setup.chart_of_commodities_path();
+            let includes = format!(
+                "include ../conf/{}\ninclude ../conf/{}\n\n",
+                accs_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/),
+                comms_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/)
+            );
+            writer.write_txn(&ts_start, 0, includes.as_str())?;
+        }
+        JournalFlavor::Beancount => {
+            // Only single file shard mode is supported for beancount
+            let accs_path = writer.setup.chart_of_accounts_path();
+            let comms_path = writer.setup.chart_of_commodities_path();
+            let includes = format!(
+                "include \"../conf/{}\"\ninclude \"../conf/{}\"\n\n",
+                accs_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/),
+                comms_path.1.file_name().unwrap(/*:ok:*/).to_str().unwrap(/*:ok:*/)
+            );
+            writer.write_txn(&ts_start, 0, includes.as_str())?;
+        }
+    }
+
+    let mut accounts = Accounts::new();
+    let mut commodities = BTreeSet::new();
+    for (i, txn) in txns.iter().enumerate() {
+        let idx = i as u32 + 1;
+        for p in &txn.postings {
+            record_account(&mut accounts, p.account.clone(), txn.ts.date());
+            commodities.insert(commodity_name(&p.commodity));
+        }
+        writer.write_txn(&txn.ts, idx, txn_generator(idx, txn)?.as_str())?;
+    }
+
+    let mut msg = String::new();
+    writeln!(
+        msg,
+        "Created {} test set ({scenario}, {} txns)",
+        writer.setup.txn_set,
+        txns.len()
+    )?;
+    match flavor {
+        JournalFlavor::Tackler => {
+            writer.write_config(Tackler::config(true, false, SCALE, &writer.setup)?.as_str())?;
+            writer.write_references(SCALE)?;
+            writer.write_chart_of_accounts(Tackler::chart_of_accounts(&accounts)?.as_str())?;
+            writer.write_chart_of_commodities(
+                Tackler::chart_of_commodities(&commodities)?.as_str(),
+            )?;
+
+            writeln!(
+                msg,
+                "Test set is located at: {}",
+                writer.setup.txn_set_path().display()
+            )?;
+            writeln!(msg, "You can test it with command:\n")?;
+            writeln!(
+                msg,
+                "   tackler --config {}",
+                writer.setup.config_path().1.display()
+            )?;
+        }
+        JournalFlavor::Ledger => {
+            writer.write_chart_of_accounts(Ledger::chart_of_accounts(&accounts)?.as_str())?;
+            writer
+                .write_chart_of_commodities(Ledger::chart_of_commodities(&commodities)?.as_str())?;
+
+            writeln!(
+                msg,
+                "Test journal is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+        JournalFlavor::Beancount => {
+            writer.write_chart_of_accounts(Beancount::chart_of_accounts(&accounts)?.as_str())?;
+            writer.write_chart_of_commodities(
+                Beancount::chart_of_commodities(&commodities)?.as_str(),
+            )?;
+
+            writeln!(
+                msg,
+                "Test journal is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+    }
+    writer.commit()?;
+    Ok(Some(writer.archived_msg(msg)))
+}
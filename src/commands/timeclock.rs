@@ -0,0 +1,115 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::cli_args::{GlobalArgs, SeedArgs};
+use crate::generators::TimeTxnGenerator;
+use crate::generators::ledger::Ledger;
+use crate::generators::tackler::Tackler;
+use crate::setup::{JournalFlavor, SetSize, ShardType, TimeFormat};
+use crate::timetrack::{HOURS_SCALE, sessions};
+use crate::writers::JournalWriter;
+use std::error::Error;
+use std::fmt::Write;
+
+pub fn exec(
+    args: GlobalArgs,
+    flavor: Option<String>,
+    time_format: Option<String>,
+    per_day: Option<u32>,
+    projects: u32,
+    seed_args: SeedArgs,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let flavor = flavor.map_or(Ok(JournalFlavor::default()), |f| {
+        JournalFlavor::try_from(f.as_str())
+    })?;
+    if time_format.is_some() && flavor != JournalFlavor::Ledger {
+        let msg = format!(
+            "Time format is not supported for journal flavor '{}', use flavor '{}' instead",
+            flavor,
+            JournalFlavor::LEDGER
+        );
+        return Err(msg.into());
+    }
+    let time_format = time_format.map_or(Ok(TimeFormat::default()), |f| {
+        TimeFormat::try_from(f.as_str())
+    })?;
+
+    let set = SetSize::try_from(args.set_size.as_str())?;
+    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
+    let ts_start = args.start_ts()?;
+    let ts_end = args.stop_ts()?;
+
+    let sessions = sessions(
+        &ts_start,
+        &ts_end,
+        set.size(),
+        per_day,
+        projects,
+        seed_args.seed,
+    )?;
+
+    let Some(mut writer) = JournalWriter::try_new(
+        flavor.clone(),
+        args.data_path.join("timeclock").as_path(),
+        set.clone(),
+        shard_type,
+        args.output()?,
+    )?
+    else {
+        return Ok(Some(JournalWriter::SET_KEPT.to_string()));
+    };
+    writer.set_format(args.journal_format()?);
+    if flavor == JournalFlavor::Tackler {
+        writer.track_postings();
+    }
+
+    let txn_generator = match flavor {
+        JournalFlavor::Tackler => Tackler::time_txn,
+        JournalFlavor::Ledger => {
+            writer.setup.time_format = Some(time_format.clone());
+            Ledger::time_txn
+        }
+        JournalFlavor::Beancount => {
+            let msg = "Timeclock mode for Beancount is not supported".to_string();
+            return Err(msg.into());
+        }
+    };
+
+    for session in &sessions {
+        let txn = txn_generator(session, &time_format)?;
+        writer.write_txn(&session.start, session.index, txn.as_str())?;
+    }
+
+    let mut msg = String::new();
+    writeln!(msg, "Created {} test set", writer.setup.txn_set)?;
+    match flavor {
+        JournalFlavor::Tackler => {
+            writer.write_config(
+                Tackler::config(false, false, HOURS_SCALE, &writer.setup)?.as_str(),
+            )?;
+            writer.write_references(HOURS_SCALE)?;
+
+            writeln!(
+                msg,
+                "Test set is located at: {}",
+                writer.setup.txn_set_path().display()
+            )?;
+            writeln!(msg, "You can test it with command:\n")?;
+            writeln!(
+                msg,
+                "   tackler --config {}",
+                writer.setup.config_path().1.display()
+            )?;
+        }
+        _ => {
+            writeln!(
+                msg,
+                "Test {time_format} file is located at: {}/txns",
+                writer.setup.txn_set_path().display()
+            )?;
+        }
+    }
+    writer.commit()?;
+    Ok(Some(writer.archived_msg(msg)))
+}
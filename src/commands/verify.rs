@@ -0,0 +1,110 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::setup::JournalFlavor;
+use crate::verifier::{UNRESOLVED_INCLUDE, Verifier, journal_files, tackler};
+use std::error::Error;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Maximum count of listed findings
+const MAX_FINDINGS: usize = 100;
+
+/// Load declarations of tackler config, which is next to the set directory
+///
+/// Config is optional, but if it exists, then its input directory and
+/// charts of accounts and commodities must exist.
+fn tackler_config(verifier: &mut Verifier, set_path: &Path) -> Result<(), Box<dyn Error>> {
+    let toml_path = set_path.with_extension("toml");
+    if !toml_path.is_file() {
+        return Ok(());
+    }
+    let toml_dir = toml_path.parent().unwrap_or(Path::new("."));
+    let config = tackler::config(&std::fs::read_to_string(&toml_path)?);
+
+    let resolve = |verifier: &mut Verifier, path: &Option<PathBuf>| {
+        let path = path.as_ref().map(|p| toml_dir.join(p))?;
+        if path.exists() {
+            Some(path)
+        } else {
+            let msg = format!("path '{}' not found", path.display());
+            verifier.report(&toml_path, 1, (UNRESOLVED_INCLUDE, msg));
+            None
+        }
+    };
+    resolve(verifier, &config.input);
+    if !config.strict {
+        return Ok(());
+    }
+    if let Some(path) = resolve(verifier, &config.accounts) {
+        for a in tackler::names(&std::fs::read_to_string(path)?) {
+            verifier.declare_account(&a);
+        }
+    }
+    if let Some(path) = resolve(verifier, &config.commodities) {
+        for c in tackler::names(&std::fs::read_to_string(path)?) {
+            verifier.declare_commodity(&c);
+        }
+    }
+    Ok(())
+}
+
+pub fn exec(set_path: PathBuf, flavor: Option<String>) -> Result<Option<String>, Box<dyn Error>> {
+    if !set_path.is_dir() {
+        let msg = format!("Test set not found: '{}'", set_path.display());
+        return Err(msg.into());
+    }
+    let flavors = match &flavor {
+        Some(f) => vec![JournalFlavor::try_from(f.as_str())?],
+        None => vec![
+            JournalFlavor::Tackler,
+            JournalFlavor::Ledger,
+            JournalFlavor::Beancount,
+        ],
+    };
+
+    let mut msg = String::new();
+    let mut findings = Vec::new();
+    for flavor in flavors {
+        let journals = journal_files(&set_path.join("txns"), &flavor)?;
+        if journals.is_empty() {
+            continue;
+        }
+        let mut verifier = Verifier::new(flavor.clone(), &set_path);
+        if flavor == JournalFlavor::Tackler {
+            tackler_config(&mut verifier, &set_path)?;
+        }
+        for journal in &journals {
+            verifier.verify_file(journal)?;
+        }
+        verifier.finish();
+        writeln!(
+            msg,
+            "Verified {} journal: {} files, {} txns, {} errors",
+            flavor,
+            verifier.files,
+            verifier.txns,
+            verifier.findings.len()
+        )?;
+        findings.append(&mut verifier.findings);
+    }
+    if msg.is_empty() {
+        let flavor = flavor.map_or(String::new(), |f| format!("{f} "));
+        let msg = format!(
+            "No {flavor}journals found at: {}",
+            set_path.join("txns").display()
+        );
+        return Err(msg.into());
+    }
+    if findings.is_empty() {
+        return Ok(Some(msg));
+    }
+    for f in findings.iter().take(MAX_FINDINGS) {
+        writeln!(msg, "{f}")?;
+    }
+    if findings.len() > MAX_FINDINGS {
+        writeln!(msg, "... and {} more", findings.len() - MAX_FINDINGS)?;
+    }
+    Err(msg.trim_end().into())
+}
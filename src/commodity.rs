@@ -0,0 +1,294 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::generators::COMMODITY_NAMES;
+use crate::prng::Prng;
+use crate::setup::JournalFlavor;
+use jiff::Zoned;
+use jiff::civil::Date;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Day zero of commodities assigned by day, see `CommodityGen::commodity`
+const EPOCH: Date = jiff::civil::date(1970, 1, 1);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CommodityNaming {
+    #[default]
+    Iso,
+    Ticker,
+    Quoted,
+}
+
+impl CommodityNaming {
+    pub const ISO: &'static str = "iso";
+    pub const TICKER: &'static str = "ticker";
+    pub const QUOTED: &'static str = "quoted";
+
+    /// Is naming scheme supported by flavor
+    ///
+    /// Quoted commodity names are supported only by (h)ledger.
+    pub fn supported(&self, flavor: &JournalFlavor) -> bool {
+        match self {
+            CommodityNaming::Quoted => *flavor == JournalFlavor::Ledger,
+            _ => true,
+        }
+    }
+}
+
+impl TryFrom<&str> for CommodityNaming {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::ISO => Ok(CommodityNaming::Iso),
+            Self::TICKER => Ok(CommodityNaming::Ticker),
+            Self::QUOTED => Ok(CommodityNaming::Quoted),
+            _ => Err(format!(
+                "Unknown commodity naming: {}, supported names are: {}, {}, {}",
+                value,
+                Self::ISO,
+                Self::TICKER,
+                Self::QUOTED
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for CommodityNaming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommodityNaming::Iso => write!(f, "{}", Self::ISO),
+            CommodityNaming::Ticker => write!(f, "{}", Self::TICKER),
+            CommodityNaming::Quoted => write!(f, "{}", Self::QUOTED),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CommodityAssign {
+    #[default]
+    Day,
+    RoundRobin,
+    Random,
+    PerAccount,
+}
+
+impl CommodityAssign {
+    pub const DAY: &'static str = "day";
+    pub const ROUND_ROBIN: &'static str = "round-robin";
+    pub const RANDOM: &'static str = "random";
+    pub const PER_ACCOUNT: &'static str = "per-account";
+}
+
+impl TryFrom<&str> for CommodityAssign {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::DAY => Ok(CommodityAssign::Day),
+            Self::ROUND_ROBIN => Ok(CommodityAssign::RoundRobin),
+            Self::RANDOM => Ok(CommodityAssign::Random),
+            Self::PER_ACCOUNT => Ok(CommodityAssign::PerAccount),
+            _ => Err(format!(
+                "Unknown commodity assignment: {}, supported assignments are: {}, {}, {}, {}",
+                value,
+                Self::DAY,
+                Self::ROUND_ROBIN,
+                Self::RANDOM,
+                Self::PER_ACCOUNT
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for CommodityAssign {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommodityAssign::Day => write!(f, "{}", Self::DAY),
+            CommodityAssign::RoundRobin => write!(f, "{}", Self::ROUND_ROBIN),
+            CommodityAssign::Random => write!(f, "{}", Self::RANDOM),
+            CommodityAssign::PerAccount => write!(f, "{}", Self::PER_ACCOUNT),
+        }
+    }
+}
+
+/// Commodity universe and assignment of commodities to txns
+///
+/// With 31 ISO-like commodities assigned by day (the defaults),
+/// the commodity is the same as `generators::commodity_name`.
+#[derive(Debug, Clone)]
+pub struct CommodityGen {
+    names: Vec<String>,
+    assign: CommodityAssign,
+    seed: u64,
+}
+
+impl CommodityGen {
+    /// PRNG stream for commodities
+    const STREAM: u64 = 2;
+    /// Maximum count of commodities
+    pub const MAX_COUNT: u32 = 10_000;
+
+    pub fn try_new(
+        count: u32,
+        naming: CommodityNaming,
+        assign: CommodityAssign,
+        seed: u64,
+    ) -> Result<CommodityGen, Box<dyn Error>> {
+        if !(1..=Self::MAX_COUNT).contains(&count) {
+            let msg = format!(
+                "Invalid count of commodities: {count}, it must be between 1 and {}",
+                Self::MAX_COUNT
+            );
+            return Err(msg.into());
+        }
+        let names = match naming {
+            CommodityNaming::Iso => iso_names(count as usize),
+            CommodityNaming::Ticker => (0..count).map(|i| ticker_name(i + 26)).collect(),
+            CommodityNaming::Quoted => (1..=count).map(|i| format!("\"Fund {i} A\"")).collect(),
+        };
+        Ok(CommodityGen {
+            names,
+            assign,
+            seed,
+        })
+    }
+
+    /// Commodity of txn
+    ///
+    /// With `CommodityAssign::Day` and at most 31 commodities, the commodity is
+    /// based on day of month. With more commodities, it's based on the count of days
+    /// since 1970-01-01, so consecutive days have different commodities, and all of
+    /// them are used, if the set spans at least as many days as there are commodities.
+    pub fn commodity(&self, ts: &Zoned, index: u32) -> &str {
+        let count = self.names.len();
+        let i = match self.assign {
+            CommodityAssign::Day if count <= COMMODITY_NAMES.len() => {
+                (ts.day() - 1) as usize % count
+            }
+            CommodityAssign::Day => {
+                let days = ts.date().since(EPOCH).expect("IE: span of days");
+                days.get_days().rem_euclid(count as i32) as usize
+            }
+            CommodityAssign::RoundRobin => (index as usize).saturating_sub(1) % count,
+            CommodityAssign::Random => Prng::new(self.seed, Self::STREAM, u64::from(index))
+                .next_below(count as u128) as usize,
+            CommodityAssign::PerAccount => {
+                // Expense account is based on the date of txn
+                let account = (ts.year() as u64) * 10_000 + (ts.month() as u64) * 100;
+                let account = account + ts.day() as u64;
+                Prng::new(self.seed, Self::STREAM, account).next_below(count as u128) as usize
+            }
+        };
+        self.names[i].as_str()
+    }
+}
+
+/// Three letter uppercase codes, starting with the default commodities
+fn iso_names(count: usize) -> Vec<String> {
+    let mut names: Vec<String> = COMMODITY_NAMES
+        .iter()
+        .take(count)
+        .map(|c| c.to_string())
+        .collect();
+    let mut n = 0;
+    while names.len() < count {
+        let code = ticker_name(n + 702);
+        if !COMMODITY_NAMES.contains(&code.as_str()) {
+            names.push(code);
+        }
+        n += 1;
+    }
+    names
+}
+
+/// Bijective base-26 name (0 = A, 25 = Z, 26 = AA, ...)
+fn ticker_name(n: u32) -> String {
+    let mut name = Vec::new();
+    let mut n = n + 1;
+    while n > 0 {
+        n -= 1;
+        name.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap(/*:ok: ASCII */)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commodity::{CommodityAssign, CommodityGen, CommodityNaming, ticker_name};
+    use crate::generators::commodity_name;
+    use jiff::Timestamp;
+    use jiff::tz::TimeZone;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_ticker_name() {
+        assert_eq!(ticker_name(0), "A");
+        assert_eq!(ticker_name(25), "Z");
+        assert_eq!(ticker_name(26), "AA");
+        assert_eq!(ticker_name(701), "ZZ");
+        assert_eq!(ticker_name(702), "AAA");
+    }
+
+    #[test]
+    fn test_default() {
+        let comm_gen = CommodityGen::try_new(31, CommodityNaming::Iso, CommodityAssign::Day, 0).unwrap(/*:test:*/);
+        for day in ["01", "15", "31"] {
+            let ts: Timestamp = format!("2024-12-{day}T23:58:00Z").parse().unwrap(/*:test:*/);
+            let ts = ts.to_zoned(TimeZone::UTC);
+            assert_eq!(comm_gen.commodity(&ts, 1), commodity_name(&ts));
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        for naming in [
+            CommodityNaming::Iso,
+            CommodityNaming::Ticker,
+            CommodityNaming::Quoted,
+        ] {
+            let comm_gen = CommodityGen::try_new(10_000, naming, CommodityAssign::RoundRobin, 0).unwrap(/*:test:*/);
+            let names: HashSet<_> = comm_gen.names.iter().collect();
+            assert_eq!(names.len(), 10_000);
+        }
+        let comm_gen = CommodityGen::try_new(2, CommodityNaming::Quoted, CommodityAssign::Day, 0).unwrap(/*:test:*/);
+        assert_eq!(comm_gen.names, vec!["\"Fund 1 A\"", "\"Fund 2 A\""]);
+
+        assert!(CommodityGen::try_new(0, CommodityNaming::Iso, CommodityAssign::Day, 0).is_err());
+    }
+
+    #[test]
+    fn test_assign() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+
+        let comm_gen = CommodityGen::try_new(3, CommodityNaming::Ticker, CommodityAssign::RoundRobin, 0).unwrap(/*:test:*/);
+        let comms: Vec<_> = (1..=4).map(|i| comm_gen.commodity(&ts, i)).collect();
+        assert_eq!(comms, vec!["AA", "AB", "AC", "AA"]);
+
+        let comm_gen = CommodityGen::try_new(100, CommodityNaming::Iso, CommodityAssign::PerAccount, 1).unwrap(/*:test:*/);
+        assert_eq!(comm_gen.commodity(&ts, 1), comm_gen.commodity(&ts, 2));
+
+        // more commodities than days of month, all of them are used by consecutive days
+        let comm_gen = CommodityGen::try_new(100, CommodityNaming::Ticker, CommodityAssign::Day, 0).unwrap(/*:test:*/);
+        let comms: HashSet<_> = (0..100)
+            .map(|d| {
+                comm_gen
+                    .commodity(
+                        &ts.checked_add(jiff::Span::new().days(d)).unwrap(/*:test:*/),
+                        1,
+                    )
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(comms.len(), 100);
+
+        let comm_gen = CommodityGen::try_new(100, CommodityNaming::Iso, CommodityAssign::Random, 1).unwrap(/*:test:*/);
+        let comms: HashSet<_> = (1..=100).map(|i| comm_gen.commodity(&ts, i)).collect();
+        assert!(comms.len() > 10);
+    }
+}
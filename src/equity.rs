@@ -0,0 +1,195 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use crate::reference::checked_sum;
+use crate::scenarios::ScenarioTxn;
+use jiff::Zoned;
+use jiff::civil::date;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+/// Counter account of opening balances
+pub const OPENING_ACCOUNT: &str = "Equity:Opening-Balances";
+/// Counter account of year-end closing
+pub const RETAINED_EARNINGS: &str = "Equity:Retained-Earnings";
+/// Opening balance of each commodity
+const OPENING_AMOUNT: i128 = 1000;
+
+/// Opening balance txns at `ts`, one txn for each commodity
+///
+/// There is an opening balance for each pair of asset account and commodity,
+/// which are used by the generated txns of the set. Tackler doesn't allow
+/// several commodities in a txn without prices, so commodities are in own txns.
+pub fn opening_txns(ts: &Zoned, balances: &BTreeSet<(String, String)>) -> Vec<ScenarioTxn> {
+    let mut accounts: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (account, comm) in balances {
+        accounts.entry(comm).or_default().push(account);
+    }
+    accounts
+        .into_iter()
+        .map(|(comm, accounts)| {
+            let mut txn = ScenarioTxn::new(ts.clone(), &format!("Opening balances of {comm}"));
+            for account in &accounts {
+                txn = txn.amount_posting(account, Amount::new(OPENING_AMOUNT, 0), comm);
+            }
+            let total = OPENING_AMOUNT * accounts.len() as i128;
+            txn.amount_posting(OPENING_ACCOUNT, Amount::new(-total, 0), comm)
+        })
+        .collect()
+}
+
+/// Book-keeping of income and expense accounts for year-end closing
+///
+/// Balances are recorded per account and commodity, and at the end of year
+/// they are closed into retained earnings. Txns must be recorded
+/// in chronological order.
+#[derive(Default)]
+pub struct YearEndClosing {
+    year: Option<i16>,
+    balances: BTreeMap<(String, String), Amount>,
+}
+
+impl YearEndClosing {
+    /// Closing txns of the previous year, if `ts` is the first txn of a new year
+    ///
+    /// This must be called before the txn at `ts` is recorded.
+    pub fn year_change(&mut self, ts: &Zoned) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+        match self.year {
+            Some(year) if year < ts.year() => self.closing_txns(ts),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Record posting of income or expense account
+    pub fn record(
+        &mut self,
+        ts: &Zoned,
+        account: &str,
+        amount: &Amount,
+        commodity: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.year = Some(ts.year());
+        let balance = self
+            .balances
+            .entry((account.to_string(), commodity.to_string()))
+            .or_insert(Amount::new(0, amount.scale));
+        *balance = balance
+            .checked_add(amount)
+            .ok_or_else(|| format!("Balance of account '{account}' is too large"))?;
+        Ok(())
+    }
+
+    /// Closing txns of the last year, if the end of year is before `stop`
+    pub fn finish(&mut self, stop: &Zoned) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+        match self.year {
+            Some(year) if Self::year_end(stop, year)? < *stop => self.closing_txns(stop),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Last second of year, in the timezone of `ts`
+    fn year_end(ts: &Zoned, year: i16) -> Result<Zoned, Box<dyn Error>> {
+        Ok(date(year, 12, 31)
+            .at(23, 59, 59, 0)
+            .to_zoned(ts.time_zone().clone())?)
+    }
+
+    /// Closing txns of the year, one txn for each commodity (see `opening_txns`)
+    fn closing_txns(&mut self, ts: &Zoned) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+        let Some(year) = self.year.take() else {
+            return Ok(Vec::new());
+        };
+        let year_end = Self::year_end(ts, year)?;
+        let mut by_commodity: BTreeMap<String, Vec<(String, Amount)>> = BTreeMap::new();
+        for ((account, commodity), balance) in std::mem::take(&mut self.balances) {
+            if balance.units != 0 {
+                by_commodity
+                    .entry(commodity)
+                    .or_default()
+                    .push((account, balance));
+            }
+        }
+        let mut txns = Vec::new();
+        for (commodity, balances) in by_commodity {
+            let mut txn = ScenarioTxn::new(
+                year_end.clone(),
+                &format!("Closing of year {year} for {commodity}"),
+            );
+            let mut retained = Amount::new(0, 0);
+            for (account, balance) in balances {
+                txn = txn.amount_posting(&account, balance.neg(), &commodity);
+                retained = checked_sum(&retained, &balance)
+                    .ok_or_else(|| format!("Retained earnings of year {year} are too large"))?;
+            }
+            txns.push(txn.amount_posting(RETAINED_EARNINGS, retained, &commodity));
+        }
+        Ok(txns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::Amount;
+    use crate::equity::{OPENING_ACCOUNT, RETAINED_EARNINGS, YearEndClosing, opening_txns};
+    use jiff::Zoned;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_opening() {
+        let ts: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let balances: BTreeSet<_> = [
+            ("Assets:Ay2024:Am01", "CAA"),
+            ("Assets:Ay2024:Am01", "CAB"),
+            ("Assets:Ay2024:Am02", "CAA"),
+        ]
+        .iter()
+        .map(|(a, c)| (a.to_string(), c.to_string()))
+        .collect();
+        let txns = opening_txns(&ts, &balances);
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0].desc, "Opening balances of CAA");
+        assert_eq!(txns[0].postings.len(), 3);
+        assert_eq!(txns[0].postings[0].account, "Assets:Ay2024:Am01");
+        assert_eq!(txns[0].postings[1].account, "Assets:Ay2024:Am02");
+        assert_eq!(txns[0].postings[2].account, OPENING_ACCOUNT);
+        assert_eq!(txns[0].postings[2].amount.to_string(), "-2000");
+        assert_eq!(txns[1].postings[1].commodity, "CAB");
+        assert!(txns.iter().all(|t| t.is_balanced()));
+    }
+
+    #[test]
+    fn test_closing() {
+        let ts1: Zoned = "2024-03-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let ts2: Zoned = "2025-02-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2026-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let amount = Amount::new(15, 1);
+
+        let mut closing = YearEndClosing::default();
+        assert!(closing.year_change(&ts1).unwrap(/*:test:*/).is_empty());
+        closing.record(&ts1, "Expenses:A", &amount, "CAA").unwrap(/*:test:*/);
+        closing.record(&ts1, "Expenses:B", &amount, "CAA").unwrap(/*:test:*/);
+        closing.record(&ts1, "Expenses:B", &amount, "CAB").unwrap(/*:test:*/);
+
+        let txns = closing.year_change(&ts2).unwrap(/*:test:*/);
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0].ts.to_string(), "2024-12-31T23:59:59+00:00[UTC]");
+        assert_eq!(txns[0].desc, "Closing of year 2024 for CAA");
+        assert_eq!(txns[0].postings.len(), 3);
+        assert_eq!(txns[0].postings[0].amount.to_string(), "-1.5");
+        assert_eq!(txns[0].postings[2].account, RETAINED_EARNINGS);
+        assert_eq!(txns[0].postings[2].amount.to_string(), "3.0");
+        assert_eq!(txns[1].postings[1].commodity, "CAB");
+        assert!(txns.iter().all(|t| t.is_balanced()));
+
+        closing.record(&ts2, "Expenses:A", &amount, "CAA").unwrap(/*:test:*/);
+        let txns = closing.finish(&stop).unwrap(/*:test:*/);
+        assert_eq!(txns[0].desc, "Closing of year 2025 for CAA");
+
+        // Year has not ended before stop
+        let mut closing = YearEndClosing::default();
+        closing.record(&ts2, "Expenses:A", &amount, "CAA").unwrap(/*:test:*/);
+        assert!(closing.finish(&ts2).unwrap(/*:test:*/).is_empty());
+    }
+}
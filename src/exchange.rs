@@ -0,0 +1,172 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use crate::prng::Prng;
+use jiff::Zoned;
+use jiff::civil::Date;
+use std::error::Error;
+
+/// Currency of asset accounts, the other side of conversion
+pub const BASE_CURRENCY: &str = "EUR";
+
+/// Foreign currencies with their reference rates in base currency
+const FX_CURRENCIES: [(&str, f64); 5] = [
+    ("USD", 0.92),
+    ("GBP", 1.17),
+    ("JPY", 0.0062),
+    ("SEK", 0.088),
+    ("CHF", 1.04),
+];
+
+/// How the price of conversion is expressed
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceStyle {
+    /// Unit price (`@`)
+    Unit,
+    /// Total price (`@@`, tackler: `=`)
+    Total,
+}
+
+/// Currency conversion of txn
+///
+/// The foreign `amount` is converted to base currency with `rate`,
+/// and the `total` is exact product of those. If `counter` is set,
+/// the base currency posting is explicit, otherwise it is implicit.
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub amount: Amount,
+    pub commodity: &'static str,
+    pub rate: Amount,
+    pub total: Amount,
+    pub style: PriceStyle,
+    pub counter: Option<Amount>,
+}
+
+/// Deterministic daily exchange rates
+///
+/// Each currency has a reference rate, and daily rate varies
+/// randomly +/- 5% around it. The same seed will produce the same rates.
+pub struct ExchangeRates {
+    seed: u64,
+}
+
+impl ExchangeRates {
+    /// PRNG stream for exchange rates
+    const STREAM: u64 = 3;
+    /// Scale of exchange rates
+    pub const RATE_SCALE: u32 = 6;
+    /// Scale of rounded base currency amounts
+    pub const COUNTER_SCALE: u32 = 2;
+
+    pub fn new(seed: u64) -> Self {
+        ExchangeRates { seed }
+    }
+
+    /// Rate of foreign currency in base currency on date
+    pub fn rate(&self, date: Date, currency: usize) -> Amount {
+        let day = (date.year() as u64) * 10_000 + (date.month() as u64) * 100 + date.day() as u64;
+        let mut rng = Prng::new(
+            self.seed,
+            Self::STREAM,
+            day * FX_CURRENCIES.len() as u64 + currency as u64,
+        );
+        let rate = FX_CURRENCIES[currency].1 * (0.95 + 0.1 * rng.next_f64());
+        let units = (rate * 10f64.powi(Self::RATE_SCALE as i32)).round() as i128;
+        Amount::new(units.max(1), Self::RATE_SCALE)
+    }
+
+    /// Conversion of txn
+    ///
+    /// Currency is selected round-robin by txn index, and the price style
+    /// and counter posting alternate by index:
+    /// unit price, total price, and unit price with explicit counter posting.
+    /// The explicit counter posting is rounded to `COUNTER_SCALE`,
+    /// so it will leave a rounding residual, unless `exact_counter` is set.
+    pub fn conversion(
+        &self,
+        ts: &Zoned,
+        index: u32,
+        amount: &Amount,
+        exact_counter: bool,
+    ) -> Result<Conversion, Box<dyn Error>> {
+        let currency = (index as usize).saturating_sub(1) % FX_CURRENCIES.len();
+        let rate = self.rate(ts.date(), currency);
+        let total = amount
+            .checked_mul(&rate)
+            .ok_or_else(|| format!("Converted amount of txn {index} is too large"))?;
+
+        let (style, counter) = match index % 3 {
+            1 => (PriceStyle::Unit, None),
+            2 => (PriceStyle::Total, None),
+            _ => {
+                let counter = if exact_counter {
+                    total.neg()
+                } else {
+                    total.round(Self::COUNTER_SCALE).neg()
+                };
+                (PriceStyle::Unit, Some(counter))
+            }
+        };
+        Ok(Conversion {
+            amount: *amount,
+            commodity: FX_CURRENCIES[currency].0,
+            rate,
+            total,
+            style,
+            counter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::Amount;
+    use crate::exchange::{ExchangeRates, PriceStyle};
+    use jiff::Timestamp;
+    use jiff::civil::date;
+    use jiff::tz::TimeZone;
+
+    #[test]
+    fn test_rate() {
+        let rates = ExchangeRates::new(0);
+        let r1 = rates.rate(date(2024, 1, 1), 0);
+        assert_eq!(r1, rates.rate(date(2024, 1, 1), 0));
+        assert_ne!(r1, rates.rate(date(2024, 1, 2), 0));
+        assert_eq!(r1.scale, ExchangeRates::RATE_SCALE);
+        assert!((874_000..=966_000).contains(&r1.units));
+
+        let jpy = rates.rate(date(2024, 1, 1), 2);
+        assert!((5_890..=6_510).contains(&jpy.units));
+    }
+
+    #[test]
+    fn test_conversion() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+        let rates = ExchangeRates::new(0);
+        let amount = Amount::new(310_000_001, 7);
+
+        let conv = rates.conversion(&ts, 1, &amount, false).unwrap(/*:test:*/);
+        assert_eq!(conv.commodity, "USD");
+        assert_eq!(conv.style, PriceStyle::Unit);
+        assert_eq!(conv.counter, None);
+        assert_eq!(
+            conv.total,
+            amount.checked_mul(&conv.rate).unwrap(/*:test:*/)
+        );
+
+        let conv = rates.conversion(&ts, 2, &amount, false).unwrap(/*:test:*/);
+        assert_eq!(conv.commodity, "GBP");
+        assert_eq!(conv.style, PriceStyle::Total);
+
+        let conv = rates.conversion(&ts, 3, &amount, false).unwrap(/*:test:*/);
+        let counter = conv.counter.unwrap(/*:test:*/);
+        assert_eq!(counter.scale, 2);
+        assert_eq!(counter, conv.total.round(2).neg());
+
+        let conv = rates.conversion(&ts, 3, &amount, true).unwrap(/*:test:*/);
+        assert_eq!(conv.counter, Some(conv.total.neg()));
+    }
+}
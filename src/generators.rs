@@ -2,9 +2,15 @@
  * PTA-Generator 2025
  * SPDX-License-Identifier: Apache-2.0
  */
-use crate::setup::SetSize;
+use crate::account::TxnAccounts;
+use crate::amount::Amount;
+use crate::exchange::Conversion;
+use crate::scenarios::ScenarioTxn;
+use crate::setup::{ErrorKind, SetSize, TimeFormat};
+use crate::timetrack::Session;
 use jiff::Zoned;
-use std::collections::BTreeSet;
+use jiff::civil::Date;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 
 pub mod beancount;
@@ -16,24 +22,68 @@ pub struct TxnAccComm {
     pub commodities: Vec<String>,
 }
 
+/// Lifecycle of account
+///
+/// Dates of the first and last usage of account,
+/// and optional date when account is closed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountUsage {
+    pub first: Date,
+    pub last: Date,
+    pub close: Option<Date>,
+}
+
+/// Used accounts with their lifecycle
+pub type Accounts = BTreeMap<String, AccountUsage>;
+
+/// Record usage of account on date
+pub fn record_account(accounts: &mut Accounts, account: String, date: Date) {
+    accounts
+        .entry(account)
+        .and_modify(|usage| {
+            usage.first = usage.first.min(date);
+            usage.last = usage.last.max(date);
+        })
+        .or_insert(AccountUsage {
+            first: date,
+            last: date,
+            close: None,
+        });
+}
+
+/// Default commodity names, one for each day of month
+#[rustfmt::skip]
+pub const COMMODITY_NAMES: [&str; 31] = [
+    "CAA", "CAB", "CAC", "CAD", "CAE",
+    "CBA", "CBB", "CBC", "CBD", "CBE",
+    "CCA", "CCB", "CCC", "CCD", "CCE",
+    "CDA", "CDB", "CDC", "CDD", "CDE",
+    "CEA", "CEB", "CEC", "CED", "CEE",
+    "CFA", "CFB", "CFC", "CFD", "CFE",
+    "EUR"
+];
+
 /// Get commodity name based on day
 pub fn commodity_name(ts: &Zoned) -> &'static str {
-    #[rustfmt::skip]
-    let comm_names = vec![
-        "CAA", "CAB", "CAC", "CAD", "CAE",
-        "CBA", "CBB", "CBC", "CBD", "CBE",
-        "CCA", "CCB", "CCC", "CCD", "CCE",
-        "CDA", "CDB", "CDC", "CDD", "CDE",
-        "CEA", "CEB", "CEC", "CED", "CEE",
-        "CFA", "CFB", "CFC", "CFD", "CFE",
-        "EUR"
-    ];
-    comm_names[(ts.day() - 1) as usize]
+    COMMODITY_NAMES[(ts.day() - 1) as usize]
+}
+
+/// Commodity symbol of name, e.g. `Widget A` -> `WIDGET_A`
+pub fn commodity_symbol(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
 
 /// Chart of Accounts
 pub trait ChartOfAccGenerator {
-    fn chart_of_accounts(accounts: &BTreeSet<String>) -> Result<String, Box<dyn Error>>;
+    fn chart_of_accounts(accounts: &Accounts) -> Result<String, Box<dyn Error>>;
 }
 
 /// Chart of Commodities
@@ -47,12 +97,18 @@ pub trait AuditTxnGenerator: ChartOfAccGenerator {
         set: &SetSize,
         ts: &Zoned,
         index: u32,
+        amount: &Amount,
     ) -> Result<(String, TxnAccComm), Box<dyn Error>>;
 }
 
 /// Plain txn, no extra
 pub trait PlainTxnGenerator {
-    fn plain_txn(set: &SetSize, ts: &Zoned, index: u32) -> Result<String, Box<dyn Error>>;
+    fn plain_txn(
+        set: &SetSize,
+        ts: &Zoned,
+        index: u32,
+        amount: &Amount,
+    ) -> Result<String, Box<dyn Error>>;
 }
 
 /// Transactions with commodities, Charts of accounts and commodities
@@ -61,15 +117,570 @@ pub trait CommodityTxnGenerator: ChartOfAccGenerator + ChartOfCommGenerator {
         set: &SetSize,
         ts: &Zoned,
         index: u32,
+        amount: &Amount,
+        commodity: &str,
+        accounts: &TxnAccounts,
+    ) -> Result<(String, TxnAccComm), Box<dyn Error>>;
+}
+
+/// Transactions with currency conversion, Charts of accounts and commodities
+pub trait FxTxnGenerator: ChartOfAccGenerator + ChartOfCommGenerator {
+    fn fx_txn(
+        set: &SetSize,
+        ts: &Zoned,
+        index: u32,
+        conversion: &Conversion,
     ) -> Result<(String, TxnAccComm), Box<dyn Error>>;
+
+    /// Price directive of commodity in base currency, empty if not supported
+    fn fx_price(date: Date, commodity: &str, rate: &Amount) -> Result<String, Box<dyn Error>>;
+}
+
+/// Scenario transactions (e.g. personal finance), Charts of accounts and commodities
+///
+/// Commodities of scenarios are plain names (e.g. `Widget A`),
+/// and `scenario_commodity` converts them to the syntax of flavor.
+pub trait ScenarioTxnGenerator: ChartOfAccGenerator + ChartOfCommGenerator {
+    fn scenario_txn(index: u32, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>>;
+
+    /// Equity txn of `comm` (opening balances or year-end closing)
+    ///
+    /// This is scenario txn, if the flavor has only dates.
+    fn equity_txn(index: u32, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>> {
+        Self::scenario_txn(index, txn)
+    }
+
+    fn scenario_commodity(name: &str) -> String;
+}
+
+/// Time tracking sessions
+///
+/// The `format` is used only by (h)ledger, other flavors have only one format.
+pub trait TimeTxnGenerator {
+    fn time_txn(session: &Session, format: &TimeFormat) -> Result<String, Box<dyn Error>>;
+}
+
+/// Txn with deliberate error, as parts which are rendered by `InvalidTxnGenerator`
+///
+/// Parts are based on the injected error, e.g. month is 13 with malformed date.
+pub struct InvalidTxn {
+    pub year: i16,
+    pub month: i8,
+    pub day: i8,
+    pub indent: &'static str,
+    pub expenses: String,
+    pub amount: String,
+    pub commodity: String,
+    pub assets: String,
+    /// Amount of assets posting, which makes txn unbalanced
+    pub unbalanced: Option<String>,
+}
+
+/// Transactions with deliberate errors, Charts of accounts and commodities
+///
+/// The txn is rendered with header, optional metadata and postings of expenses and assets.
+/// Returned line offset is the line of the first posting (0-based) within txn, and
+/// metadata with uuid (if any) is on the line before it.
+/// See `invalid_txn` for injection of errors.
+pub trait InvalidTxnGenerator: ChartOfAccGenerator + ChartOfCommGenerator {
+    fn invalid_txn(
+        set: &SetSize,
+        ts: &Zoned,
+        index: u32,
+        txn: &InvalidTxn,
+    ) -> Result<(String, usize), Box<dyn Error>>;
+}
+
+/// Txn with deliberate error
+///
+/// The `error` is injected into the txn. Without error, txn is valid.
+/// Unbalanced txn is off by one.
+/// Returned line offset is the line of the injected error (0-based) within txn.
+/// Accounts and commodities which are deliberately unknown are not returned.
+pub fn invalid_txn<G: InvalidTxnGenerator>(
+    set: &SetSize,
+    ts: &Zoned,
+    index: u32,
+    amount: &Amount,
+    error: Option<&ErrorKind>,
+) -> Result<(String, TxnAccComm, usize), Box<dyn Error>> {
+    let y = ts.year();
+    let m = ts.month();
+    let d = ts.day();
+
+    let assets = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
+    let mut accounts = vec![assets.clone()];
+    let mut commodities = vec![];
+
+    let expenses = match error {
+        // Root of the account is valid, but the account is not in the chart of accounts
+        Some(ErrorKind::UnknownAccount) => {
+            format!("Expenses:Unknown:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d)
+        }
+        _ => {
+            let acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
+            accounts.push(acc.clone());
+            acc
+        }
+    };
+    let commodity = match error {
+        Some(ErrorKind::UndeclaredCommodity) => "UNDECLARED".to_string(),
+        _ => {
+            let comm = commodity_name(ts).to_string();
+            commodities.push(comm.clone());
+            comm
+        }
+    };
+    let parts = InvalidTxn {
+        year: y,
+        month: if error == Some(&ErrorKind::MalformedDate) {
+            13
+        } else {
+            m
+        },
+        day: d,
+        indent: if error == Some(&ErrorKind::BadIndentation) {
+            ""
+        } else {
+            "  "
+        },
+        expenses,
+        amount: amount.to_string(),
+        commodity,
+        assets,
+        unbalanced: match error {
+            Some(ErrorKind::Unbalanced) => {
+                let one = Amount::from_int(1, amount.scale).expect("IE: scale is too large");
+                let total = amount
+                    .checked_add(&one)
+                    .ok_or("Unbalanced amount is too large")?;
+                Some(total.neg().to_string())
+            }
+            _ => None,
+        },
+    };
+    let (txn, posting) = G::invalid_txn(set, ts, index, &parts)?;
+
+    let (txn, line) = match error {
+        Some(ErrorKind::Unbalanced) | Some(ErrorKind::MalformedDate) => (txn, 0),
+        Some(ErrorKind::DuplicateUuid) => {
+            if posting == 1 {
+                let msg = format!(
+                    "Error kind '{}' is not supported by journal without txn metadata",
+                    ErrorKind::DuplicateUuid
+                );
+                return Err(msg.into());
+            }
+            // uuid of the duplicate txn is on the line before its first posting
+            let line = txn.lines().count() + posting - 1;
+            let dup = txn.replacen(&format!("txn-{index}"), &format!("txn-{index}-dup"), 1);
+            (txn + dup.as_str(), line)
+        }
+        Some(ErrorKind::TruncatedFile) => (truncate_txn(&txn, posting), posting),
+        Some(_) => (txn, posting),
+        None => (txn, 0),
+    };
+
+    Ok((
+        txn,
+        TxnAccComm {
+            accounts,
+            commodities,
+        },
+        line,
+    ))
+}
+
+/// Grammar primitives of the flavor for fuzzing corpus
+pub trait CorpusGenerator {
+    /// Comment line
+    fn corpus_comment(text: &str) -> String;
+
+    /// Date of txn, which is not validated (e.g. month can be 13)
+    fn corpus_date(year: i32, month: i32, day: i32) -> String;
+
+    /// Header line of txn, without newline
+    fn corpus_header(date: &str, desc: &str) -> String;
+}
+
+/// Line of txn body in fuzzing corpus
+#[derive(Debug, Clone)]
+pub enum CorpusLine {
+    /// Account and optional amount with commodity
+    Posting(String, Option<String>),
+    Comment(String),
+}
+
+/// Txn of fuzzing corpus as syntax elements, so that they can be mutated one by one
+#[derive(Debug, Clone)]
+pub struct CorpusTxn {
+    pub date: (i32, i32, i32),
+    pub desc: String,
+    pub indent: String,
+    pub lines: Vec<CorpusLine>,
+}
+
+impl CorpusTxn {
+    pub fn new(desc: &str, postings: &[(&str, Option<&str>)]) -> CorpusTxn {
+        CorpusTxn {
+            date: (2024, 1, 1),
+            desc: desc.to_string(),
+            indent: "  ".to_string(),
+            lines: postings
+                .iter()
+                .map(|(acc, amount)| CorpusLine::Posting(acc.to_string(), amount.map(String::from)))
+                .collect(),
+        }
+    }
+
+    /// Render txn with grammar primitives of the flavor
+    pub fn render<G: CorpusGenerator>(&self) -> String {
+        let (y, m, d) = self.date;
+        let mut txn = G::corpus_header(&G::corpus_date(y, m, d), &self.desc);
+        txn.push('\n');
+        for line in &self.lines {
+            txn.push_str(&self.indent);
+            match line {
+                CorpusLine::Posting(acc, Some(amount)) => {
+                    txn.push_str(&format!("{acc}  {amount}\n"));
+                }
+                CorpusLine::Posting(acc, None) => txn.push_str(&format!("{acc}\n")),
+                CorpusLine::Comment(text) => txn.push_str(&G::corpus_comment(text)),
+            }
+        }
+        txn.push('\n');
+        txn
+    }
+}
+
+/// Fuzzing corpus: edge cases of journals, as (case name, journal)
+///
+/// Some of the cases are at the limits of the grammar of flavor,
+/// so they are not necessarily valid for all tools.
+pub fn corpus<G: CorpusGenerator>() -> Vec<(String, String)> {
+    let txn = |desc: &str, postings: &[(&str, Option<&str>)]| {
+        CorpusTxn::new(desc, postings).render::<G>()
+    };
+    let simple = |amount: &str| {
+        txn(
+            "txn",
+            &[("Expenses:Food", Some(amount)), ("Assets:Cash", None)],
+        )
+    };
+    let food = [("Expenses:Food", Some("1.00 EUR")), ("Assets:Cash", None)];
+
+    let mut many_postings = Vec::new();
+    let accounts: Vec<_> = (1..=100).map(|i| format!("Expenses:E{i:0>3}")).collect();
+    for acc in &accounts {
+        many_postings.push((acc.as_str(), Some("1.01 EUR")));
+    }
+    many_postings.push(("Assets:Cash", None));
+
+    let mut many_txns = String::new();
+    for i in 1..=100 {
+        many_txns.push_str(&txn(&format!("txn-{i}"), &food));
+    }
+
+    let cases = vec![
+        ("empty-file", String::new()),
+        (
+            "comment-only",
+            G::corpus_comment("comment only") + G::corpus_comment("").as_str(),
+        ),
+        ("simple", simple("1.00 EUR")),
+        (
+            "no-trailing-newline",
+            simple("1.00 EUR").trim_end().to_string(),
+        ),
+        ("zero-amount", simple("0 EUR")),
+        ("negative-amount", simple("-1.00 EUR")),
+        (
+            "extreme-amount-max",
+            simple("79228162514264337593543950335 EUR"),
+        ),
+        (
+            "extreme-amount-min",
+            simple("0.0000000000000000000000000001 EUR"),
+        ),
+        ("scale-28", simple("1.2345678901234567890123456789 EUR")),
+        ("scale-32", simple("1.23456789012345678901234567890123 EUR")),
+        ("empty-description", txn("", &food)),
+        ("long-description", txn(&"Lorem ipsum ".repeat(1000), &food)),
+        ("unicode-description", txn("Äyriäiset ja 寿司 🍣", &food)),
+        (
+            "unicode-account-fi",
+            txn(
+                "txn",
+                &[
+                    ("Expenses:Äyriäiset:Öljy", Some("1.00 EUR")),
+                    ("Assets:Käteinen", None),
+                ],
+            ),
+        ),
+        (
+            "unicode-account-cjk",
+            txn(
+                "txn",
+                &[
+                    ("Expenses:食費:寿司", Some("1.00 EUR")),
+                    ("Assets:現金", None),
+                ],
+            ),
+        ),
+        (
+            "unicode-account-emoji",
+            txn(
+                "txn",
+                &[
+                    ("Expenses:Food🍣", Some("1.00 EUR")),
+                    ("Assets:Cash💶", None),
+                ],
+            ),
+        ),
+        (
+            "deep-account",
+            txn(
+                "txn",
+                &[
+                    (&format!("Expenses{}", ":Sub".repeat(50)), Some("1.00 EUR")),
+                    ("Assets:Cash", None),
+                ],
+            ),
+        ),
+        (
+            "long-account",
+            txn(
+                "txn",
+                &[
+                    (&format!("Expenses:{}", "A".repeat(1000)), Some("1.00 EUR")),
+                    ("Assets:Cash", None),
+                ],
+            ),
+        ),
+        ("many-postings", txn("txn", &many_postings)),
+        ("many-txns", many_txns),
+    ];
+    cases
+        .into_iter()
+        .map(|(name, journal)| (name.to_string(), journal))
+        .collect()
+}
+
+/// Grammar-based mutations of a valid txn, as (mutation name, journal)
+///
+/// Each mutation changes one syntax element of the txn (date, posting, amount,
+/// indent or comment), and the rest of the txn is kept valid.
+/// Mutated journals are mostly invalid, but some of them are at the limits
+/// of the grammar, and are valid for some tools.
+pub fn mutations<G: CorpusGenerator>() -> Vec<(String, String)> {
+    let base = CorpusTxn::new(
+        "txn",
+        &[("Expenses:Food", Some("1.00 EUR")), ("Assets:Cash", None)],
+    );
+    let posting = |acc: &str, amount: Option<&str>| {
+        CorpusLine::Posting(acc.to_string(), amount.map(String::from))
+    };
+    let mut mutants: Vec<(String, CorpusTxn)> = Vec::new();
+
+    for (name, date) in [
+        ("year-0", (0, 1, 1)),
+        ("year-10000", (10000, 1, 1)),
+        ("month-0", (2024, 0, 1)),
+        ("month-13", (2024, 13, 1)),
+        ("day-0", (2024, 1, 0)),
+        ("day-32", (2024, 1, 32)),
+        ("feb-30", (2024, 2, 30)),
+        ("feb-29-non-leap", (2023, 2, 29)),
+    ] {
+        mutants.push((
+            format!("date-{name}"),
+            CorpusTxn {
+                date,
+                ..base.clone()
+            },
+        ));
+    }
+
+    for (name, lines) in [
+        ("none", vec![]),
+        ("single", vec![posting("Expenses:Food", Some("1.00 EUR"))]),
+        (
+            "no-amounts",
+            vec![posting("Expenses:Food", None), posting("Assets:Cash", None)],
+        ),
+        (
+            "unbalanced",
+            vec![
+                posting("Expenses:Food", Some("1.00 EUR")),
+                posting("Assets:Cash", Some("-2.00 EUR")),
+            ],
+        ),
+        (
+            "same-account",
+            vec![
+                posting("Expenses:Food", Some("1.00 EUR")),
+                posting("Expenses:Food", None),
+            ],
+        ),
+        (
+            "empty-account",
+            vec![posting("", Some("1.00 EUR")), posting("Assets:Cash", None)],
+        ),
+        (
+            "account-trailing-colon",
+            vec![
+                posting("Expenses:Food:", Some("1.00 EUR")),
+                posting("Assets:Cash", None),
+            ],
+        ),
+        (
+            "account-empty-segment",
+            vec![
+                posting("Expenses::Food", Some("1.00 EUR")),
+                posting("Assets:Cash", None),
+            ],
+        ),
+    ] {
+        mutants.push((
+            format!("posting-{name}"),
+            CorpusTxn {
+                lines,
+                ..base.clone()
+            },
+        ));
+    }
+
+    for (name, amount) in [
+        ("empty", ""),
+        ("no-commodity", "1.00"),
+        ("trailing-dot", "1. EUR"),
+        ("leading-dot", ".5 EUR"),
+        ("two-dots", "1.0.0 EUR"),
+        ("thousands-separator", "1,000.00 EUR"),
+        ("decimal-comma", "1,00 EUR"),
+        ("exponent", "1e3 EUR"),
+        ("plus-sign", "+1.00 EUR"),
+        ("double-minus", "--1.00 EUR"),
+        ("space-in-number", "1 000.00 EUR"),
+        ("commodity-first", "EUR 1.00"),
+        ("digits-100", &format!("{} EUR", "9".repeat(100))),
+        ("scale-100", &format!("0.{}1 EUR", "0".repeat(99))),
+    ] {
+        let lines = vec![
+            posting("Expenses:Food", Some(amount)),
+            posting("Assets:Cash", None),
+        ];
+        mutants.push((
+            format!("amount-{name}"),
+            CorpusTxn {
+                lines,
+                ..base.clone()
+            },
+        ));
+    }
+
+    for (name, indent) in [
+        ("none", ""),
+        ("one-space", " "),
+        ("tab", "\t"),
+        ("tab-space", "\t "),
+        ("eight-spaces", "        "),
+        ("nbsp", "\u{a0}\u{a0}"),
+    ] {
+        let txn = CorpusTxn {
+            indent: indent.to_string(),
+            ..base.clone()
+        };
+        mutants.push((format!("indent-{name}"), txn));
+    }
+
+    let comment = |at: usize, text: &str| {
+        let mut lines = base.lines.clone();
+        lines.insert(at, CorpusLine::Comment(text.to_string()));
+        CorpusTxn {
+            lines,
+            ..base.clone()
+        }
+    };
+    mutants.push(("comment-before-postings".to_string(), comment(0, "comment")));
+    mutants.push((
+        "comment-between-postings".to_string(),
+        comment(1, "comment"),
+    ));
+    mutants.push(("comment-after-postings".to_string(), comment(2, "comment")));
+    mutants.push(("comment-empty".to_string(), comment(1, "")));
+    mutants.push((
+        "comment-unicode".to_string(),
+        comment(1, "寿司 🍣 \u{200b}"),
+    ));
+    let only_comments = CorpusTxn {
+        lines: vec![CorpusLine::Comment("comment".to_string())],
+        ..base.clone()
+    };
+    mutants.push(("comment-no-postings".to_string(), only_comments));
+
+    mutants
+        .into_iter()
+        .map(|(name, txn)| (format!("mutate-{name}"), txn.render::<G>()))
+        .collect()
+}
+
+/// Truncate txn in the middle of line
+///
+/// Lines before `line` are kept as they are,
+/// and the `line` is cut in half without trailing newline.
+pub fn truncate_txn(txn: &str, line: usize) -> String {
+    let mut truncated = String::with_capacity(txn.len());
+    for l in txn.lines().take(line) {
+        truncated.push_str(l);
+        truncated.push('\n');
+    }
+    if let Some(l) = txn.lines().nth(line) {
+        let cut = l.len() / 2;
+        let cut = (0..=cut)
+            .rev()
+            .find(|c| l.is_char_boundary(*c))
+            .unwrap_or(0);
+        truncated.push_str(&l[..cut]);
+    }
+    truncated
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::generators::commodity_name;
+    use crate::amount::{Amount, AmountGen};
+    use crate::generators::beancount::Beancount;
+    use crate::generators::ledger::Ledger;
+    use crate::generators::tackler::Tackler;
+    use crate::generators::{
+        AccountUsage, Accounts, commodity_name, corpus, invalid_txn, mutations, record_account,
+        truncate_txn,
+    };
+    use crate::setup::{ErrorKind, SetSize};
     use jiff::Timestamp;
+    use jiff::civil::date;
     use jiff::tz::TimeZone;
 
+    #[test]
+    fn test_record_account() {
+        let mut accounts = Accounts::new();
+        record_account(&mut accounts, "a:b".to_string(), date(2024, 3, 1));
+        record_account(&mut accounts, "a:b".to_string(), date(2024, 5, 1));
+        record_account(&mut accounts, "a:b".to_string(), date(2023, 12, 31));
+        record_account(&mut accounts, "a:c".to_string(), date(2024, 4, 1));
+
+        assert_eq!(
+            accounts["a:b"],
+            AccountUsage {
+                first: date(2023, 12, 31),
+                last: date(2024, 5, 1),
+                close: None,
+            }
+        );
+        assert_eq!(accounts["a:c"].first, date(2024, 4, 1));
+        assert_eq!(accounts["a:c"].last, date(2024, 4, 1));
+    }
+
     #[test]
     fn test_commodity() {
         let ts: Timestamp = "2024-12-01T23:58:00Z".parse().unwrap();
@@ -81,4 +692,120 @@ mod tests {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap();
         assert_eq!(commodity_name(&ts.to_zoned(TimeZone::UTC)), "EUR");
     }
+
+    #[test]
+    fn test_truncate_txn() {
+        let txn = "2024-01-01 txn\n  e:a  1.0000001\n  a:b\n\n";
+        assert_eq!(truncate_txn(txn, 1), "2024-01-01 txn\n  e:a  1");
+        assert_eq!(truncate_txn(txn, 0), "2024-01");
+    }
+
+    #[test]
+    fn test_corpus() {
+        let cases = corpus::<Ledger>();
+        let names: std::collections::HashSet<_> = cases.iter().map(|c| &c.0).collect();
+        assert_eq!(names.len(), cases.len());
+
+        let simple = cases.iter().find(|c| c.0 == "simple").unwrap(/*:test:*/);
+        assert_eq!(
+            simple.1,
+            "2024/01/01 txn\n  Expenses:Food  1.00 EUR\n  Assets:Cash\n\n"
+        );
+    }
+
+    #[test]
+    fn test_mutations() {
+        let mutants = mutations::<Tackler>();
+        let names: std::collections::HashSet<_> = mutants.iter().map(|c| &c.0).collect();
+        assert_eq!(names.len(), mutants.len());
+        for element in ["date", "posting", "amount", "indent", "comment"] {
+            let prefix = format!("mutate-{element}-");
+            assert!(
+                mutants.iter().any(|m| m.0.starts_with(&prefix)),
+                "{element}"
+            );
+        }
+
+        let mutant = |name: &str| {
+            let m = mutants.iter().find(|m| m.0 == name).unwrap(/*:test:*/);
+            m.1.as_str()
+        };
+        assert_eq!(
+            mutant("mutate-date-month-13"),
+            "2024-13-01 'txn\n  Expenses:Food  1.00 EUR\n  Assets:Cash\n\n"
+        );
+        assert_eq!(
+            mutant("mutate-amount-two-dots"),
+            "2024-01-01 'txn\n  Expenses:Food  1.0.0 EUR\n  Assets:Cash\n\n"
+        );
+        assert_eq!(
+            mutant("mutate-indent-tab"),
+            "2024-01-01 'txn\n\tExpenses:Food  1.00 EUR\n\tAssets:Cash\n\n"
+        );
+        assert_eq!(
+            mutant("mutate-comment-between-postings"),
+            "2024-01-01 'txn\n  Expenses:Food  1.00 EUR\n  # comment\n  Assets:Cash\n\n"
+        );
+        assert_eq!(
+            mutant("mutate-posting-single"),
+            "2024-01-01 'txn\n  Expenses:Food  1.00 EUR\n\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_txn() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+        let amount = AmountGen::default().amount(&ts, 999);
+        let txn = |error: ErrorKind| {
+            invalid_txn::<Beancount>(&SetSize::Sz1e3, &ts, 999, &amount, Some(&error)).unwrap(/*:test:*/)
+        };
+
+        let (valid, acc_comm, line) =
+            invalid_txn::<Beancount>(&SetSize::Sz1e3, &ts, 999, &amount, None).unwrap(/*:test:*/);
+        assert_eq!(
+            valid,
+            "2024-12-31 * \"(#0000999) 1E3 txn-999\"\n  Expenses:Ey2024:Em12:Ed31  31.0000001 EUR\n  Assets:Ay2024:Am12\n\n"
+        );
+        assert_eq!(acc_comm.accounts.len(), 2);
+        assert_eq!(line, 0);
+
+        // unknown account has a valid root, so it is not a syntax error
+        let (unknown, acc_comm, line) = txn(ErrorKind::UnknownAccount);
+        assert_eq!(
+            unknown.lines().nth(line),
+            Some("  Expenses:Unknown:Ey2024:Em12:Ed31  31.0000001 EUR")
+        );
+        assert_eq!(acc_comm.accounts, vec!["Assets:Ay2024:Am12".to_string()]);
+
+        let (malformed, _, line) = txn(ErrorKind::MalformedDate);
+        assert!(malformed.starts_with("2024-13-31 "));
+        assert_eq!(line, 0);
+
+        let (unbalanced, _, _) = txn(ErrorKind::Unbalanced);
+        assert!(unbalanced.contains("  Assets:Ay2024:Am12  -32.0000001 EUR\n"));
+
+        let amount = Amount::new(12345, 2);
+        let (unbalanced, _, _) = invalid_txn::<Beancount>(&SetSize::Sz1e3, &ts, 999, &amount, Some(&ErrorKind::Unbalanced))
+                .unwrap(/*:test:*/);
+        assert!(unbalanced.contains("  Expenses:Ey2024:Em12:Ed31  123.45 EUR\n"));
+        assert!(unbalanced.contains("  Assets:Ay2024:Am12  -124.45 EUR\n"));
+
+        let (bad_indent, _, line) = txn(ErrorKind::BadIndentation);
+        assert_eq!(
+            bad_indent.lines().nth(line),
+            Some("Expenses:Ey2024:Em12:Ed31  31.0000001 EUR")
+        );
+
+        assert!(
+            invalid_txn::<Ledger>(
+                &SetSize::Sz1e3,
+                &ts,
+                999,
+                &amount,
+                Some(&ErrorKind::DuplicateUuid)
+            )
+            .is_err()
+        );
+    }
 }
@@ -2,32 +2,49 @@
  * PTA-Generator 2025
  * SPDX-License-Identifier: Apache-2.0
  */
+use crate::account::TxnAccounts;
+use crate::amount::Amount;
+use crate::exchange::{BASE_CURRENCY, Conversion, PriceStyle};
 use crate::generators::{
-    ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator, TxnAccComm, commodity_name,
+    Accounts, ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator, CorpusGenerator,
+    FxTxnGenerator, InvalidTxn, InvalidTxnGenerator, ScenarioTxnGenerator, TxnAccComm,
+    commodity_symbol,
 };
+use crate::scenarios::{CURRENCY, ScenarioTxn};
 use crate::setup::SetSize;
 use jiff::Zoned;
-use std::collections::BTreeSet;
+use jiff::civil::Date;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::fmt::Write;
 
 pub struct Beancount;
 
 impl ChartOfAccGenerator for Beancount {
-    fn chart_of_accounts(accounts: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
+    fn chart_of_accounts(accounts: &Accounts) -> Result<String, Box<dyn Error>> {
         let mut chart = String::with_capacity(accounts.len() * 100);
 
-        for acc in accounts.iter() {
-            writeln!(chart, "2024-01-01 open {acc}")?;
+        for (acc, usage) in accounts.iter() {
+            writeln!(chart, "{} open {acc}", usage.first)?;
+        }
+        for (acc, usage) in accounts.iter() {
+            if let Some(close) = usage.close {
+                writeln!(chart, "{close} close {acc}")?;
+            }
         }
         Ok(chart)
     }
 }
 
 impl ChartOfCommGenerator for Beancount {
-    fn chart_of_commodities(_commodities: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
-        // This is not needed at the moment
-        unimplemented!()
+    fn chart_of_commodities(commodities: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
+        let mut chart = String::with_capacity(commodities.len() * 32);
+
+        // Commodity declaration date is not validated by beancount
+        for comm in commodities.iter() {
+            writeln!(chart, "1970-01-01 commodity {comm}")?;
+        }
+        Ok(chart)
     }
 }
 
@@ -36,14 +53,17 @@ impl CommodityTxnGenerator for Beancount {
         set: &SetSize,
         ts_tz: &Zoned,
         index: u32,
+        amount: &Amount,
+        commodity: &str,
+        accounts: &TxnAccounts,
     ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
         let y = ts_tz.year();
         let m = ts_tz.month();
         let d = ts_tz.day();
 
-        let assets_acc = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
-        let expenses_acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
-        let commodity = commodity_name(ts_tz).to_string();
+        let assets_acc = accounts.assets.clone();
+        let expenses_acc = accounts.expenses.clone();
+        let commodity = commodity.to_string();
 
         let mut txn = String::new();
         writeln!(
@@ -55,7 +75,7 @@ impl CommodityTxnGenerator for Beancount {
             index,
             set.str().to_uppercase()
         )?;
-        writeln!(txn, "  {expenses_acc}  {d}.0000001 {commodity}")?;
+        writeln!(txn, "  {expenses_acc}  {amount} {commodity}")?;
         writeln!(txn, "  {assets_acc}")?;
         writeln!(txn)?;
 
@@ -69,18 +89,373 @@ impl CommodityTxnGenerator for Beancount {
     }
 }
 
+impl FxTxnGenerator for Beancount {
+    fn fx_txn(
+        set: &SetSize,
+        ts_tz: &Zoned,
+        index: u32,
+        conversion: &Conversion,
+    ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
+        let y = ts_tz.year();
+        let m = ts_tz.month();
+        let d = ts_tz.day();
+
+        let assets_acc = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
+        let expenses_acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
+        let commodity = conversion.commodity;
+        let price = match conversion.style {
+            PriceStyle::Unit => format!("@ {} {BASE_CURRENCY}", conversion.rate),
+            PriceStyle::Total => format!("@@ {} {BASE_CURRENCY}", conversion.total),
+        };
+
+        let mut txn = String::new();
+        writeln!(
+            txn,
+            "{:0>4}-{:0>2}-{:0>2} * \"(#{:0>7}) {} txn-{index}\"",
+            y,
+            m,
+            d,
+            index,
+            set.str().to_uppercase()
+        )?;
+        writeln!(
+            txn,
+            "  {expenses_acc}  {} {commodity} {price}",
+            conversion.amount
+        )?;
+        match &conversion.counter {
+            Some(counter) => writeln!(txn, "  {assets_acc}  {counter} {BASE_CURRENCY}")?,
+            None => writeln!(txn, "  {assets_acc}")?,
+        }
+        writeln!(txn)?;
+
+        Ok((
+            txn,
+            TxnAccComm {
+                accounts: vec![assets_acc, expenses_acc],
+                commodities: vec![commodity.to_string(), BASE_CURRENCY.to_string()],
+            },
+        ))
+    }
+
+    fn fx_price(date: Date, commodity: &str, rate: &Amount) -> Result<String, Box<dyn Error>> {
+        Ok(format!(
+            "{date} price {commodity} {rate} {BASE_CURRENCY}\n\n"
+        ))
+    }
+}
+
+impl ScenarioTxnGenerator for Beancount {
+    fn scenario_txn(index: u32, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>> {
+        let mut s = String::new();
+        writeln!(s, "{} * \"(#{:0>7}) {}\"", txn.ts.date(), index, txn.desc)?;
+        for p in &txn.postings {
+            let comm = Self::scenario_commodity(&p.commodity);
+            match &p.price {
+                Some(price) => writeln!(
+                    s,
+                    "  {}  {} {comm} @ {price} {CURRENCY}",
+                    p.account, p.amount
+                )?,
+                None => writeln!(s, "  {}  {} {comm}", p.account, p.amount)?,
+            }
+        }
+        writeln!(s)?;
+        Ok(s)
+    }
+
+    fn scenario_commodity(name: &str) -> String {
+        commodity_symbol(name)
+    }
+}
+
+impl InvalidTxnGenerator for Beancount {
+    fn invalid_txn(
+        set: &SetSize,
+        _ts: &Zoned,
+        index: u32,
+        txn: &InvalidTxn,
+    ) -> Result<(String, usize), Box<dyn Error>> {
+        let date = format!("{:0>4}-{:0>2}-{:0>2}", txn.year, txn.month, txn.day);
+        let unbalanced = txn
+            .unbalanced
+            .as_ref()
+            .map_or(String::new(), |a| format!("  {a} {}", txn.commodity));
+
+        let mut s = String::new();
+        writeln!(
+            s,
+            "{date} * \"(#{:0>7}) {} txn-{index}\"",
+            index,
+            set.str().to_uppercase()
+        )?;
+        writeln!(
+            s,
+            "{}{}  {} {}",
+            txn.indent, txn.expenses, txn.amount, txn.commodity
+        )?;
+        writeln!(s, "  {}{unbalanced}", txn.assets)?;
+        writeln!(s)?;
+
+        Ok((s, 1))
+    }
+}
+
+impl CorpusGenerator for Beancount {
+    fn corpus_comment(text: &str) -> String {
+        format!("; {text}\n")
+    }
+
+    fn corpus_date(year: i32, month: i32, day: i32) -> String {
+        format!("{year:0>4}-{month:0>2}-{day:0>2}")
+    }
+
+    fn corpus_header(date: &str, desc: &str) -> String {
+        format!("{date} * \"{}\"", desc.replace('"', "\\\""))
+    }
+}
+
+impl Beancount {
+    /// Counter account for post-close usage transactions
+    pub const POST_CLOSE_ACCOUNT: &'static str = "Equity:Post-Close";
+
+    /// Transaction which uses account after it has been closed
+    ///
+    /// This is deliberate error, and it is used to test error reporting of tools.
+    pub fn post_close_txn(
+        set: &SetSize,
+        date: Date,
+        number: u32,
+        account: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut txn = String::new();
+        writeln!(
+            txn,
+            "{date} * \"(#post-close-{number}) {} post-close usage of {account}\"",
+            set.str().to_uppercase()
+        )?;
+        writeln!(txn, "  {account}  1.0000001 EUR")?;
+        writeln!(txn, "  {}", Self::POST_CLOSE_ACCOUNT)?;
+        writeln!(txn)?;
+        Ok(txn)
+    }
+}
+
+/// Running state of the active monthly asset account
+struct MonthAccount {
+    account: String,
+    padded: bool,
+    balances: BTreeMap<String, Amount>,
+}
+
+/// Generator for the non-transaction Beancount directives
+///
+/// This will produce options, plugins, `pad`, `balance`, `note`,
+/// `document`, `event`, `query`, `custom` and `close` directives
+/// around the commodity transactions:
+///
+/// - header: options, plugin and `custom` directive
+/// - on each month: `event`, and `pad` on quarter's first month
+/// - at the end of month: `balance` for each commodity, and the month's
+///   asset account is closed (see `BeancountDirectives::closed_accounts`)
+/// - every 50th txn: `note`, every 500th txn: `document`
+/// - at the end of set: `query` directives
+pub struct BeancountDirectives {
+    set: SetSize,
+    month: Option<MonthAccount>,
+    last_date: Option<Date>,
+    documents: Vec<(String, String)>,
+    closed: Vec<(String, Date)>,
+}
+
+impl BeancountDirectives {
+    /// Source account for `pad` directives
+    pub const OPENING_ACCOUNT: &'static str = "Equity:Opening-Balances";
+    /// Padded amount for each commodity
+    const OPENING_AMOUNT: i64 = 1000;
+
+    pub fn new(set: &SetSize) -> Self {
+        BeancountDirectives {
+            set: set.clone(),
+            month: None,
+            last_date: None,
+            documents: Vec::new(),
+            closed: Vec::new(),
+        }
+    }
+
+    /// Options, plugins and custom directives for the beginning of journal
+    pub fn header(&self, ts_tz: &Zoned) -> Result<String, Box<dyn Error>> {
+        let mut dirs = String::new();
+        writeln!(dirs, "option \"title\" \"PTA-Generator: {}\"", self.set)?;
+        writeln!(dirs, "option \"operating_currency\" \"EUR\"")?;
+        writeln!(dirs, "plugin \"beancount.plugins.implicit_prices\"")?;
+        writeln!(dirs)?;
+        writeln!(
+            dirs,
+            "{} custom \"fava-option\" \"locale\" \"en\"",
+            ts_tz.date()
+        )?;
+        writeln!(dirs)?;
+        Ok(dirs)
+    }
+
+    /// Directives to be written before the txn, and book-keeping of the txn
+    ///
+    /// The `txn` must be an output of `Beancount::commodity_txn` for `ts_tz`, `index` and `amount`.
+    pub fn txn_directives(
+        &mut self,
+        ts_tz: &Zoned,
+        index: u32,
+        txn: &TxnAccComm,
+        amount: &Amount,
+    ) -> Result<String, Box<dyn Error>> {
+        let date = ts_tz.date();
+        let assets_acc = &txn.accounts[0];
+        let expenses_acc = &txn.accounts[1];
+        let commodity = &txn.commodities[0];
+
+        let mut dirs = String::new();
+        if self
+            .month
+            .as_ref()
+            .is_none_or(|ma| &ma.account != assets_acc)
+        {
+            dirs.push_str(self.close_month(date)?.as_str());
+
+            let padded = ts_tz.month() % 3 == 1;
+            writeln!(
+                dirs,
+                "{date} event \"period\" \"{:0>4}-{:0>2}\"",
+                ts_tz.year(),
+                ts_tz.month()
+            )?;
+            if padded {
+                writeln!(dirs, "{date} pad {assets_acc} {}", Self::OPENING_ACCOUNT)?;
+            }
+            writeln!(dirs)?;
+            self.month = Some(MonthAccount {
+                account: assets_acc.clone(),
+                padded,
+                balances: BTreeMap::new(),
+            });
+        }
+
+        if index % 50 == 1 {
+            writeln!(
+                dirs,
+                "{date} note {expenses_acc} \"(#{index:0>7}) {} txn-{index} reviewed\"",
+                self.set.str().to_uppercase()
+            )?;
+            writeln!(dirs)?;
+        }
+        if index % 500 == 1 {
+            let doc = format!(
+                "{:0>4}/{:0>2}/{}-txn-{index}.txt",
+                ts_tz.year(),
+                ts_tz.month(),
+                date
+            );
+            writeln!(dirs, "{date} document {expenses_acc} \"../docs/{doc}\"")?;
+            writeln!(dirs)?;
+            self.documents.push((
+                doc,
+                format!(
+                    "Receipt for {} txn-{index}\n",
+                    self.set.str().to_uppercase()
+                ),
+            ));
+        }
+
+        if let Some(ma) = self.month.as_mut() {
+            let balance = ma
+                .balances
+                .entry(commodity.clone())
+                .or_insert(Amount::new(0, amount.scale));
+            *balance = balance
+                .checked_add(&amount.neg())
+                .ok_or_else(|| format!("Balance of {} is too large", ma.account))?;
+        }
+        self.last_date = Some(date);
+
+        Ok(dirs)
+    }
+
+    /// Directives to be written after the last txn
+    pub fn finish(&mut self) -> Result<String, Box<dyn Error>> {
+        let Some(last_date) = self.last_date else {
+            return Ok(String::new());
+        };
+        let date = last_date.tomorrow()?;
+
+        let mut dirs = self.close_month(date)?;
+        writeln!(
+            dirs,
+            "{date} query \"assets\" \"SELECT account, sum(position) WHERE account ~ '^Assets' GROUP BY account\""
+        )?;
+        writeln!(
+            dirs,
+            "{date} query \"expenses\" \"SELECT account, sum(position) WHERE account ~ '^Expenses' GROUP BY account\""
+        )?;
+        Ok(dirs)
+    }
+
+    /// Document files referred by `document` directives
+    ///
+    /// Paths are relative to the `docs` directory of the txn set.
+    pub fn documents(&self) -> &[(String, String)] {
+        &self.documents
+    }
+
+    /// Accounts closed by directives, with their close date
+    ///
+    /// The `close` directives are written with the chart of accounts.
+    pub fn closed_accounts(&self) -> &[(String, Date)] {
+        &self.closed
+    }
+
+    /// Balance assertions and close of the active month account
+    fn close_month(&mut self, date: Date) -> Result<String, Box<dyn Error>> {
+        let mut dirs = String::new();
+        if let Some(ma) = self.month.take() {
+            for (comm, balance) in &ma.balances {
+                let balance = if ma.padded {
+                    Amount::from_int(Self::OPENING_AMOUNT, balance.scale)
+                        .and_then(|opening| opening.checked_add(balance))
+                        .ok_or_else(|| format!("Balance of {} is too large", ma.account))?
+                } else {
+                    *balance
+                };
+                writeln!(dirs, "{date} balance {}  {balance} {comm}", ma.account)?;
+            }
+            writeln!(dirs)?;
+            self.closed.push((ma.account, date));
+        }
+        Ok(dirs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::generators::CommodityTxnGenerator;
-    use crate::generators::beancount::Beancount;
+    use crate::account::AccountGen;
+    use crate::amount::Amount;
+    use crate::exchange::{Conversion, PriceStyle};
+    use crate::generators::beancount::{Beancount, BeancountDirectives};
+    use crate::generators::{
+        Accounts, ChartOfAccGenerator, CommodityTxnGenerator, FxTxnGenerator, ScenarioTxnGenerator,
+        record_account,
+    };
+    use crate::scenarios::ScenarioTxn;
     use crate::setup::SetSize;
     use jiff::Timestamp;
+    use jiff::civil::date;
     use jiff::tz::TimeZone;
 
     #[test]
     fn test_commodity() {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
-        let txn = Beancount::commodity_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999).unwrap(/*:test:*/);
+        let accs = AccountGen::default().accounts(&ts.to_zoned(TimeZone::UTC)).unwrap(/*:test:*/);
+        let txn = Beancount::commodity_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &Amount::new(310_000_001, 7), "EUR", &accs).unwrap(/*:test:*/);
         let txn_str = "2024-12-31 * \"(#0000999) 1E3 txn-999\"
   Expenses:Ey2024:Em12:Ed31  31.0000001 EUR
   Assets:Ay2024:Am12
@@ -96,4 +471,130 @@ mod tests {
         );
         assert_eq!(txn.1.commodities, vec!["EUR".to_string()]);
     }
+
+    #[test]
+    fn test_directives() {
+        let set = SetSize::Sz1e3;
+        let mut dirs = BeancountDirectives::new(&set);
+
+        let ts: Timestamp = "2024-01-31T12:00:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+        let amount = Amount::new(310_000_001, 7);
+        let accs = AccountGen::default().accounts(&ts).unwrap(/*:test:*/);
+        let txn = Beancount::commodity_txn(&set, &ts, 1, &amount, "EUR", &accs).unwrap(/*:test:*/);
+        let txn_dirs = dirs.txn_directives(&ts, 1, &txn.1, &amount).unwrap(/*:test:*/);
+        assert_eq!(
+            txn_dirs,
+            "2024-01-31 event \"period\" \"2024-01\"
+2024-01-31 pad Assets:Ay2024:Am01 Equity:Opening-Balances
+
+2024-01-31 note Expenses:Ey2024:Em01:Ed31 \"(#0000001) 1E3 txn-1 reviewed\"
+
+2024-01-31 document Expenses:Ey2024:Em01:Ed31 \"../docs/2024/01/2024-01-31-txn-1.txt\"
+
+"
+        );
+        assert_eq!(dirs.documents()[0].0, "2024/01/2024-01-31-txn-1.txt");
+
+        let ts: Timestamp = "2024-02-01T12:00:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+        let amount = Amount::new(10_000_001, 7);
+        let accs = AccountGen::default().accounts(&ts).unwrap(/*:test:*/);
+        let txn = Beancount::commodity_txn(&set, &ts, 2, &amount, "CAA", &accs).unwrap(/*:test:*/);
+        let txn_dirs = dirs.txn_directives(&ts, 2, &txn.1, &amount).unwrap(/*:test:*/);
+        assert_eq!(
+            txn_dirs,
+            "2024-02-01 balance Assets:Ay2024:Am01  968.9999999 EUR
+
+2024-02-01 event \"period\" \"2024-02\"
+
+"
+        );
+
+        let end_dirs = dirs.finish().unwrap(/*:test:*/);
+        assert!(end_dirs.starts_with(
+            "2024-02-02 balance Assets:Ay2024:Am02  -1.0000001 CAA
+
+2024-02-02 query \"assets\""
+        ));
+    }
+
+    #[test]
+    fn test_chart_of_accounts() {
+        let mut accounts = Accounts::new();
+        record_account(&mut accounts, "Assets:A".to_string(), date(2023, 6, 1));
+        record_account(&mut accounts, "Assets:A".to_string(), date(2023, 6, 9));
+        record_account(&mut accounts, "Assets:B".to_string(), date(2023, 7, 1));
+        accounts.get_mut("Assets:A").unwrap(/*:test:*/).close = Some(date(2023, 6, 10));
+
+        let chart = Beancount::chart_of_accounts(&accounts).unwrap(/*:test:*/);
+        assert_eq!(
+            chart,
+            "2023-06-01 open Assets:A
+2023-07-01 open Assets:B
+2023-06-10 close Assets:A
+"
+        );
+    }
+
+    #[test]
+    fn test_post_close_txn() {
+        let txn = Beancount::post_close_txn(&SetSize::Sz1e3, date(2024, 2, 2), 1, "Assets:A").unwrap(/*:test:*/);
+        assert_eq!(
+            txn,
+            "2024-02-02 * \"(#post-close-1) 1E3 post-close usage of Assets:A\"
+  Assets:A  1.0000001 EUR
+  Equity:Post-Close
+
+"
+        );
+    }
+
+    #[test]
+    fn test_fx() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let amount = Amount::new(310_000_001, 7);
+        let rate = Amount::new(912_345, 6);
+        let total = amount.checked_mul(&rate).unwrap(/*:test:*/);
+        let conversion = Conversion {
+            amount,
+            commodity: "USD",
+            rate,
+            total,
+            style: PriceStyle::Total,
+            counter: Some(total.round(2).neg()),
+        };
+        let txn = Beancount::fx_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &conversion).unwrap(/*:test:*/);
+        let txn_str = "2024-12-31 * \"(#0000999) 1E3 txn-999\"
+  Expenses:Ey2024:Em12:Ed31  31.0000001 USD @@ 28.2826950912345 EUR
+  Assets:Ay2024:Am12  -28.28 EUR
+
+";
+        assert_eq!(txn.0, txn_str);
+        assert_eq!(
+            txn.1.commodities,
+            vec!["USD".to_string(), "EUR".to_string()]
+        );
+
+        let price =
+            Beancount::fx_price(ts.to_zoned(TimeZone::UTC).date(), "USD", &rate).unwrap(/*:test:*/);
+        assert_eq!(price, "2024-12-31 price USD 0.912345 EUR\n\n");
+    }
+
+    #[test]
+    fn test_scenario() {
+        let ts: Timestamp = "2024-12-31T12:00:00Z".parse().unwrap(/*:test:*/);
+        let txn = ScenarioTxn::new(ts.to_zoned(TimeZone::UTC), "Rent")
+            .posting("Expenses:Rent", 110_000)
+            .posting("Assets:Checking", -110_000);
+        let txn_str = Beancount::scenario_txn(999, &txn).unwrap(/*:test:*/);
+        assert_eq!(
+            txn_str,
+            "2024-12-31 * \"(#0000999) Rent\"
+  Expenses:Rent  1100.00 EUR
+  Assets:Checking  -1100.00 EUR
+
+"
+        );
+    }
 }
@@ -2,12 +2,19 @@
  * PTA-Generator 2025
  * SPDX-License-Identifier: Apache-2.0
  */
+use crate::account::TxnAccounts;
+use crate::amount::Amount;
+use crate::exchange::{BASE_CURRENCY, Conversion, PriceStyle};
 use crate::generators::{
-    ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator, PlainTxnGenerator,
-    TxnAccComm, commodity_name,
+    Accounts, ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator, CorpusGenerator,
+    FxTxnGenerator, InvalidTxn, InvalidTxnGenerator, PlainTxnGenerator, ScenarioTxnGenerator,
+    TimeTxnGenerator, TxnAccComm,
 };
-use crate::setup::SetSize;
+use crate::scenarios::{CURRENCY, ScenarioTxn};
+use crate::setup::{SetSize, TimeFormat};
+use crate::timetrack::Session;
 use jiff::Zoned;
+use jiff::civil::Date;
 use std::collections::BTreeSet;
 use std::error::Error;
 use std::fmt::Write;
@@ -15,7 +22,12 @@ use std::fmt::Write;
 pub struct Ledger;
 
 impl PlainTxnGenerator for Ledger {
-    fn plain_txn(set: &SetSize, ts_tz: &Zoned, index: u32) -> Result<String, Box<dyn Error>> {
+    fn plain_txn(
+        set: &SetSize,
+        ts_tz: &Zoned,
+        index: u32,
+        amount: &Amount,
+    ) -> Result<String, Box<dyn Error>> {
         let y = ts_tz.year();
         let m = ts_tz.month();
         let d = ts_tz.day();
@@ -33,7 +45,7 @@ impl PlainTxnGenerator for Ledger {
             index,
             set.str().to_uppercase()
         )?;
-        writeln!(txn, "  {expenses_acc}  {d}.0000001")?;
+        writeln!(txn, "  {expenses_acc}  {amount}")?;
         writeln!(txn, "  {assets_acc}")?;
         writeln!(txn)?;
 
@@ -42,10 +54,10 @@ impl PlainTxnGenerator for Ledger {
 }
 
 impl ChartOfAccGenerator for Ledger {
-    fn chart_of_accounts(accounts: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
+    fn chart_of_accounts(accounts: &Accounts) -> Result<String, Box<dyn Error>> {
         let mut chart = String::with_capacity(accounts.len() * 100);
 
-        for acc in accounts.iter() {
+        for acc in accounts.keys() {
             writeln!(chart, "account {acc}")?;
         }
         Ok(chart)
@@ -53,9 +65,13 @@ impl ChartOfAccGenerator for Ledger {
 }
 
 impl ChartOfCommGenerator for Ledger {
-    fn chart_of_commodities(_commodities: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
-        // This is not needed at the moment
-        unimplemented!()
+    fn chart_of_commodities(commodities: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
+        let mut chart = String::with_capacity(commodities.len() * 16);
+
+        for comm in commodities.iter() {
+            writeln!(chart, "commodity {comm}")?;
+        }
+        Ok(chart)
     }
 }
 
@@ -64,14 +80,17 @@ impl CommodityTxnGenerator for Ledger {
         set: &SetSize,
         ts_tz: &Zoned,
         index: u32,
+        amount: &Amount,
+        commodity: &str,
+        accounts: &TxnAccounts,
     ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
         let y = ts_tz.year();
         let m = ts_tz.month();
         let d = ts_tz.day();
 
-        let assets_acc = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
-        let expenses_acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
-        let commodity = commodity_name(ts_tz).to_string();
+        let assets_acc = accounts.assets.clone();
+        let expenses_acc = accounts.expenses.clone();
+        let commodity = commodity.to_string();
 
         let mut txn = String::new();
         writeln!(
@@ -83,7 +102,7 @@ impl CommodityTxnGenerator for Ledger {
             index,
             set.str().to_uppercase()
         )?;
-        writeln!(txn, "  {expenses_acc}  {d}.0000001 {commodity}")?;
+        writeln!(txn, "  {expenses_acc}  {amount} {commodity}")?;
         writeln!(txn, "  {assets_acc}")?;
         writeln!(txn)?;
 
@@ -97,10 +116,197 @@ impl CommodityTxnGenerator for Ledger {
     }
 }
 
+impl FxTxnGenerator for Ledger {
+    fn fx_txn(
+        set: &SetSize,
+        ts_tz: &Zoned,
+        index: u32,
+        conversion: &Conversion,
+    ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
+        let y = ts_tz.year();
+        let m = ts_tz.month();
+        let d = ts_tz.day();
+
+        let assets_acc = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
+        let expenses_acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
+        let commodity = conversion.commodity;
+        let price = match conversion.style {
+            PriceStyle::Unit => format!("@ {} {BASE_CURRENCY}", conversion.rate),
+            PriceStyle::Total => format!("@@ {} {BASE_CURRENCY}", conversion.total),
+        };
+
+        let mut txn = String::new();
+        writeln!(
+            txn,
+            "{:0>4}/{:0>2}/{:0>2} (#{:0>7}) {} txn-{index}",
+            y,
+            m,
+            d,
+            index,
+            set.str().to_uppercase()
+        )?;
+        writeln!(
+            txn,
+            "  {expenses_acc}  {} {commodity} {price}",
+            conversion.amount
+        )?;
+        match &conversion.counter {
+            Some(counter) => writeln!(txn, "  {assets_acc}  {counter} {BASE_CURRENCY}")?,
+            None => writeln!(txn, "  {assets_acc}")?,
+        }
+        writeln!(txn)?;
+
+        Ok((
+            txn,
+            TxnAccComm {
+                accounts: vec![assets_acc, expenses_acc],
+                commodities: vec![commodity.to_string(), BASE_CURRENCY.to_string()],
+            },
+        ))
+    }
+
+    fn fx_price(date: Date, commodity: &str, rate: &Amount) -> Result<String, Box<dyn Error>> {
+        Ok(format!(
+            "P {} {commodity} {rate} {BASE_CURRENCY}\n\n",
+            date.strftime("%Y/%m/%d")
+        ))
+    }
+}
+
+impl ScenarioTxnGenerator for Ledger {
+    fn scenario_txn(index: u32, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>> {
+        let mut s = String::new();
+        writeln!(
+            s,
+            "{} (#{:0>7}) {}",
+            txn.ts.date().strftime("%Y/%m/%d"),
+            index,
+            txn.desc
+        )?;
+        for p in &txn.postings {
+            let comm = Self::scenario_commodity(&p.commodity);
+            match &p.price {
+                Some(price) => writeln!(
+                    s,
+                    "  {}  {} {comm} @ {price} {CURRENCY}",
+                    p.account, p.amount
+                )?,
+                None => writeln!(s, "  {}  {} {comm}", p.account, p.amount)?,
+            }
+        }
+        writeln!(s)?;
+        Ok(s)
+    }
+
+    /// Names with other than letters are quoted, e.g. `"Widget A"`
+    fn scenario_commodity(name: &str) -> String {
+        if name.starts_with('"') || name.chars().all(|c| c.is_ascii_alphabetic()) {
+            name.to_string()
+        } else {
+            format!("\"{name}\"")
+        }
+    }
+}
+
+impl TimeTxnGenerator for Ledger {
+    /// Timeclock has clock-in and clock-out entries of session.
+    ///
+    /// Timedot has date line for each day, and hours of session as a number,
+    /// or as dots (one dot is a quarter of hour) for every other session.
+    fn time_txn(session: &Session, format: &TimeFormat) -> Result<String, Box<dyn Error>> {
+        let mut entry = String::new();
+        match format {
+            TimeFormat::Timeclock => {
+                writeln!(
+                    entry,
+                    "i {} {}  session {}",
+                    session.start.strftime("%Y/%m/%d %H:%M:%S"),
+                    session.project,
+                    session.index
+                )?;
+                writeln!(entry, "o {}", session.end.strftime("%Y/%m/%d %H:%M:%S"))?;
+                writeln!(entry)?;
+            }
+            TimeFormat::Timedot => {
+                if session.first_of_day {
+                    if session.index > 1 {
+                        writeln!(entry)?;
+                    }
+                    writeln!(entry, "{}", session.start.date())?;
+                }
+                let quarter = 10i128.pow(session.hours.scale) / 4;
+                if session.hours.units % quarter == 0 && session.index % 2 == 0 {
+                    let quarters = session.hours.units / quarter;
+                    let dots: Vec<_> = (0..quarters)
+                        .step_by(4)
+                        .map(|q| &"...."[..(quarters - q).min(4) as usize])
+                        .collect();
+                    writeln!(entry, "{}  {}", session.project, dots.join(" "))?;
+                } else {
+                    writeln!(entry, "{}  {}", session.project, session.hours)?;
+                }
+            }
+        }
+        Ok(entry)
+    }
+}
+
+impl InvalidTxnGenerator for Ledger {
+    fn invalid_txn(
+        set: &SetSize,
+        _ts: &Zoned,
+        index: u32,
+        txn: &InvalidTxn,
+    ) -> Result<(String, usize), Box<dyn Error>> {
+        let date = format!("{:0>4}/{:0>2}/{:0>2}", txn.year, txn.month, txn.day);
+        let unbalanced = txn
+            .unbalanced
+            .as_ref()
+            .map_or(String::new(), |a| format!("  {a} {}", txn.commodity));
+
+        let mut s = String::new();
+        writeln!(
+            s,
+            "{date} (#{:0>7}) {} txn-{index}",
+            index,
+            set.str().to_uppercase()
+        )?;
+        writeln!(
+            s,
+            "{}{}  {} {}",
+            txn.indent, txn.expenses, txn.amount, txn.commodity
+        )?;
+        writeln!(s, "  {}{unbalanced}", txn.assets)?;
+        writeln!(s)?;
+
+        Ok((s, 1))
+    }
+}
+
+impl CorpusGenerator for Ledger {
+    fn corpus_comment(text: &str) -> String {
+        format!("; {text}\n")
+    }
+
+    fn corpus_date(year: i32, month: i32, day: i32) -> String {
+        format!("{year:0>4}/{month:0>2}/{day:0>2}")
+    }
+
+    fn corpus_header(date: &str, desc: &str) -> String {
+        format!("{date} {desc}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::account::AccountGen;
+    use crate::amount::Amount;
+    use crate::exchange::{Conversion, PriceStyle};
     use crate::generators::ledger::Ledger;
-    use crate::generators::{CommodityTxnGenerator, PlainTxnGenerator};
+    use crate::generators::{
+        CommodityTxnGenerator, FxTxnGenerator, PlainTxnGenerator, ScenarioTxnGenerator,
+    };
+    use crate::scenarios::ScenarioTxn;
     use crate::setup::SetSize;
     use jiff::Timestamp;
     use jiff::tz::TimeZone;
@@ -108,8 +314,7 @@ mod tests {
     #[test]
     fn test_plain() {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
-        let txn =
-            Ledger::plain_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999).unwrap(/*:test:*/);
+        let txn = Ledger::plain_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &Amount::new(310_000_001, 7)).unwrap(/*:test:*/);
         let txn_str = "2024/12/31 (#0000999) 1E3 txn-999
   e:ey2024:em12:ed31  31.0000001
   a:ay2024:am12
@@ -121,7 +326,8 @@ mod tests {
     #[test]
     fn test_commodity() {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
-        let txn = Ledger::commodity_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999).unwrap(/*:test:*/);
+        let accs = AccountGen::default().accounts(&ts.to_zoned(TimeZone::UTC)).unwrap(/*:test:*/);
+        let txn = Ledger::commodity_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &Amount::new(310_000_001, 7), "EUR", &accs).unwrap(/*:test:*/);
         let txn_str = "2024/12/31 (#0000999) 1E3 txn-999
   Expenses:Ey2024:Em12:Ed31  31.0000001 EUR
   Assets:Ay2024:Am12
@@ -137,4 +343,52 @@ mod tests {
         );
         assert_eq!(txn.1.commodities, vec!["EUR".to_string()]);
     }
+
+    #[test]
+    fn test_fx() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let amount = Amount::new(310_000_001, 7);
+        let rate = Amount::new(912_345, 6);
+        let total = amount.checked_mul(&rate).unwrap(/*:test:*/);
+        let conversion = Conversion {
+            amount,
+            commodity: "USD",
+            rate,
+            total,
+            style: PriceStyle::Total,
+            counter: Some(total.round(2).neg()),
+        };
+        let txn = Ledger::fx_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &conversion).unwrap(/*:test:*/);
+        let txn_str = "2024/12/31 (#0000999) 1E3 txn-999
+  Expenses:Ey2024:Em12:Ed31  31.0000001 USD @@ 28.2826950912345 EUR
+  Assets:Ay2024:Am12  -28.28 EUR
+
+";
+        assert_eq!(txn.0, txn_str);
+        assert_eq!(
+            txn.1.commodities,
+            vec!["USD".to_string(), "EUR".to_string()]
+        );
+
+        let price =
+            Ledger::fx_price(ts.to_zoned(TimeZone::UTC).date(), "USD", &rate).unwrap(/*:test:*/);
+        assert_eq!(price, "P 2024/12/31 USD 0.912345 EUR\n\n");
+    }
+
+    #[test]
+    fn test_scenario() {
+        let ts: Timestamp = "2024-12-31T12:00:00Z".parse().unwrap(/*:test:*/);
+        let txn = ScenarioTxn::new(ts.to_zoned(TimeZone::UTC), "Rent")
+            .posting("Expenses:Rent", 110_000)
+            .posting("Assets:Checking", -110_000);
+        let txn_str = Ledger::scenario_txn(999, &txn).unwrap(/*:test:*/);
+        assert_eq!(
+            txn_str,
+            "2024/12/31 (#0000999) Rent
+  Expenses:Rent  1100.00 EUR
+  Assets:Checking  -1100.00 EUR
+
+"
+        );
+    }
 }
@@ -2,26 +2,41 @@
  * PTA-Generator 2025
  * SPDX-License-Identifier: Apache-2.0
  */
+use crate::account::TxnAccounts;
+use crate::amount::Amount;
+use crate::exchange::{BASE_CURRENCY, Conversion, PriceStyle};
 use crate::generators::{
-    AuditTxnGenerator, ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator,
-    PlainTxnGenerator, TxnAccComm, commodity_name,
+    Accounts, AuditTxnGenerator, ChartOfAccGenerator, ChartOfCommGenerator, CommodityTxnGenerator,
+    CorpusGenerator, FxTxnGenerator, InvalidTxn, InvalidTxnGenerator, PlainTxnGenerator,
+    ScenarioTxnGenerator, TimeTxnGenerator, TxnAccComm, commodity_symbol,
 };
-use crate::setup::{JournalSetup, SetSize};
+use crate::scenarios::{CURRENCY, ScenarioTxn};
+use crate::setup::{JournalSetup, SetSize, TimeFormat};
+use crate::timetrack::{Session, TIME_ACCOUNT};
 use crate::txn_uuid::get_txn_uuid;
 use jiff::Zoned;
+use jiff::civil::Date;
 use jiff::fmt::strtime;
 use std::collections::BTreeSet;
 use std::error::Error;
 use std::fmt::Write;
 
+/// Minimum scale of tackler reports, if amounts have at least this scale
+const REPORT_MIN_SCALE: u32 = 2;
+
+/// Reports of tackler config
+pub const BALANCE_REPORT: &str = "Balance Report";
+pub const BALANCE_GROUP_REPORT: &str = "Balance Group Report";
+pub const REGISTER_REPORT: &str = "Register Report";
+
 pub struct Tackler {}
 
 impl ChartOfAccGenerator for Tackler {
-    fn chart_of_accounts(accounts: &BTreeSet<String>) -> Result<String, Box<dyn Error>> {
+    fn chart_of_accounts(accounts: &Accounts) -> Result<String, Box<dyn Error>> {
         let mut chart = String::with_capacity(accounts.len() * 100);
 
         writeln!(chart, "accounts = [")?;
-        for acc in accounts.iter() {
+        for acc in accounts.keys() {
             writeln!(chart, "   \"{acc}\",")?;
         }
         writeln!(chart, "]")?;
@@ -48,6 +63,7 @@ impl AuditTxnGenerator for Tackler {
         set: &SetSize,
         ts_tz: &Zoned,
         index: u32,
+        amount: &Amount,
     ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
         let ts = ts_tz.timestamp();
         let ts_tz_str = strtime::format("%Y-%m-%dT%H:%M:%S%.f%:z", ts_tz)?;
@@ -69,7 +85,7 @@ impl AuditTxnGenerator for Tackler {
             set.str().to_uppercase()
         )?;
         writeln!(txn, "  # uuid: {uuid}")?;
-        writeln!(txn, "  {expenses_acc}  {d}.0000001")?;
+        writeln!(txn, "  {expenses_acc}  {amount}")?;
         writeln!(txn, "  {assets_acc}")?;
         writeln!(txn)?;
 
@@ -84,7 +100,12 @@ impl AuditTxnGenerator for Tackler {
 }
 
 impl PlainTxnGenerator for Tackler {
-    fn plain_txn(set: &SetSize, ts_tz: &Zoned, index: u32) -> Result<String, Box<dyn Error>> {
+    fn plain_txn(
+        set: &SetSize,
+        ts_tz: &Zoned,
+        index: u32,
+        amount: &Amount,
+    ) -> Result<String, Box<dyn Error>> {
         let y = ts_tz.year();
         let m = ts_tz.month();
         let d = ts_tz.day();
@@ -102,7 +123,7 @@ impl PlainTxnGenerator for Tackler {
             index,
             set.str().to_uppercase()
         )?;
-        writeln!(txn, "  {expenses_acc}  {d}.0000001")?;
+        writeln!(txn, "  {expenses_acc}  {amount}")?;
         writeln!(txn, "  {assets_acc}")?;
         writeln!(txn)?;
 
@@ -115,14 +136,17 @@ impl CommodityTxnGenerator for Tackler {
         set: &SetSize,
         ts_tz: &Zoned,
         index: u32,
+        amount: &Amount,
+        commodity: &str,
+        accounts: &TxnAccounts,
     ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
         let y = ts_tz.year();
         let m = ts_tz.month();
         let d = ts_tz.day();
 
-        let assets_acc = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
-        let expenses_acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
-        let commodity = commodity_name(ts_tz).to_string();
+        let assets_acc = accounts.assets.clone();
+        let expenses_acc = accounts.expenses.clone();
+        let commodity = commodity.to_string();
 
         let mut txn = String::new();
         writeln!(
@@ -134,7 +158,7 @@ impl CommodityTxnGenerator for Tackler {
             index,
             set.str().to_uppercase()
         )?;
-        writeln!(txn, "  {expenses_acc}  {d}.0000001 {commodity}",)?;
+        writeln!(txn, "  {expenses_acc}  {amount} {commodity}")?;
         writeln!(txn, "  {assets_acc}")?;
         writeln!(txn)?;
 
@@ -148,10 +172,169 @@ impl CommodityTxnGenerator for Tackler {
     }
 }
 
+impl FxTxnGenerator for Tackler {
+    fn fx_txn(
+        set: &SetSize,
+        ts_tz: &Zoned,
+        index: u32,
+        conversion: &Conversion,
+    ) -> Result<(String, TxnAccComm), Box<dyn Error>> {
+        let y = ts_tz.year();
+        let m = ts_tz.month();
+        let d = ts_tz.day();
+
+        let assets_acc = format!("Assets:Ay{:0>4}:Am{:0>2}", y, m);
+        let expenses_acc = format!("Expenses:Ey{:0>4}:Em{:0>2}:Ed{:0>2}", y, m, d);
+        let commodity = conversion.commodity;
+        let price = match conversion.style {
+            PriceStyle::Unit => format!("@ {} {BASE_CURRENCY}", conversion.rate),
+            PriceStyle::Total => format!("= {} {BASE_CURRENCY}", conversion.total),
+        };
+
+        let mut txn = String::new();
+        writeln!(
+            txn,
+            "{:0>4}-{:0>2}-{:0>2} (#{:0>7}) '{} txn-{index}",
+            y,
+            m,
+            d,
+            index,
+            set.str().to_uppercase()
+        )?;
+        writeln!(
+            txn,
+            "  {expenses_acc}  {} {commodity} {price}",
+            conversion.amount
+        )?;
+        match &conversion.counter {
+            Some(counter) => writeln!(txn, "  {assets_acc}  {counter} {BASE_CURRENCY}")?,
+            None => writeln!(txn, "  {assets_acc}")?,
+        }
+        writeln!(txn)?;
+
+        Ok((
+            txn,
+            TxnAccComm {
+                accounts: vec![assets_acc, expenses_acc],
+                commodities: vec![commodity.to_string(), BASE_CURRENCY.to_string()],
+            },
+        ))
+    }
+
+    fn fx_price(_date: Date, _commodity: &str, _rate: &Amount) -> Result<String, Box<dyn Error>> {
+        // Tackler doesn't have price directives in journal
+        Ok(String::new())
+    }
+}
+
+impl ScenarioTxnGenerator for Tackler {
+    fn scenario_txn(index: u32, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>> {
+        let header = format!("{} (#{:0>7}) '{}", txn.ts.date(), index, txn.desc);
+        Self::txn_with_header(&header, txn)
+    }
+
+    /// Equity txn has full timestamp, so that year-end closing (at 23:59:59)
+    /// is after other txns of the day, which have only date
+    fn equity_txn(index: u32, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>> {
+        let ts = strtime::format("%Y-%m-%dT%H:%M:%S%.f%:z", &txn.ts)?;
+        let header = format!("{ts} (#{:0>7}) '{}", index, txn.desc);
+        Self::txn_with_header(&header, txn)
+    }
+
+    fn scenario_commodity(name: &str) -> String {
+        commodity_symbol(name)
+    }
+}
+
+impl TimeTxnGenerator for Tackler {
+    /// Session is txn at its start time, with sub-second timestamp
+    fn time_txn(session: &Session, _format: &TimeFormat) -> Result<String, Box<dyn Error>> {
+        let ts = strtime::format("%Y-%m-%dT%H:%M:%S%.f%:z", &session.start)?;
+        let end = strtime::format("%H:%M:%S%.f", &session.end)?;
+
+        let mut txn = String::new();
+        writeln!(
+            txn,
+            "{ts} (#{:0>7}) 'session {} until {end}",
+            session.index, session.index
+        )?;
+        writeln!(txn, "  {}  {}", session.project, session.hours)?;
+        writeln!(txn, "  {TIME_ACCOUNT}")?;
+        writeln!(txn)?;
+        Ok(txn)
+    }
+}
+
+impl InvalidTxnGenerator for Tackler {
+    fn invalid_txn(
+        set: &SetSize,
+        ts_tz: &Zoned,
+        index: u32,
+        txn: &InvalidTxn,
+    ) -> Result<(String, usize), Box<dyn Error>> {
+        let uuid = get_txn_uuid(set, index, ts_tz.timestamp(), Some("invalid"));
+        let date = format!("{:0>4}-{:0>2}-{:0>2}", txn.year, txn.month, txn.day);
+        let unbalanced = txn
+            .unbalanced
+            .as_ref()
+            .map_or(String::new(), |a| format!("  {a} {}", txn.commodity));
+
+        let mut s = String::new();
+        writeln!(
+            s,
+            "{date} (#{:0>7}) '{} txn-{index}",
+            index,
+            set.str().to_uppercase()
+        )?;
+        writeln!(s, "  # uuid: {uuid}")?;
+        writeln!(
+            s,
+            "{}{}  {} {}",
+            txn.indent, txn.expenses, txn.amount, txn.commodity
+        )?;
+        writeln!(s, "  {}{unbalanced}", txn.assets)?;
+        writeln!(s)?;
+
+        Ok((s, 2))
+    }
+}
+
+impl CorpusGenerator for Tackler {
+    fn corpus_comment(text: &str) -> String {
+        format!("# {text}\n")
+    }
+
+    fn corpus_date(year: i32, month: i32, day: i32) -> String {
+        format!("{year:0>4}-{month:0>2}-{day:0>2}")
+    }
+
+    fn corpus_header(date: &str, desc: &str) -> String {
+        if desc.is_empty() {
+            date.to_string()
+        } else {
+            format!("{date} '{desc}")
+        }
+    }
+}
+
 impl Tackler {
+    /// Title of tackler report, which is used by config and by reference reports
+    pub fn report_title(txn_set: &SetSize, report: &str) -> String {
+        format!("Tackler: {txn_set} {report}")
+    }
+
+    /// Scale of tackler reports (min, max) for amounts with `scale`
+    pub fn report_scale(scale: u32) -> (u32, u32) {
+        (scale.min(REPORT_MIN_SCALE), scale)
+    }
+
+    /// Tackler configuration of the txn set
+    ///
+    /// Report scale follows the `scale` of generated amounts.
     pub fn config(
         strict_mode: bool,
         audit_mode: bool,
+        scale: u32,
         setup: &JournalSetup,
     ) -> Result<String, Box<dyn Error>> {
         let accounts_toml = if strict_mode {
@@ -184,37 +367,66 @@ tags        = {{ path = "none" }}
 
 [report]
 report-timezone = "UTC"
-scale = {{ min = 2, max = 7 }}
+scale = {{ min = {scale_min}, max = {scale} }}
 accounts = [ ]
 targets = [ "balance" ]
 
-balance       = {{ title = "Tackler: {txn_set} Balance Report", type = "flat" }}
-balance-group = {{ title = "Tackler: {txn_set} Balance Group Report", type = "flat", group-by = "month" }}
-register      = {{ title = "Tackler: {txn_set} Register Report" }}
+balance       = {{ title = "{balance_title}", type = "flat" }}
+balance-group = {{ title = "{balance_group_title}", type = "flat", group-by = "month" }}
+register      = {{ title = "{register_title}" }}
 
 [export]
 targets = [ ]
 equity = {{ accounts = [ "Assets(:.*)?", ], equity-account = "Equity:Balance" }}
 "##,
             txn_set_dir = setup.txn_set_dir(),
-            txn_set = setup.txn_set.to_string(),
+            balance_title = Self::report_title(&setup.txn_set, BALANCE_REPORT),
+            balance_group_title = Self::report_title(&setup.txn_set, BALANCE_GROUP_REPORT),
+            register_title = Self::report_title(&setup.txn_set, REGISTER_REPORT),
+            scale_min = Self::report_scale(scale).0,
         );
         Ok(toml)
     }
+
+    /// Scenario txn with header line
+    fn txn_with_header(header: &str, txn: &ScenarioTxn) -> Result<String, Box<dyn Error>> {
+        let mut s = String::new();
+        writeln!(s, "{header}")?;
+        for p in &txn.postings {
+            let comm = Self::scenario_commodity(&p.commodity);
+            match &p.price {
+                Some(price) => writeln!(
+                    s,
+                    "  {}  {} {comm} @ {price} {CURRENCY}",
+                    p.account, p.amount
+                )?,
+                None => writeln!(s, "  {}  {} {comm}", p.account, p.amount)?,
+            }
+        }
+        writeln!(s)?;
+        Ok(s)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::account::AccountGen;
+    use crate::amount::{Amount, AmountGen};
+    use crate::exchange::{Conversion, PriceStyle};
     use crate::generators::tackler::Tackler;
-    use crate::generators::{AuditTxnGenerator, CommodityTxnGenerator, PlainTxnGenerator};
-    use crate::setup::SetSize;
+    use crate::generators::{
+        AuditTxnGenerator, CommodityTxnGenerator, FxTxnGenerator, PlainTxnGenerator,
+        ScenarioTxnGenerator, invalid_txn,
+    };
+    use crate::scenarios::ScenarioTxn;
+    use crate::setup::{ErrorKind, SetSize};
     use jiff::Timestamp;
     use jiff::tz::TimeZone;
 
     #[test]
     fn test_audit() {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
-        let txn = Tackler::audit_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999).unwrap(/*:test:*/);
+        let txn = Tackler::audit_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &Amount::new(310_000_001, 7)).unwrap(/*:test:*/);
         let txn_str = "2024-12-31T23:58:00+00:00 (#0000999) '1E3 txn-999
   # uuid: 8e43c795-8fb1-552e-9dde-eae36f233676
   e:ey2024:em12:ed31  31.0000001
@@ -227,7 +439,7 @@ mod tests {
     #[test]
     fn test_plain() {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
-        let txn = Tackler::plain_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999).unwrap(/*:test:*/);
+        let txn = Tackler::plain_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &Amount::new(310_000_001, 7)).unwrap(/*:test:*/);
         let txn_str = "2024-12-31 (#0000999) '1E3 txn-999
   e:ey2024:em12:ed31  31.0000001
   a:ay2024:am12
@@ -239,7 +451,8 @@ mod tests {
     #[test]
     fn test_commodity() {
         let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
-        let txn = Tackler::commodity_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999).unwrap(/*:test:*/);
+        let accs = AccountGen::default().accounts(&ts.to_zoned(TimeZone::UTC)).unwrap(/*:test:*/);
+        let txn = Tackler::commodity_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &Amount::new(310_000_001, 7), "EUR", &accs).unwrap(/*:test:*/);
         let txn_str = "2024-12-31 (#0000999) '1E3 txn-999
   Expenses:Ey2024:Em12:Ed31  31.0000001 EUR
   Assets:Ay2024:Am12
@@ -255,4 +468,92 @@ mod tests {
         );
         assert_eq!(txn.1.commodities, vec!["EUR".to_string()]);
     }
+
+    #[test]
+    fn test_invalid() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let ts = ts.to_zoned(TimeZone::UTC);
+        let amount = AmountGen::default().amount(&ts, 999);
+
+        let txn =
+            invalid_txn::<Tackler>(&SetSize::Sz1e3, &ts, 999, &amount, None).unwrap(/*:test:*/);
+        let txn_str = "2024-12-31 (#0000999) '1E3 txn-999
+  # uuid: acf73acd-7d42-55be-a5d5-ef02f3f18e71
+  Expenses:Ey2024:Em12:Ed31  31.0000001 EUR
+  Assets:Ay2024:Am12
+
+";
+        assert_eq!(txn.0, txn_str);
+        assert_eq!(txn.1.accounts.len(), 2);
+        assert_eq!(txn.1.commodities, vec!["EUR".to_string()]);
+
+        let txn = invalid_txn::<Tackler>(&SetSize::Sz1e3, &ts, 999, &amount, Some(&ErrorKind::Unbalanced)).unwrap(/*:test:*/);
+        assert!(txn.0.contains("  Assets:Ay2024:Am12  -32.0000001 EUR\n"));
+        assert_eq!(txn.2, 0);
+
+        let txn = invalid_txn::<Tackler>(&SetSize::Sz1e3, &ts, 999, &amount, Some(&ErrorKind::UnknownAccount)).unwrap(/*:test:*/);
+        assert!(
+            txn.0
+                .contains("  Expenses:Unknown:Ey2024:Em12:Ed31  31.0000001 EUR\n")
+        );
+        assert_eq!(txn.1.accounts, vec!["Assets:Ay2024:Am12".to_string()]);
+        assert_eq!(txn.2, 2);
+
+        let txn = invalid_txn::<Tackler>(&SetSize::Sz1e3, &ts, 999, &amount, Some(&ErrorKind::DuplicateUuid)).unwrap(/*:test:*/);
+        assert_eq!(txn.0.lines().nth(txn.2), txn.0.lines().nth(1));
+        assert!(txn.0.contains("txn-999-dup"));
+
+        let txn = invalid_txn::<Tackler>(&SetSize::Sz1e3, &ts, 999, &amount, Some(&ErrorKind::TruncatedFile)).unwrap(/*:test:*/);
+        assert!(!txn.0.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_fx() {
+        let ts: Timestamp = "2024-12-31T23:58:00Z".parse().unwrap(/*:test:*/);
+        let amount = Amount::new(310_000_001, 7);
+        let rate = Amount::new(912_345, 6);
+        let total = amount.checked_mul(&rate).unwrap(/*:test:*/);
+        let conversion = Conversion {
+            amount,
+            commodity: "USD",
+            rate,
+            total,
+            style: PriceStyle::Total,
+            counter: Some(total.round(2).neg()),
+        };
+        let txn = Tackler::fx_txn(&SetSize::Sz1e3, &ts.to_zoned(TimeZone::UTC), 999, &conversion).unwrap(/*:test:*/);
+        let txn_str = "2024-12-31 (#0000999) '1E3 txn-999
+  Expenses:Ey2024:Em12:Ed31  31.0000001 USD = 28.2826950912345 EUR
+  Assets:Ay2024:Am12  -28.28 EUR
+
+";
+        assert_eq!(txn.0, txn_str);
+        assert_eq!(
+            txn.1.commodities,
+            vec!["USD".to_string(), "EUR".to_string()]
+        );
+
+        let price =
+            Tackler::fx_price(ts.to_zoned(TimeZone::UTC).date(), "USD", &rate).unwrap(/*:test:*/);
+        assert_eq!(price, "");
+    }
+
+    #[test]
+    fn test_scenario() {
+        let ts: Timestamp = "2024-12-31T12:00:00Z".parse().unwrap(/*:test:*/);
+        let txn = ScenarioTxn::new(ts.to_zoned(TimeZone::UTC), "Rent")
+            .posting("Expenses:Rent", 110_000)
+            .posting("Assets:Checking", -110_000);
+        let txn_str = Tackler::scenario_txn(999, &txn).unwrap(/*:test:*/);
+        assert_eq!(
+            txn_str,
+            "2024-12-31 (#0000999) 'Rent
+  Expenses:Rent  1100.00 EUR
+  Assets:Checking  -1100.00 EUR
+
+"
+        );
+        let txn_str = Tackler::equity_txn(999, &txn).unwrap(/*:test:*/);
+        assert!(txn_str.starts_with("2024-12-31T12:00:00+00:00 (#0000999) 'Rent\n"));
+    }
 }
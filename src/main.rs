@@ -4,14 +4,30 @@
  */
 
 use crate::cli_args::Commands;
-use crate::commands::{audit, comm, plain};
+use crate::commands::{
+    audit, bench, comm, compare, fuzz, fx, invalid, plain, scenario, timeclock, verify,
+};
+use crate::scenarios::Scenario;
 use clap::Parser;
 
+mod account;
+mod amount;
+mod archive;
+mod benchmark;
 mod cli_args;
 mod commands;
+mod commodity;
+mod equity;
+mod exchange;
 mod generators;
+mod manifest;
+mod prng;
+mod reference;
+mod scenarios;
 mod setup;
+mod timetrack;
 mod txn_uuid;
+mod verifier;
 mod writers;
 
 fn main() {
@@ -22,20 +38,104 @@ fn main() {
         Commands::Audit {
             global_args,
             flavor: _,
+            amount_args,
         } => {
-            audit::exec(global_args)
+            audit::exec(global_args, amount_args)
         },
         Commands::Plain {
             global_args,
             flavor,
+            amount_args,
+            append_args,
         } => {
-            plain::exec(global_args, flavor)
+            plain::exec(global_args, flavor, amount_args, append_args)
         },
         Commands::Comm {
             global_args,
             flavor,
+            amount_args,
+            commodity_args,
+            account_args,
+            equity_args,
+            beancount_args,
+            append_args,
         } => {
-            comm::exec(global_args, flavor)
+            comm::exec(global_args, flavor, amount_args, commodity_args, account_args, equity_args, beancount_args, append_args)
+        },
+        Commands::Fx {
+            global_args,
+            flavor,
+            amount_args,
+        } => {
+            fx::exec(global_args, flavor, amount_args)
+        },
+        Commands::Personal {
+            global_args,
+            flavor,
+            seed_args,
+        } => {
+            scenario::exec(global_args, flavor, seed_args, Scenario::Personal)
+        },
+        Commands::Business {
+            global_args,
+            flavor,
+            seed_args,
+        } => {
+            scenario::exec(global_args, flavor, seed_args, Scenario::Business)
+        },
+        Commands::Inventory {
+            global_args,
+            flavor,
+            seed_args,
+        } => {
+            scenario::exec(global_args, flavor, seed_args, Scenario::Inventory)
+        },
+        Commands::Timeclock {
+            global_args,
+            flavor,
+            time_format,
+            sessions,
+            projects,
+            seed_args,
+        } => {
+            timeclock::exec(global_args, flavor, time_format, sessions, projects, seed_args)
+        },
+        Commands::Invalid {
+            global_args,
+            flavor,
+            errors,
+            error_count,
+            amount_args,
+        } => {
+            invalid::exec(global_args, flavor, errors, error_count, amount_args)
+        },
+        Commands::Fuzz {
+            data_path,
+            flavor,
+        } => {
+            fuzz::exec(data_path, flavor)
+        },
+        Commands::Verify {
+            set_path,
+            flavor,
+        } => {
+            verify::exec(set_path, flavor)
+        },
+        Commands::Bench {
+            data_path,
+            set_size,
+            runs,
+            tools,
+        } => {
+            bench::exec(data_path, set_size, runs, tools)
+        },
+        Commands::Compare {
+            results,
+            metric,
+            threshold,
+            output,
+        } => {
+            compare::exec(results, metric, threshold, output)
         },
     };
 
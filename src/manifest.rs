@@ -0,0 +1,247 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::cli_args::GlobalArgs;
+use crate::setup::{JournalFlavor, JournalSetup, SetSize, ShardType};
+use crate::writers::JournalWriter;
+use jiff::{Span, Unit, Zoned};
+use std::error::Error;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Manifest of generated txn set
+///
+/// Manifest is written at the root of txn set (`manifest-<flavor>.toml`),
+/// and it has the position of the last txn, so that the set
+/// could be continued with append mode.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    /// Mode which generated the set (e.g. `plain`)
+    pub mode: String,
+    pub flavor: JournalFlavor,
+    pub txn_set: SetSize,
+    pub shard_type: ShardType,
+    /// Time between txns in seconds
+    pub step: u32,
+    pub last_ts: Zoned,
+    pub last_index: u32,
+}
+
+const MODE: &str = "mode";
+const FLAVOR: &str = "flavor";
+const SET_SIZE: &str = "set-size";
+const SHARD_TYPE: &str = "shard-type";
+const STEP: &str = "step-seconds";
+const LAST_TS: &str = "last-timestamp";
+const LAST_INDEX: &str = "last-index";
+
+/// Txns to be generated: timestamp of the first txn, indices and step in seconds
+#[derive(Debug, Clone)]
+pub struct TxnRange {
+    pub ts: Zoned,
+    pub indices: RangeInclusive<u32>,
+    pub step: u32,
+    /// Txns are appended to an existing set
+    pub append: bool,
+}
+
+impl TxnRange {
+    /// Step between txns as span
+    pub fn step_span(&self) -> Span {
+        Span::new().seconds(self.step)
+    }
+
+    pub fn count(&self) -> u32 {
+        self.indices.end() + 1 - self.indices.start()
+    }
+
+    /// Summary of generated set, e.g. "Created 1e3 (1_000) test set"
+    pub fn summary(&self, set: &SetSize) -> String {
+        if self.append {
+            format!("Appended {} txns to {set} test set", self.count())
+        } else {
+            format!("Created {set} test set")
+        }
+    }
+
+    /// Manifest of the set after the txns of the range
+    pub fn manifest(&self, mode: &str, setup: &JournalSetup) -> Result<Manifest, Box<dyn Error>> {
+        let last = Span::new().seconds(i64::from(self.step) * i64::from(self.count() - 1));
+        Ok(Manifest {
+            mode: mode.to_string(),
+            flavor: setup.flavor.clone(),
+            txn_set: setup.txn_set.clone(),
+            shard_type: setup.shard_type.clone(),
+            step: self.step,
+            last_ts: self.ts.checked_add(last)?,
+            last_index: *self.indices.end(),
+        })
+    }
+}
+
+/// Writer and txns of a new set, or of an existing set with `append` txns
+///
+/// New set has txns between start and stop, and there is no writer
+/// if the set exists and it is kept with `--skip-existing` (see `JournalWriter::try_new`).
+/// Existing set is continued after the last txn of its manifest,
+/// which must have been generated with the same mode and flavor.
+pub fn open_set(
+    mode: &str,
+    args: &GlobalArgs,
+    flavor: &JournalFlavor,
+    append: Option<u32>,
+) -> Result<Option<(JournalWriter, TxnRange)>, Box<dyn Error>> {
+    let set = SetSize::try_from(args.set_size.as_str())?;
+    let shard_type = ShardType::try_from(args.shard_type.as_str())?;
+    let path = args.data_path.join(mode);
+
+    let Some(count) = append else {
+        let ts_start = args.start_ts()?;
+        let ts_end = args.stop_ts()?;
+        let span_secs = (&ts_end - &ts_start).total(Unit::Second)? as u32;
+        let range = TxnRange {
+            ts: ts_start,
+            indices: 1..=set.size(),
+            step: span_secs / set.size(),
+            append: false,
+        };
+        let writer =
+            JournalWriter::try_new(flavor.clone(), &path, set, shard_type, args.output()?)?;
+        return Ok(writer.map(|w| (w, range)));
+    };
+
+    let shards = args.output()?.shards;
+    let writer = JournalWriter::try_append(flavor.clone(), &path, set, shard_type, shards)?;
+    let manifest = Manifest::read(&writer.setup.manifest_path().1)?;
+    manifest.check(mode, flavor)?;
+    let first = manifest
+        .last_index
+        .checked_add(1)
+        .filter(|first| first.checked_add(count - 1).is_some())
+        .ok_or("Too many txns to append")?;
+    let range = TxnRange {
+        ts: manifest
+            .last_ts
+            .checked_add(Span::new().seconds(manifest.step))?,
+        indices: first..=first + (count - 1),
+        step: manifest.step,
+        append: true,
+    };
+    Ok(Some((writer, range)))
+}
+
+impl Manifest {
+    pub fn to_toml(&self) -> Result<String, Box<dyn Error>> {
+        let mut m = String::new();
+        writeln!(m, "# PTA-Generator: manifest of txn set")?;
+        writeln!(m, "{MODE} = \"{}\"", self.mode)?;
+        writeln!(m, "{FLAVOR} = \"{}\"", self.flavor)?;
+        writeln!(m, "{SET_SIZE} = \"{}\"", self.txn_set.str())?;
+        writeln!(m, "{SHARD_TYPE} = \"{}\"", self.shard_type)?;
+        writeln!(m, "{STEP} = {}", self.step)?;
+        writeln!(m, "{LAST_TS} = \"{}\"", self.last_ts)?;
+        writeln!(m, "{LAST_INDEX} = {}", self.last_index)?;
+        Ok(m)
+    }
+
+    /// Parse manifest, which is written by `Manifest::to_toml`
+    pub fn parse(text: &str) -> Result<Manifest, Box<dyn Error>> {
+        let mut values = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("Invalid line in manifest: '{line}'").into());
+            };
+            values.push((key.trim(), value.trim().trim_matches('"')));
+        }
+        let value = |key: &str| {
+            values
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| *v)
+                .ok_or_else(|| format!("Missing key in manifest: '{key}'"))
+        };
+        let number = |key: &str| -> Result<u32, Box<dyn Error>> {
+            let v = value(key)?;
+            v.parse::<u32>()
+                .map_err(|err| format!("Invalid value of '{key}' in manifest: '{v}': {err}").into())
+        };
+        Ok(Manifest {
+            mode: value(MODE)?.to_string(),
+            flavor: JournalFlavor::try_from(value(FLAVOR)?)?,
+            txn_set: SetSize::try_from(value(SET_SIZE)?)?,
+            shard_type: ShardType::try_from(value(SHARD_TYPE)?)?,
+            step: number(STEP)?,
+            last_ts: value(LAST_TS)?.parse()?,
+            last_index: number(LAST_INDEX)?,
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Manifest, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            format!(
+                "Can't read manifest of txn set (was it generated by this version?): {err}: '{}'",
+                path.display()
+            )
+        })?;
+        Self::parse(&text)
+    }
+
+    /// Check that the set is continued with the same mode and flavor
+    pub fn check(&self, mode: &str, flavor: &JournalFlavor) -> Result<(), Box<dyn Error>> {
+        if self.mode != mode || self.flavor != *flavor {
+            let msg = format!(
+                "Txn set is generated with mode '{}' and flavor '{}', it can't be appended with mode '{mode}' and flavor '{flavor}'",
+                self.mode, self.flavor
+            );
+            return Err(msg.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::Manifest;
+    use crate::setup::{JournalFlavor, SetSize, ShardType};
+
+    #[test]
+    fn test_manifest() {
+        let manifest = Manifest {
+            mode: "plain".to_string(),
+            flavor: JournalFlavor::Ledger,
+            txn_set: SetSize::Sz1e2,
+            shard_type: ShardType::Single,
+            step: 315_360,
+            last_ts: "2024-12-28T08:24:00+00:00[UTC]".parse().unwrap(/*:test:*/),
+            last_index: 100,
+        };
+        let toml = manifest.to_toml().unwrap(/*:test:*/);
+        assert_eq!(
+            toml,
+            r#"# PTA-Generator: manifest of txn set
+mode = "plain"
+flavor = "ledger"
+set-size = "1e2"
+shard-type = "single"
+step-seconds = 315360
+last-timestamp = "2024-12-28T08:24:00+00:00[UTC]"
+last-index = 100
+"#
+        );
+        let parsed = Manifest::parse(&toml).unwrap(/*:test:*/);
+        assert_eq!(parsed.last_ts, manifest.last_ts);
+        assert_eq!(parsed.last_index, 100);
+        assert_eq!(parsed.step, 315_360);
+        assert!(parsed.check("plain", &JournalFlavor::Ledger).is_ok());
+        assert!(parsed.check("comm", &JournalFlavor::Ledger).is_err());
+        assert!(parsed.check("plain", &JournalFlavor::Tackler).is_err());
+
+        assert!(Manifest::parse("mode = \"plain\"\n").is_err());
+        assert!(Manifest::parse(&toml.replace("= 100", "= x")).is_err());
+    }
+}
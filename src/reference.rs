@@ -0,0 +1,369 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use crate::setup::JournalFlavor;
+use crate::txn_uuid::uuid_of;
+use crate::verifier;
+use jiff::civil::{Date, DateTime};
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+
+pub mod balances;
+pub mod tackler;
+
+/// Posting of txn, account and commodity are indices of `Reference`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefPosting {
+    pub account: usize,
+    pub commodity: usize,
+    pub amount: Amount,
+}
+
+/// Txn of reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefTxn {
+    pub ts: Timestamp,
+    /// Header of txn without timestamp (code and description)
+    pub header: String,
+    /// Uuid of txn (`# uuid: ...`)
+    pub uuid: Option<String>,
+    pub postings: Vec<RefPosting>,
+}
+
+/// Postings of written txns, for reference reports
+///
+/// Txns are parsed back from the journal text which is written,
+/// so that the references are based on the exactly same data as the tools see.
+/// Account and commodity names are stored only once.
+#[derive(Debug, Clone, Default)]
+pub struct Reference {
+    flavor: JournalFlavor,
+    pub accounts: Vec<String>,
+    account_ids: HashMap<String, usize>,
+    pub commodities: Vec<String>,
+    commodity_ids: HashMap<String, usize>,
+    txns: Vec<RefTxn>,
+    /// Sums by account and commodity, for padding
+    sums: HashMap<(usize, usize), Amount>,
+    /// Active pads by account: source account and date of pad
+    pads: HashMap<usize, (usize, Timestamp)>,
+    /// Accounts and commodities which are already padded by active pads
+    padded: HashSet<(usize, usize)>,
+}
+
+/// Sums by account and commodity
+pub type Sums<'a> = BTreeMap<(&'a str, &'a str), Amount>;
+
+/// Id of name, name is added if it's new
+fn intern(names: &mut Vec<String>, ids: &mut HashMap<String, usize>, name: &str) -> usize {
+    if let Some(id) = ids.get(name) {
+        return *id;
+    }
+    names.push(name.to_string());
+    ids.insert(name.to_string(), names.len() - 1);
+    names.len() - 1
+}
+
+/// Timestamp of txn header
+///
+/// Timestamps without zone are in UTC, and dates are at midnight,
+/// which is the default time of tackler config. Dates can be
+/// also in ledger format (`2024/12/31`).
+pub fn timestamp(token: &str) -> Option<Timestamp> {
+    if token.contains('/') {
+        return timestamp(&token.replace('/', "-"));
+    }
+    if let Ok(ts) = token.parse::<Zoned>() {
+        return Some(ts.timestamp());
+    }
+    if let Ok(ts) = token.parse::<Timestamp>() {
+        return Some(ts);
+    }
+    let dt = match token.parse::<DateTime>() {
+        Ok(dt) => dt,
+        Err(_) => token
+            .parse::<Date>()
+            .ok()?
+            .to_datetime(jiff::civil::Time::midnight()),
+    };
+    dt.to_zoned(TimeZone::UTC).ok().map(|ts| ts.timestamp())
+}
+
+/// Sum of amounts with any scales, `None` in case of overflow
+pub fn checked_sum(a: &Amount, b: &Amount) -> Option<Amount> {
+    let scale = a.scale.max(b.scale);
+    a.rescale(scale)?.checked_add(&b.rescale(scale)?)
+}
+
+/// Amount with at least `min` and at most `max` decimals
+///
+/// This is the scale setting of tackler reports: the scale of amount is kept
+/// (trailing zeros are not removed), amounts with less than `min` decimals
+/// are padded, and amounts with more than `max` decimals are rounded.
+pub fn format_amount(amount: &Amount, min: u32, max: u32) -> String {
+    let scale = amount.scale.min(max).max(min);
+    let a = amount.round(scale);
+    a.rescale(scale).unwrap_or(a).to_string()
+}
+
+pub fn overflow() -> Box<dyn Error> {
+    "Overflow in reference report".into()
+}
+
+/// Sums of accounts by commodity, sorted by account and commodity
+pub fn balances<'a>(
+    reference: &'a Reference,
+    txns: &[&RefTxn],
+) -> Result<Sums<'a>, Box<dyn Error>> {
+    let mut sums = Sums::new();
+    for p in txns.iter().flat_map(|t| t.postings.iter()) {
+        let key = (
+            reference.accounts[p.account].as_str(),
+            reference.commodities[p.commodity].as_str(),
+        );
+        let sum = match sums.get(&key) {
+            Some(sum) => checked_sum(sum, &p.amount).ok_or_else(overflow)?,
+            None => p.amount,
+        };
+        sums.insert(key, sum);
+    }
+    Ok(sums)
+}
+
+impl Reference {
+    pub fn new(flavor: JournalFlavor) -> Reference {
+        Reference {
+            flavor,
+            ..Reference::default()
+        }
+    }
+
+    /// Txns in timestamp order, txns with the same timestamp are in written order
+    pub fn txns(&self) -> Vec<&RefTxn> {
+        let mut txns: Vec<&RefTxn> = self.txns.iter().collect();
+        txns.sort_by_key(|t| t.ts);
+        txns
+    }
+
+    /// Add txn, which is in the journal format of the flavor
+    ///
+    /// Other entries than txns (e.g. includes and directives) are ignored,
+    /// except beancount `pad` and `balance` directives, see `Reference::directive`.
+    pub fn add_txn(&mut self, txn: &str) -> Result<(), Box<dyn Error>> {
+        let invalid = |msg: &str| format!("Invalid txn for reference ({msg}): '{txn}'");
+        let mut lines = txn
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with(['#', ';']));
+        let Some(header) = lines.next() else {
+            return Ok(());
+        };
+        let (token, rest) = header.split_once(' ').unwrap_or((header, ""));
+        let Some(ts) = timestamp(token) else {
+            return Ok(());
+        };
+        if self.flavor == JournalFlavor::Beancount
+            && !matches!(rest.split_whitespace().next(), Some("*" | "!" | "txn"))
+        {
+            for line in txn.lines().filter(|l| !l.trim().is_empty()) {
+                self.directive(line).map_err(|msg| invalid(msg.as_str()))?;
+            }
+            return Ok(());
+        }
+
+        let mut postings = Vec::new();
+        let mut elided = None;
+        let mut weights: Vec<(Amount, String)> = Vec::new();
+        for line in lines {
+            let p = verifier::posting(&self.flavor, line.trim())
+                .map_err(|(_, msg)| invalid(msg.as_str()))?;
+            let account = intern(&mut self.accounts, &mut self.account_ids, &p.account);
+            let (Some(amount), Some((weight, weight_comm))) = (p.amount, p.weight) else {
+                elided = Some(account);
+                continue;
+            };
+            match weights.iter_mut().find(|(_, c)| *c == weight_comm) {
+                Some((sum, _)) => {
+                    *sum = checked_sum(sum, &weight).ok_or_else(|| invalid("overflow"))?
+                }
+                None => weights.push((weight, weight_comm)),
+            }
+            let comm = p.commodities.first().map_or("", |c| c.as_str());
+            postings.push(RefPosting {
+                account,
+                commodity: intern(&mut self.commodities, &mut self.commodity_ids, comm),
+                amount,
+            });
+        }
+        if let Some(account) = elided {
+            let [(sum, comm)] = weights.as_slice() else {
+                return Err(invalid("elided amount with several commodities").into());
+            };
+            postings.push(RefPosting {
+                account,
+                commodity: intern(&mut self.commodities, &mut self.commodity_ids, comm),
+                amount: sum.neg(),
+            });
+        }
+        self.push_txn(RefTxn {
+            ts,
+            header: rest.trim().to_string(),
+            uuid: uuid_of(txn),
+            postings,
+        })
+        .map_err(|msg| invalid(msg.as_str()).into())
+    }
+
+    fn push_txn(&mut self, txn: RefTxn) -> Result<(), String> {
+        for p in &txn.postings {
+            let sum = match self.sums.get(&(p.account, p.commodity)) {
+                Some(sum) => checked_sum(sum, &p.amount).ok_or("overflow")?,
+                None => p.amount,
+            };
+            self.sums.insert((p.account, p.commodity), sum);
+        }
+        self.txns.push(txn);
+        Ok(())
+    }
+
+    /// Beancount directive, `pad` and `balance` are used for padding
+    ///
+    /// Pad fills the difference of the first following balance assertion
+    /// of each commodity. Directives must be added in date order,
+    /// as the balance of the account is the sum of all earlier txns.
+    fn directive(&mut self, line: &str) -> Result<(), String> {
+        let mut tokens = line.split_whitespace();
+        let (Some(ts), Some(directive)) = (tokens.next().and_then(timestamp), tokens.next()) else {
+            return Ok(());
+        };
+        match directive {
+            "pad" => {
+                let (Some(account), Some(source)) = (tokens.next(), tokens.next()) else {
+                    return Err("pad without accounts".to_string());
+                };
+                let account = intern(&mut self.accounts, &mut self.account_ids, account);
+                let source = intern(&mut self.accounts, &mut self.account_ids, source);
+                self.pads.insert(account, (source, ts));
+                self.padded.retain(|(acc, _)| *acc != account);
+            }
+            "balance" => {
+                let assertion = line.splitn(3, ' ').nth(2).unwrap_or("").trim();
+                let p = verifier::posting(&self.flavor, assertion).map_err(|(_, msg)| msg)?;
+                let (Some(amount), Some(comm)) = (p.amount, p.commodities.first()) else {
+                    return Err("balance without amount".to_string());
+                };
+                let account = intern(&mut self.accounts, &mut self.account_ids, &p.account);
+                let commodity = intern(&mut self.commodities, &mut self.commodity_ids, comm);
+                let Some((source, pad_ts)) = self.pads.get(&account).copied() else {
+                    return Ok(());
+                };
+                if !self.padded.insert((account, commodity)) {
+                    return Ok(());
+                }
+                let balance = self
+                    .sums
+                    .get(&(account, commodity))
+                    .copied()
+                    .unwrap_or(Amount::new(0, amount.scale));
+                let pad = checked_sum(&amount, &balance.neg()).ok_or("overflow")?;
+                if pad.units != 0 {
+                    self.push_txn(RefTxn {
+                        ts: pad_ts,
+                        header: format!("(Padding inserted for balance of {} {comm})", p.account),
+                        uuid: None,
+                        postings: vec![
+                            RefPosting {
+                                account,
+                                commodity,
+                                amount: pad,
+                            },
+                            RefPosting {
+                                account: source,
+                                commodity,
+                                amount: pad.neg(),
+                            },
+                        ],
+                    })?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::Amount;
+    use crate::reference::{Reference, checked_sum, format_amount, timestamp};
+    use crate::setup::JournalFlavor;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(
+            format_amount(&Amount::new(310_000_000, 7), 2, 7),
+            "31.0000000"
+        );
+        assert_eq!(format_amount(&Amount::new(15, 1), 2, 7), "1.50");
+        assert_eq!(
+            format_amount(&Amount::new(310_000_001, 7), 2, 7),
+            "31.0000001"
+        );
+        assert_eq!(
+            format_amount(&Amount::new(-310_000_001, 7), 2, 4),
+            "-31.0000"
+        );
+        assert_eq!(format_amount(&Amount::new(-3, 0), 0, 0), "-3");
+        assert_eq!(
+            checked_sum(&Amount::new(15, 1), &Amount::new(-125, 2)),
+            Some(Amount::new(25, 2))
+        );
+    }
+
+    #[test]
+    fn test_add_txn() {
+        assert_eq!(
+            timestamp("2024-12-31").map(|ts| ts.to_string()),
+            Some("2024-12-31T00:00:00Z".to_string())
+        );
+        assert_eq!(
+            timestamp("2024-12-31T23:58:00.5+02:00").map(|ts| ts.to_string()),
+            Some("2024-12-31T21:58:00.5Z".to_string())
+        );
+
+        let mut reference = Reference::new(JournalFlavor::Tackler);
+        reference
+            .add_txn("2024-12-31T12:00:00Z (#1) 'txn-2\n  # uuid: x\n  e:b  1.50 EUR\n  e:c  2 EUR\n  a:b\n\n")
+            .unwrap(/*:test:*/);
+        reference
+            .add_txn("2024-12-31 'txn-1\n  e:b  100 USD @ 0.9 EUR\n  a:b\n")
+            .unwrap(/*:test:*/);
+        assert_eq!(reference.accounts, vec!["e:b", "e:c", "a:b"]);
+        assert_eq!(reference.commodities, vec!["EUR", "USD"]);
+
+        let txns = reference.txns();
+        assert_eq!(txns[0].header, "'txn-1");
+        assert_eq!(txns[0].postings[1].amount.to_string(), "-90.0");
+        assert_eq!(txns[1].header, "(#1) 'txn-2");
+        assert_eq!(txns[1].uuid.as_deref(), Some("x"));
+        assert_eq!(txns[1].postings[2].amount.to_string(), "-3.50");
+
+        assert!(
+            reference
+                .add_txn("2024-12-31 'x\n  a  1 EUR\n  b  1 USD\n  c\n")
+                .is_err()
+        );
+        assert!(
+            reference
+                .add_txn("2024-12-31 'x\n  a  x EUR\n  c\n")
+                .is_err()
+        );
+
+        reference.add_txn("include x.txn\n").unwrap(/*:test:*/);
+        reference.add_txn("").unwrap(/*:test:*/);
+        assert_eq!(reference.txns().len(), 2);
+    }
+}
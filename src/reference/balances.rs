@@ -0,0 +1,109 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::reference::{Reference, balances};
+use crate::setup::JournalFlavor;
+use std::error::Error;
+use std::fmt::Write;
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Balances of accounts as normalized CSV
+///
+/// Rows are `account,commodity,amount`, sorted by account and commodity.
+/// Only accounts with postings are listed (no parent accounts),
+/// zero balances are omitted and amounts don't have trailing zeros.
+pub fn csv(reference: &Reference) -> Result<String, Box<dyn Error>> {
+    let mut out = String::new();
+    writeln!(out, "account,commodity,amount")?;
+    for ((account, comm), sum) in balances(reference, &reference.txns())? {
+        if sum.units == 0 {
+            continue;
+        }
+        writeln!(
+            out,
+            "{},{},{}",
+            csv_field(account),
+            csv_field(comm),
+            sum.normalize()
+        )?;
+    }
+    Ok(out)
+}
+
+/// Reference balances of (h)ledger and beancount: (file name, report)
+///
+/// The same balances are used for ledger and hledger.
+pub fn reports(
+    reference: &Reference,
+    flavor: &JournalFlavor,
+) -> Result<Vec<(&'static str, String)>, Box<dyn Error>> {
+    let name = match flavor {
+        JournalFlavor::Tackler => return Ok(Vec::new()),
+        JournalFlavor::Ledger => "ledger-balances.csv",
+        JournalFlavor::Beancount => "beancount-balances.csv",
+    };
+    Ok(vec![(name, csv(reference)?)])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reference::Reference;
+    use crate::reference::balances::{csv, reports};
+    use crate::setup::JournalFlavor;
+
+    #[test]
+    fn test_csv() {
+        let mut reference = Reference::new(JournalFlavor::Ledger);
+        for txn in [
+            "include ../conf/accounts.ledger\n\n",
+            "2024/01/01 (#0000001) 1E1 txn-1\n  Expenses:Ey2024:Em01:Ed01  1.5000000 CAA\n  Assets:Ay2024:Am01\n",
+            "2024/01/02 (#0000002) 1E1 txn-2\n  Expenses:Ey2024:Em01:Ed02  2 \"A,B\"\n  Assets:Ay2024:Am01\n",
+            "2024/01/03 (#0000003) 1E1 txn-3\n  Expenses:Ey2024:Em01:Ed02  -2 \"A,B\"\n  Assets:Ay2024:Am01\n",
+        ] {
+            reference.add_txn(txn).unwrap(/*:test:*/);
+        }
+        assert_eq!(
+            csv(&reference).unwrap(/*:test:*/),
+            "account,commodity,amount
+Assets:Ay2024:Am01,CAA,-1.5
+Expenses:Ey2024:Em01:Ed01,CAA,1.5
+"
+        );
+        let reports = reports(&reference, &JournalFlavor::Ledger).unwrap(/*:test:*/);
+        assert_eq!(reports[0].0, "ledger-balances.csv");
+    }
+
+    #[test]
+    fn test_csv_pad() {
+        let mut reference = Reference::new(JournalFlavor::Beancount);
+        for txn in [
+            "option \"title\" \"PTA-Generator: 1e1 (10)\"\n\n",
+            "2024-01-01 event \"period\" \"2024-01\"\n2024-01-01 pad Assets:A Equity:Opening\n\n",
+            "2024-01-01 note Expenses:E \"(#0000001) 1E1 txn-1 reviewed\"\n\n",
+            "2024-01-01 * \"(#0000001) 1E1 txn-1\"\n  Expenses:E  1.0000001 CAA\n  Assets:A\n",
+            "2024-01-02 * \"(#0000002) 1E1 txn-2\"\n  Expenses:E  2 CAB\n  Assets:A\n",
+            "2024-02-01 balance Assets:A  998.9999999 CAA\n2024-02-01 balance Assets:A  998 CAB\n\n",
+        ] {
+            reference.add_txn(txn).unwrap(/*:test:*/);
+        }
+        assert_eq!(
+            csv(&reference).unwrap(/*:test:*/),
+            "account,commodity,amount
+Assets:A,CAA,998.9999999
+Assets:A,CAB,998
+Equity:Opening,CAA,-1000
+Equity:Opening,CAB,-1000
+Expenses:E,CAA,1.0000001
+Expenses:E,CAB,2
+"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use crate::generators::tackler::{BALANCE_GROUP_REPORT, BALANCE_REPORT, REGISTER_REPORT, Tackler};
+use crate::reference::{RefTxn, Reference, checked_sum, format_amount, overflow};
+use crate::setup::SetSize;
+use jiff::tz::TimeZone;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::Write;
+
+/// Indentation of sums of balance reports
+const LEFT_RULER: &str = "         ";
+/// Indentation of postings of register report
+const POSTING_INDENT: &str = "            ";
+/// Minimum width of sums of balance reports
+const MIN_WIDTH: usize = 12;
+/// Width of account column of register report
+const ACCOUNT_WIDTH: usize = 33;
+/// Width of amount columns of register report
+const AMOUNT_WIDTH: usize = 18;
+/// Width of names of metadata items
+const ITEM_PAD: usize = 15;
+
+/// Min and max scale of reports
+type Scale = (u32, u32);
+/// Sums of balance report by commodity and account (sub-account names)
+type BalanceSums<'a> = BTreeMap<(&'a str, Vec<&'a str>), Amount>;
+
+fn title(out: &mut String, title: &str) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{title}")?;
+    writeln!(out, "{}", "-".repeat(title.chars().count()))?;
+    Ok(())
+}
+
+/// Code and description of txn header
+fn code_and_desc(header: &str) -> (&str, &str) {
+    let (code, rest) = match header.strip_prefix('(').and_then(|h| h.split_once(')')) {
+        Some((code, rest)) => (code.trim(), rest.trim_start()),
+        None => ("", header),
+    };
+    (code, rest.strip_prefix('\'').unwrap_or(rest).trim_end())
+}
+
+/// Txns in tackler order: timestamp, code, description and uuid
+fn txns(reference: &Reference) -> Vec<&RefTxn> {
+    let mut txns = reference.txns();
+    txns.sort_by(|a, b| {
+        (a.ts, code_and_desc(&a.header), &a.uuid).cmp(&(b.ts, code_and_desc(&b.header), &b.uuid))
+    });
+    txns
+}
+
+/// Metadata of report, audit metadata is included only in audit mode
+fn metadata(
+    out: &mut String,
+    txns: &[&RefTxn],
+    audit: bool,
+    time_zone: bool,
+) -> Result<(), Box<dyn Error>> {
+    if audit {
+        let mut uuids = txns
+            .iter()
+            .map(|t| t.uuid.as_deref())
+            .collect::<Option<Vec<_>>>()
+            .ok_or("Audit reference needs uuid for all txns")?;
+        uuids.sort_unstable();
+        let mut hasher = Sha256::new();
+        for uuid in uuids {
+            hasher.update(uuid.as_bytes());
+            hasher.update(b"\n");
+        }
+        let checksum = hasher.finalize().iter().fold(String::new(), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        });
+        writeln!(out, "Txn Set Checksum")?;
+        writeln!(out, "{:>ITEM_PAD$} : {checksum}", "SHA-256")?;
+        writeln!(out, "{:>ITEM_PAD$} : {}", "set size", txns.len())?;
+        writeln!(out)?;
+        writeln!(out, "Account Selector Checksum")?;
+        writeln!(out, "{:>ITEM_PAD$} : select all", "None")?;
+        writeln!(out)?;
+    }
+    if time_zone {
+        writeln!(out, "Report Time Zone")?;
+        writeln!(out, "{:>ITEM_PAD$} : UTC", "TZ name")?;
+        writeln!(out)?;
+    }
+    if audit || time_zone {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Flat balance of txns, with deltas by commodity
+///
+/// Accounts are sorted by commodity and then by sub-account names.
+fn balance_body(
+    out: &mut String,
+    report_title: &str,
+    reference: &Reference,
+    txns: &[&RefTxn],
+    scale: Scale,
+) -> Result<(), Box<dyn Error>> {
+    let mut sums = BalanceSums::new();
+    for p in txns.iter().flat_map(|t| t.postings.iter()) {
+        let key = (
+            reference.commodities[p.commodity].as_str(),
+            reference.accounts[p.account].split(':').collect(),
+        );
+        let sum = match sums.get(&key) {
+            Some(sum) => checked_sum(sum, &p.amount).ok_or_else(overflow)?,
+            None => p.amount,
+        };
+        sums.insert(key, sum);
+    }
+    let mut deltas: BTreeMap<&str, Amount> = BTreeMap::new();
+    for ((comm, _), sum) in &sums {
+        let delta = match deltas.get(comm) {
+            Some(delta) => checked_sum(delta, sum).ok_or_else(overflow)?,
+            None => *sum,
+        };
+        deltas.insert(comm, delta);
+    }
+    let fmt = |a: &Amount| format_amount(a, scale.0, scale.1);
+    // sums are measured with sign, and deltas without scale setting
+    let width = sums
+        .values()
+        .map(|a| fmt(a).len() + usize::from(a.units >= 0))
+        .chain(deltas.values().map(|a| a.to_string().len()))
+        .max()
+        .unwrap_or(0)
+        .max(MIN_WIDTH);
+    let comm_width = deltas.keys().map(|c| c.chars().count()).max().unwrap_or(0);
+
+    title(out, report_title)?;
+    if sums.is_empty() {
+        return Ok(());
+    }
+    let filler = " ".repeat(if comm_width == 0 { 3 } else { 4 + comm_width });
+    for ((comm, account), sum) in &sums {
+        let comm = if comm_width == 0 {
+            String::new()
+        } else {
+            format!("{comm:<comm_width$}  ")
+        };
+        writeln!(
+            out,
+            "{LEFT_RULER}{:>width$}{filler}{comm}{}",
+            fmt(sum),
+            account.join(":")
+        )?;
+    }
+    let comm_len = if comm_width == 0 { 0 } else { comm_width + 1 };
+    writeln!(out, "{}", "=".repeat(LEFT_RULER.len() + width + comm_len))?;
+    for (comm, delta) in &deltas {
+        let comm = if comm.is_empty() {
+            String::new()
+        } else {
+            format!(" {comm}")
+        };
+        writeln!(out, "{LEFT_RULER}{:>width$}{comm}", fmt(delta))?;
+    }
+    Ok(())
+}
+
+/// Balance report (flat)
+pub fn balance(
+    report_title: &str,
+    reference: &Reference,
+    scale: Scale,
+    audit: bool,
+) -> Result<String, Box<dyn Error>> {
+    let txns = txns(reference);
+    let mut out = String::new();
+    metadata(&mut out, &txns, audit, false)?;
+    balance_body(&mut out, report_title, reference, &txns, scale)?;
+    Ok(out)
+}
+
+/// Balance group report (flat), grouped by month in UTC
+pub fn balance_group(
+    report_title: &str,
+    reference: &Reference,
+    scale: Scale,
+    audit: bool,
+) -> Result<String, Box<dyn Error>> {
+    let txns = txns(reference);
+    let mut groups: BTreeMap<String, Vec<&RefTxn>> = BTreeMap::new();
+    for txn in &txns {
+        let month = txn.ts.to_zoned(TimeZone::UTC).strftime("%Y-%m").to_string();
+        groups.entry(month).or_default().push(txn);
+    }
+    let mut out = String::new();
+    metadata(&mut out, &txns, audit, true)?;
+    title(&mut out, report_title)?;
+    for (month, txns) in &groups {
+        balance_body(&mut out, month, reference, txns, scale)?;
+    }
+    Ok(out)
+}
+
+/// Register report with running totals of accounts
+///
+/// Postings of txn are sorted by commodity and account,
+/// and running totals are accumulated in that order.
+pub fn register(
+    report_title: &str,
+    reference: &Reference,
+    scale: Scale,
+    audit: bool,
+) -> Result<String, Box<dyn Error>> {
+    let txns = txns(reference);
+    // positive amounts which fill the column are separated by space
+    let fmt = |a: &Amount| {
+        let s = format_amount(a, scale.0, scale.1);
+        if a.units >= 0 && s.len() >= AMOUNT_WIDTH {
+            format!(" {s}")
+        } else {
+            s
+        }
+    };
+
+    let mut out = String::new();
+    metadata(&mut out, &txns, audit, true)?;
+    title(&mut out, report_title)?;
+    let mut totals: HashMap<(usize, usize), Amount> = HashMap::new();
+    for txn in &txns {
+        let date = txn.ts.to_zoned(TimeZone::UTC).strftime("%Y-%m-%d");
+        let (code, desc) = code_and_desc(&txn.header);
+        write!(out, "{date}")?;
+        if !code.is_empty() {
+            write!(out, " ({code})")?;
+        }
+        if !desc.is_empty() {
+            write!(out, " '{desc}")?;
+        }
+        writeln!(out)?;
+        if let Some(uuid) = &txn.uuid {
+            writeln!(out, "{POSTING_INDENT}# uuid: {uuid}")?;
+        }
+
+        let mut postings: Vec<_> = txn.postings.iter().collect();
+        postings.sort_by_key(|p| {
+            (
+                &reference.commodities[p.commodity],
+                reference.accounts[p.account].split(':').collect::<Vec<_>>(),
+            )
+        });
+        let mut line_len = 0;
+        for p in postings {
+            let total = match totals.get(&(p.account, p.commodity)) {
+                Some(total) => checked_sum(total, &p.amount).ok_or_else(overflow)?,
+                None => p.amount,
+            };
+            totals.insert((p.account, p.commodity), total);
+            let comm = &reference.commodities[p.commodity];
+            let comm = if comm.is_empty() {
+                String::new()
+            } else {
+                format!(" {comm}")
+            };
+            let line = format!(
+                "{POSTING_INDENT}{:<ACCOUNT_WIDTH$}{:>AMOUNT_WIDTH$} {:>AMOUNT_WIDTH$}{comm}",
+                reference.accounts[p.account],
+                fmt(&p.amount),
+                fmt(&total),
+            );
+            line_len = line_len.max(line.chars().count());
+            writeln!(out, "{line}")?;
+        }
+        writeln!(out, "{}", "-".repeat(line_len))?;
+    }
+    Ok(out)
+}
+
+/// Reference reports of tackler config: (file name, report)
+///
+/// Reports of audit mode have the txn set checksum as metadata.
+pub fn reports(
+    reference: &Reference,
+    txn_set: &SetSize,
+    scale: u32,
+    audit: bool,
+) -> Result<Vec<(&'static str, String)>, Box<dyn Error>> {
+    let scale = Tackler::report_scale(scale);
+    Ok(vec![
+        (
+            "tackler-balance.txt",
+            balance(
+                &Tackler::report_title(txn_set, BALANCE_REPORT),
+                reference,
+                scale,
+                audit,
+            )?,
+        ),
+        (
+            "tackler-balance-group.txt",
+            balance_group(
+                &Tackler::report_title(txn_set, BALANCE_GROUP_REPORT),
+                reference,
+                scale,
+                audit,
+            )?,
+        ),
+        (
+            "tackler-register.txt",
+            register(
+                &Tackler::report_title(txn_set, REGISTER_REPORT),
+                reference,
+                scale,
+                audit,
+            )?,
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reference::Reference;
+    use crate::reference::tackler::{balance, balance_group, register, reports};
+    use crate::setup::{JournalFlavor, SetSize};
+
+    fn reference() -> Reference {
+        let mut reference = Reference::new(JournalFlavor::Tackler);
+        for txn in [
+            "2024-01-31 (#0000001) '1E1 txn-1\n  Expenses:Ey2024:Em01:Ed31  31.0000001 EUR\n  Assets:Ay2024:Am01\n",
+            "2024-02-01T12:00:00+02:00 (#0000002) '1E1 txn-2\n  Expenses:Ey2024:Em02:Ed01  1.50 EUR\n  Assets:Ay2024:Am02\n",
+            "2024-02-01 (#0000003) '1E1 txn-3\n  Expenses:Ey2024:Em02:Ed01  2 SEK\n  Assets:Ay2024:Am02\n",
+        ] {
+            reference.add_txn(txn).unwrap(/*:test:*/);
+        }
+        reference
+    }
+
+    /// Journal of txns, txns are separated by empty line
+    fn journal_reference(journal: &str) -> Reference {
+        let mut reference = Reference::new(JournalFlavor::Tackler);
+        for txn in journal.split("\n\n") {
+            reference.add_txn(txn).unwrap(/*:test:*/);
+        }
+        reference
+    }
+
+    #[test]
+    fn test_balance() {
+        let report = balance("Balance", &reference(), (2, 7), false).unwrap(/*:test:*/);
+        assert_eq!(
+            report,
+            "Balance
+-------
+          -31.0000001       EUR  Assets:Ay2024:Am01
+                -1.50       EUR  Assets:Ay2024:Am02
+           31.0000001       EUR  Expenses:Ey2024:Em01:Ed31
+                 1.50       EUR  Expenses:Ey2024:Em02:Ed01
+                -2.00       SEK  Assets:Ay2024:Am02
+                 2.00       SEK  Expenses:Ey2024:Em02:Ed01
+=========================
+            0.0000000 EUR
+                 0.00 SEK
+"
+        );
+
+        let report = balance_group("Group", &reference(), (2, 7), false).unwrap(/*:test:*/);
+        assert!(report.starts_with(
+            "Report Time Zone\n        TZ name : UTC\n\n\nGroup\n-----\n2024-01\n-------\n"
+        ));
+        assert!(report.contains("0.0000000 EUR\n2024-02\n-------\n"));
+    }
+
+    #[test]
+    fn test_register() {
+        let report = register("Register", &reference(), (2, 7), false).unwrap(/*:test:*/);
+        assert_eq!(
+            report,
+            "Report Time Zone
+        TZ name : UTC
+
+
+Register
+--------
+2024-01-31 (#0000001) '1E1 txn-1
+            Assets:Ay2024:Am01                      -31.0000001        -31.0000001 EUR
+            Expenses:Ey2024:Em01:Ed31                31.0000001         31.0000001 EUR
+--------------------------------------------------------------------------------------
+2024-02-01 (#0000003) '1E1 txn-3
+            Assets:Ay2024:Am02                            -2.00              -2.00 SEK
+            Expenses:Ey2024:Em02:Ed01                      2.00               2.00 SEK
+--------------------------------------------------------------------------------------
+2024-02-01 (#0000002) '1E1 txn-2
+            Assets:Ay2024:Am02                            -1.50              -1.50 EUR
+            Expenses:Ey2024:Em02:Ed01                      1.50               1.50 EUR
+--------------------------------------------------------------------------------------
+"
+        );
+        let actual = reports(&reference(), &SetSize::Sz1e1, 7, false).unwrap(/*:test:*/);
+        assert_eq!(actual.len(), 3);
+        assert!(
+            actual[0]
+                .1
+                .starts_with("Tackler: 1e1 (10) Balance Report\n")
+        );
+        assert!(reports(&reference(), &SetSize::Sz1e1, 7, true).is_err());
+    }
+
+    /// Reports of real tackler, see `tests/fixtures/tackler/README.md`
+    #[test]
+    fn test_tackler_fixtures() {
+        for (journal, audit, expected) in [
+            (
+                include_str!("../../tests/fixtures/tackler/comm-1e1.txn"),
+                false,
+                [
+                    include_str!("../../tests/fixtures/tackler/comm-1e1.bal.txt"),
+                    include_str!("../../tests/fixtures/tackler/comm-1e1.balgrp.txt"),
+                    include_str!("../../tests/fixtures/tackler/comm-1e1.reg.txt"),
+                ],
+            ),
+            (
+                include_str!("../../tests/fixtures/tackler/audit-1e1.txn"),
+                true,
+                [
+                    include_str!("../../tests/fixtures/tackler/audit-1e1.bal.txt"),
+                    include_str!("../../tests/fixtures/tackler/audit-1e1.balgrp.txt"),
+                    include_str!("../../tests/fixtures/tackler/audit-1e1.reg.txt"),
+                ],
+            ),
+        ] {
+            let actual =
+                reports(&journal_reference(journal), &SetSize::Sz1e1, 7, audit).unwrap(/*:test:*/);
+            for ((name, report), expected) in actual.iter().zip(expected) {
+                assert_eq!(report, expected, "{name}");
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use jiff::Zoned;
+use jiff::civil::Date;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+
+pub mod business;
+pub mod inventory;
+pub mod personal;
+
+/// Currency of scenarios
+pub const CURRENCY: &str = "EUR";
+
+/// Scale of scenario amounts
+pub const SCALE: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scenario {
+    Personal,
+    Business,
+    Inventory,
+}
+
+impl Scenario {
+    pub const PERSONAL: &'static str = "personal";
+    pub const BUSINESS: &'static str = "business";
+    pub const INVENTORY: &'static str = "inventory";
+}
+
+impl TryFrom<&str> for Scenario {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::PERSONAL => Ok(Scenario::Personal),
+            Self::BUSINESS => Ok(Scenario::Business),
+            Self::INVENTORY => Ok(Scenario::Inventory),
+            _ => Err(format!(
+                "Unknown scenario: {}, supported scenarios are: {}, {}, {}",
+                value,
+                Self::PERSONAL,
+                Self::BUSINESS,
+                Self::INVENTORY
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scenario::Personal => write!(f, "{}", Self::PERSONAL),
+            Scenario::Business => write!(f, "{}", Self::BUSINESS),
+            Scenario::Inventory => write!(f, "{}", Self::INVENTORY),
+        }
+    }
+}
+
+/// Amount in cents
+pub const fn cents(eur: i128) -> i128 {
+    eur * 100
+}
+
+pub struct Posting {
+    pub account: String,
+    pub amount: Amount,
+    pub commodity: String,
+    /// Unit price in `CURRENCY`
+    pub price: Option<Amount>,
+}
+
+/// Transaction of scenario
+///
+/// All postings have explicit amounts, and they balance to zero.
+pub struct ScenarioTxn {
+    pub ts: Zoned,
+    pub desc: String,
+    pub postings: Vec<Posting>,
+}
+
+impl ScenarioTxn {
+    pub fn new(ts: Zoned, desc: &str) -> ScenarioTxn {
+        ScenarioTxn {
+            ts,
+            desc: desc.to_string(),
+            postings: Vec::new(),
+        }
+    }
+
+    /// Add posting with amount in cents
+    pub fn posting(self, account: &str, cents: i128) -> ScenarioTxn {
+        self.amount_posting(account, Amount::new(cents, SCALE), CURRENCY)
+    }
+
+    /// Add posting with amount and commodity
+    pub fn amount_posting(mut self, account: &str, amount: Amount, commodity: &str) -> ScenarioTxn {
+        self.postings.push(Posting {
+            account: account.to_string(),
+            amount,
+            commodity: commodity.to_string(),
+            price: None,
+        });
+        self
+    }
+
+    /// Add posting with quantity of commodity and unit price in cents
+    pub fn priced_posting(
+        mut self,
+        account: &str,
+        quantity: Amount,
+        commodity: &str,
+        price_cents: i128,
+    ) -> ScenarioTxn {
+        self.postings.push(Posting {
+            account: account.to_string(),
+            amount: quantity,
+            commodity: commodity.to_string(),
+            price: Some(Amount::new(price_cents, SCALE)),
+        });
+        self
+    }
+
+    /// Txn is balanced, if weights of postings sum to zero by commodity
+    ///
+    /// Weight of priced posting is its value in `CURRENCY`.
+    pub fn is_balanced(&self) -> bool {
+        let weights: Vec<(&str, Amount)> = self
+            .postings
+            .iter()
+            .map(|p| match &p.price {
+                Some(price) => (
+                    CURRENCY,
+                    Amount::new(p.amount.units * price.units, p.amount.scale + price.scale),
+                ),
+                None => (p.commodity.as_str(), p.amount),
+            })
+            .collect();
+        let scale = weights.iter().map(|(_, w)| w.scale).max().unwrap_or(0);
+        let mut sums: BTreeMap<&str, i128> = BTreeMap::new();
+        for (commodity, w) in weights {
+            *sums.entry(commodity).or_default() += w.units * 10i128.pow(scale - w.scale);
+        }
+        sums.values().all(|sum| *sum == 0)
+    }
+}
+
+/// Generate transactions of scenario between `start` and `stop`
+///
+/// Recurring transactions of the scenario are always generated, and the
+/// set is filled up to `count` transactions with everyday transactions.
+/// Transactions are sorted by timestamp.
+pub fn generate(
+    scenario: &Scenario,
+    start: &Zoned,
+    stop: &Zoned,
+    count: u32,
+    seed: u64,
+) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+    let txns = match scenario {
+        Scenario::Personal => personal::generate(start, stop, count, seed)?,
+        Scenario::Business => business::generate(start, stop, count, seed)?,
+        Scenario::Inventory => inventory::generate(start, stop, count, seed)?,
+    };
+    if let Some(txn) = txns.iter().find(|txn| !txn.is_balanced()) {
+        let msg = format!("Internal error: txn '{}' is not balanced", txn.desc);
+        return Err(msg.into());
+    }
+    Ok(txns)
+}
+
+/// Timestamps of a day of month between `start` and `stop`
+///
+/// Transactions are at noon, in the timezone of `start`.
+pub fn monthly(start: &Zoned, stop: &Zoned, day: i8) -> Result<Vec<Zoned>, Box<dyn Error>> {
+    let mut dates = Vec::new();
+    let mut month: Date = start.date().first_of_month();
+    while month <= stop.date() {
+        let ts = month
+            .with()
+            .day(day)
+            .build()?
+            .at(12, 0, 0, 0)
+            .to_zoned(start.time_zone().clone())?;
+        if *start <= ts && ts < *stop {
+            dates.push(ts);
+        }
+        month = month.checked_add(jiff::Span::new().months(1))?;
+    }
+    Ok(dates)
+}
+
+/// Evenly spread timestamps between `start` and `stop`
+pub fn spread(start: &Zoned, stop: &Zoned, count: u32) -> Result<Vec<Zoned>, Box<dyn Error>> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let span_secs = (stop - start).total(jiff::Unit::Second)? as i64;
+    let step = span_secs / i64::from(count);
+    let mut ts = Vec::with_capacity(count as usize);
+    for i in 0..i64::from(count) {
+        ts.push(start.checked_add(jiff::Span::new().seconds(i * step))?);
+    }
+    Ok(ts)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenarios::{ScenarioTxn, monthly, spread};
+    use jiff::Zoned;
+
+    #[test]
+    fn test_monthly() {
+        let start: Zoned = "2024-01-10T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2024-04-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let dates: Vec<_> = monthly(&start, &stop, 5)
+            .unwrap(/*:test:*/)
+            .iter()
+            .map(|ts| ts.date().to_string())
+            .collect();
+        assert_eq!(dates, vec!["2024-02-05", "2024-03-05"]);
+    }
+
+    #[test]
+    fn test_spread() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2024-01-02T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let ts = spread(&start, &stop, 4).unwrap(/*:test:*/);
+        assert_eq!(ts.len(), 4);
+        assert_eq!(ts[1].hour(), 6);
+        assert!(spread(&start, &stop, 0).unwrap(/*:test:*/).is_empty());
+    }
+
+    #[test]
+    fn test_balanced() {
+        let ts: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let txn = ScenarioTxn::new(ts.clone(), "txn")
+            .posting("Expenses:Food", 1000)
+            .posting("Assets:Cash", -1000);
+        assert!(txn.is_balanced());
+        let txn = ScenarioTxn::new(ts, "txn").posting("Expenses:Food", 1000);
+        assert!(!txn.is_balanced());
+    }
+}
@@ -0,0 +1,345 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::prng::Prng;
+use crate::scenarios::{ScenarioTxn, cents, monthly, spread};
+use jiff::Zoned;
+use std::collections::VecDeque;
+use std::error::Error;
+
+const BANK: &str = "Assets:Bank:Checking";
+const RECEIVABLES: &str = "Assets:Receivables";
+const VAT_INPUT: &str = "Assets:VAT:Input";
+const EQUIPMENT: &str = "Assets:Equipment";
+const ACC_DEPRECIATION: &str = "Assets:Equipment:Accumulated-Depreciation";
+const PAYABLES: &str = "Liabilities:Payables";
+const VAT_OUTPUT: &str = "Liabilities:VAT:Output";
+const WITHHOLDING: &str = "Liabilities:Payroll:Withholding";
+const SOCIAL_SECURITY: &str = "Liabilities:Payroll:Social-Security";
+const SHARE_CAPITAL: &str = "Equity:Share-Capital";
+const SERVICES: &str = "Income:Sales:Services";
+const GOODS: &str = "Income:Sales:Goods";
+const RENT: &str = "Expenses:Rent";
+const SUPPLIES: &str = "Expenses:Supplies";
+const SOFTWARE: &str = "Expenses:Software";
+const SALARIES: &str = "Expenses:Payroll:Salaries";
+const SOCIAL_SECURITY_COST: &str = "Expenses:Payroll:Social-Security";
+const DEPRECIATION: &str = "Expenses:Depreciation";
+
+/// PRNG stream for business scenario
+const STREAM: u64 = 5;
+
+/// VAT rates, in percent
+const VAT_RATES: [i128; 3] = [24, 14, 10];
+
+/// Monthly rent (net), in cents
+const RENT_CENTS: i128 = cents(1500);
+/// Monthly gross salaries, in cents
+const SALARIES_CENTS: i128 = cents(4000);
+/// Withholding tax of salaries, in percent
+const WITHHOLDING_PCT: i128 = 25;
+/// Employer's social security cost of salaries, in percent
+const SOCIAL_SECURITY_PCT: i128 = 20;
+/// Monthly depreciation of equipment, in cents
+const DEPRECIATION_CENTS: i128 = cents(200);
+/// Sales (net) per day, in cents
+const DAILY_SALES_CENTS: i128 = cents(450);
+/// Mean of sales invoice (net), in cents
+const MEAN_INVOICE_CENTS: i128 = cents(520);
+/// Purchases (net) per day, in cents
+const DAILY_PURCHASES_CENTS: i128 = cents(60);
+/// Mean of purchase bill (net), in cents
+const MEAN_BILL_CENTS: i128 = cents(165);
+
+/// Payment term of invoices and bills, in seconds (14 days)
+const PAYMENT_TERM_SECS: i64 = 14 * 86_400;
+
+const OPENING_BANK_CENTS: i128 = cents(20000);
+const OPENING_EQUIPMENT_CENTS: i128 = cents(12000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Opening,
+    Rent,
+    TaxPayment,
+    Payroll,
+    Depreciation,
+    Trade(u32),
+}
+
+/// Small business: sales invoices and purchase bills with VAT at several rates,
+/// receivables and payables with their payments, monthly rent, payroll with
+/// withholding tax and social security, tax payments and depreciation of equipment.
+///
+/// Invoices and bills are paid in order, after their payment term. If there is
+/// nothing to pay, a cash sale or purchase is recorded instead.
+/// Invoices, bills and their payments fill the set up to `count` transactions,
+/// and the set has always `count` transactions: if there are more monthly
+/// transactions, the set ends at `count`, and a tax payment with nothing
+/// to pay is replaced by a trade.
+/// Their amounts are scaled so that sales and purchases follow the daily targets.
+pub fn generate(
+    start: &Zoned,
+    stop: &Zoned,
+    count: u32,
+    seed: u64,
+) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+    let mut events = vec![(start.clone(), Event::Opening)];
+    for (day, event) in [
+        (1, Event::Rent),
+        (12, Event::TaxPayment),
+        (20, Event::Payroll),
+        (28, Event::Depreciation),
+    ] {
+        for ts in monthly(start, stop, day)? {
+            events.push((ts, event));
+        }
+    }
+    let trades = count.saturating_sub(events.len() as u32);
+    for (n, ts) in spread(start, stop, trades)?.into_iter().enumerate() {
+        events.push((ts, Event::Trade(n as u32)));
+    }
+    let days = i128::from((stop.timestamp().as_second() - start.timestamp().as_second()) / 86_400);
+    // Every fourth trade is sales invoice, and every fourth is purchase bill
+    let rounds = i128::from(trades.div_ceil(4)).max(1);
+    let sales_ppm = days * DAILY_SALES_CENTS * 1_000_000 / (rounds * MEAN_INVOICE_CENTS);
+    let purchases_ppm = days * DAILY_PURCHASES_CENTS * 1_000_000 / (rounds * MEAN_BILL_CENTS);
+    events.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut receivables: VecDeque<(u32, i64, i128)> = VecDeque::new();
+    let mut payables: VecDeque<(u32, i64, i128)> = VecDeque::new();
+    let mut vat_output: i128 = 0;
+    let mut vat_input: i128 = 0;
+    let mut withholding: i128 = 0;
+    let mut social_security: i128 = 0;
+    let mut replacements = 0;
+    let mut txns = Vec::with_capacity(count as usize);
+    for (ts, event) in events {
+        if txns.len() == count as usize {
+            break;
+        }
+        let skipped = event == Event::TaxPayment
+            && vat_output == 0
+            && vat_input == 0
+            && withholding == 0
+            && social_security == 0;
+        let event = if skipped {
+            replacements += 1;
+            Event::Trade(trades + replacements - 1)
+        } else {
+            event
+        };
+        let txn = match event {
+            Event::Opening => ScenarioTxn::new(ts, "Opening balances")
+                .posting(BANK, OPENING_BANK_CENTS)
+                .posting(EQUIPMENT, OPENING_EQUIPMENT_CENTS)
+                .posting(
+                    SHARE_CAPITAL,
+                    -(OPENING_BANK_CENTS + OPENING_EQUIPMENT_CENTS),
+                ),
+            Event::Rent => {
+                let vat = vat_of(RENT_CENTS, VAT_RATES[0]);
+                vat_input += vat;
+                ScenarioTxn::new(ts, "Office rent")
+                    .posting(RENT, RENT_CENTS)
+                    .posting(VAT_INPUT, vat)
+                    .posting(BANK, -(RENT_CENTS + vat))
+            }
+            Event::TaxPayment => {
+                let mut txn = ScenarioTxn::new(ts, "Tax payment");
+                for (account, cents) in [
+                    (VAT_OUTPUT, vat_output),
+                    (VAT_INPUT, -vat_input),
+                    (WITHHOLDING, withholding),
+                    (SOCIAL_SECURITY, social_security),
+                ] {
+                    if cents != 0 {
+                        txn = txn.posting(account, cents);
+                    }
+                }
+                let total = vat_output - vat_input + withholding + social_security;
+                (vat_output, vat_input, withholding, social_security) = (0, 0, 0, 0);
+                txn.posting(BANK, -total)
+            }
+            Event::Payroll => {
+                let tax = SALARIES_CENTS * WITHHOLDING_PCT / 100;
+                let social = SALARIES_CENTS * SOCIAL_SECURITY_PCT / 100;
+                withholding += tax;
+                social_security += social;
+                ScenarioTxn::new(ts, "Payroll")
+                    .posting(SALARIES, SALARIES_CENTS)
+                    .posting(SOCIAL_SECURITY_COST, social)
+                    .posting(WITHHOLDING, -tax)
+                    .posting(SOCIAL_SECURITY, -social)
+                    .posting(BANK, -(SALARIES_CENTS - tax))
+            }
+            Event::Depreciation => ScenarioTxn::new(ts, "Depreciation of equipment")
+                .posting(DEPRECIATION, DEPRECIATION_CENTS)
+                .posting(ACC_DEPRECIATION, -DEPRECIATION_CENTS),
+            Event::Trade(n) => {
+                let rate = VAT_RATES[(n / 4 % VAT_RATES.len() as u32) as usize];
+                match n % 4 {
+                    0 => {
+                        let (desc, account) = if n / 4 % 2 == 0 {
+                            ("Invoice: consulting", SERVICES)
+                        } else {
+                            ("Invoice: goods", GOODS)
+                        };
+                        let net = scaled_cents(seed, &ts, cents(40), cents(1000), sales_ppm);
+                        let vat = vat_of(net, rate);
+                        vat_output += vat;
+                        receivables.push_back((n, ts.timestamp().as_second(), net + vat));
+                        ScenarioTxn::new(ts, &format!("{desc}, {}", invoice_id(n)))
+                            .posting(RECEIVABLES, net + vat)
+                            .posting(account, -net)
+                            .posting(VAT_OUTPUT, -vat)
+                    }
+                    1 => {
+                        let (desc, account) = if n / 4 % 2 == 0 {
+                            ("Bill: office supplies", SUPPLIES)
+                        } else {
+                            ("Bill: software", SOFTWARE)
+                        };
+                        let net = scaled_cents(seed, &ts, cents(10), cents(320), purchases_ppm);
+                        let vat = vat_of(net, rate);
+                        vat_input += vat;
+                        payables.push_back((n, ts.timestamp().as_second(), net + vat));
+                        ScenarioTxn::new(ts, &format!("{desc}, {}", bill_id(n)))
+                            .posting(account, net)
+                            .posting(VAT_INPUT, vat)
+                            .posting(PAYABLES, -(net + vat))
+                    }
+                    2 => match due(&mut receivables, &ts) {
+                        Some((id, cents)) => {
+                            ScenarioTxn::new(ts, &format!("Payment of {}", invoice_id(id)))
+                                .posting(BANK, cents)
+                                .posting(RECEIVABLES, -cents)
+                        }
+                        None => {
+                            let net = scaled_cents(seed, &ts, cents(40), cents(1000), sales_ppm);
+                            let vat = vat_of(net, rate);
+                            vat_output += vat;
+                            ScenarioTxn::new(ts, "Cash sale")
+                                .posting(BANK, net + vat)
+                                .posting(GOODS, -net)
+                                .posting(VAT_OUTPUT, -vat)
+                        }
+                    },
+                    _ => match due(&mut payables, &ts) {
+                        Some((id, cents)) => {
+                            ScenarioTxn::new(ts, &format!("Payment of {}", bill_id(id)))
+                                .posting(PAYABLES, cents)
+                                .posting(BANK, -cents)
+                        }
+                        None => {
+                            let net = scaled_cents(seed, &ts, cents(10), cents(320), purchases_ppm);
+                            let vat = vat_of(net, rate);
+                            vat_input += vat;
+                            ScenarioTxn::new(ts, "Cash purchase")
+                                .posting(SUPPLIES, net)
+                                .posting(VAT_INPUT, vat)
+                                .posting(BANK, -(net + vat))
+                        }
+                    },
+                }
+            }
+        };
+        txns.push(txn);
+    }
+    Ok(txns)
+}
+
+/// Oldest open invoice or bill, if its payment term has passed
+fn due(open: &mut VecDeque<(u32, i64, i128)>, ts: &Zoned) -> Option<(u32, i128)> {
+    match open.front() {
+        Some((_, issued, _)) if issued + PAYMENT_TERM_SECS <= ts.timestamp().as_second() => {
+            open.pop_front().map(|(id, _, cents)| (id, cents))
+        }
+        _ => None,
+    }
+}
+
+fn invoice_id(n: u32) -> String {
+    format!("INV-{:0>6}", n / 4 + 1)
+}
+
+fn bill_id(n: u32) -> String {
+    format!("BILL-{:0>6}", n / 4 + 1)
+}
+
+/// VAT of net amount, rounded half up to cents
+fn vat_of(net: i128, rate: i128) -> i128 {
+    (net * rate + 50) / 100
+}
+
+/// Random amount in cents between `min` and `max`, scaled by `ppm`
+fn scaled_cents(seed: u64, ts: &Zoned, min: i128, max: i128, ppm: i128) -> i128 {
+    let mut rng = Prng::new(seed, STREAM, ts.timestamp().as_second() as u64);
+    let cents = min + rng.next_below((max - min + 1) as u128) as i128;
+    (cents * ppm / 1_000_000).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenarios::business::{
+        BANK, PAYABLES, RECEIVABLES, VAT_INPUT, VAT_OUTPUT, generate, vat_of,
+    };
+    use jiff::Zoned;
+
+    #[test]
+    fn test_vat() {
+        assert_eq!(vat_of(10_000, 24), 2_400);
+        assert_eq!(vat_of(1_234, 14), 173);
+        assert_eq!(vat_of(5, 10), 1);
+    }
+
+    #[test]
+    fn test_business() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2025-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let txns = generate(&start, &stop, 1000, 0).unwrap(/*:test:*/);
+
+        assert_eq!(txns.len(), 1000);
+        assert_eq!(txns[0].desc, "Opening balances");
+        assert!(txns.iter().all(|txn| txn.is_balanced()));
+        assert!(txns.windows(2).all(|w| w[0].ts <= w[1].ts));
+        assert!(txns.iter().any(|txn| txn.postings.len() >= 5));
+
+        let balance = |account: &str| -> i128 {
+            txns.iter()
+                .flat_map(|txn| txn.postings.iter())
+                .filter(|p| p.account == account)
+                .map(|p| p.amount.units)
+                .sum()
+        };
+        assert!(balance(BANK) > 0);
+        assert!(balance(RECEIVABLES) >= 0);
+        assert!(balance(PAYABLES) <= 0);
+        // December's VAT is not paid yet
+        assert!(balance(VAT_OUTPUT) < 0);
+        assert!(balance(VAT_INPUT) > 0);
+
+        // Set ends at count, if there are more monthly transactions
+        let txns = generate(&start, &stop, 10, 0).unwrap(/*:test:*/);
+        assert_eq!(txns.len(), 10);
+        assert_eq!(txns[9].ts.month(), 3);
+    }
+
+    #[test]
+    fn test_count() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        for (stop, count) in [
+            ("2024-01-02T00:00:00+00:00[UTC]", 10),
+            ("2025-01-01T00:00:00+00:00[UTC]", 49),
+            ("2025-01-01T00:00:00+00:00[UTC]", 100),
+            ("2034-01-01T00:00:00+00:00[UTC]", 1000),
+            ("2034-01-01T00:00:00+00:00[UTC]", 10_000),
+        ] {
+            let stop: Zoned = stop.parse().unwrap(/*:test:*/);
+            let txns = generate(&start, &stop, count, 0).unwrap(/*:test:*/);
+            assert_eq!(txns.len(), count as usize, "{stop} {count}");
+            assert!(txns.iter().all(|txn| txn.is_balanced()));
+        }
+    }
+}
@@ -0,0 +1,192 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use crate::prng::Prng;
+use crate::scenarios::{SCALE, ScenarioTxn, cents, monthly, spread};
+use jiff::Zoned;
+use std::error::Error;
+
+const BANK: &str = "Assets:Bank:Checking";
+const INVENTORY: &str = "Assets:Inventory";
+const OPENING: &str = "Equity:Opening-Balances";
+const SALES: &str = "Income:Sales";
+const COGS: &str = "Expenses:Cost-of-Goods-Sold";
+const SHRINKAGE: &str = "Expenses:Inventory:Shrinkage";
+
+/// PRNG stream for inventory scenario
+const STREAM: u64 = 6;
+
+const OPENING_BANK_CENTS: i128 = cents(50000);
+
+/// Goods in stock
+struct Good {
+    name: &'static str,
+    /// Scale of quantity, e.g. 0 for items and 1 for kilograms
+    scale: u32,
+    /// Unit cost, in cents (in dimes, if quantity has decimals)
+    cost: i128,
+    /// Unit sales price, in cents
+    price: i128,
+}
+
+#[rustfmt::skip]
+const GOODS: [Good; 5] = [
+    Good { name: "Widget A", scale: 0, cost: 1250, price: 1990 },
+    Good { name: "Widget B", scale: 0, cost: 830, price: 1490 },
+    Good { name: "Gadget 2000", scale: 0, cost: 4500, price: 7900 },
+    Good { name: "Coffee Beans kg", scale: 1, cost: 1820, price: 2990 },
+    Good { name: "Support Hours", scale: 1, cost: 4000, price: 6500 },
+];
+
+/// Stock is replenished, when it's below this many units
+const REORDER_POINT: i128 = 20;
+/// Received quantity, in units
+const RECEIPT_UNITS: (i128, i128) = (40, 120);
+/// Sold quantity, in units
+const SALE_UNITS: (i128, i128) = (1, 12);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Opening,
+    StockCount,
+    Trade(u32),
+}
+
+/// Trading company: stock receipts and sales of goods with per-unit costs,
+/// cost of goods sold and monthly stock count with shrinkage.
+///
+/// Goods are counted in items, kilograms and hours, and inventory
+/// postings have quantity of goods with unit cost in `CURRENCY`.
+/// Goods are received when their stock is low, otherwise they are sold.
+/// Receipts and sales fill the set up to `count` transactions.
+pub fn generate(
+    start: &Zoned,
+    stop: &Zoned,
+    count: u32,
+    seed: u64,
+) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+    let mut events = vec![(start.clone(), Event::Opening)];
+    for ts in monthly(start, stop, 28)? {
+        events.push((ts, Event::StockCount));
+    }
+    let trades = count.saturating_sub(events.len() as u32);
+    for (n, ts) in spread(start, stop, trades)?.into_iter().enumerate() {
+        events.push((ts, Event::Trade(n as u32)));
+    }
+    events.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    // Stock of goods, in units of their scale
+    let mut stock = [0i128; GOODS.len()];
+    let mut txns = Vec::with_capacity(events.len());
+    for (ts, event) in events {
+        let txn = match event {
+            Event::Opening => ScenarioTxn::new(ts, "Opening balances")
+                .posting(BANK, OPENING_BANK_CENTS)
+                .posting(OPENING, -OPENING_BANK_CENTS),
+            Event::StockCount => {
+                let g = ts.month() as usize % GOODS.len();
+                let good = &GOODS[g];
+                let lost = Amount::new(10i128.pow(good.scale), good.scale);
+                if stock[g] < lost.units {
+                    continue;
+                }
+                stock[g] -= lost.units;
+                ScenarioTxn::new(ts, &format!("Stock count: {}", good.name))
+                    .posting(SHRINKAGE, good.cost)
+                    .priced_posting(INVENTORY, lost.neg(), good.name, good.cost)
+            }
+            Event::Trade(n) => {
+                let mut rng = Prng::new(seed, STREAM, u64::from(n));
+                let g = rng.next_below(GOODS.len() as u128) as usize;
+                let good = &GOODS[g];
+                let unit = 10i128.pow(good.scale);
+                if stock[g] < REORDER_POINT * unit {
+                    let qty = random_qty(&mut rng, RECEIPT_UNITS, unit);
+                    stock[g] += qty;
+                    let quantity = Amount::new(qty, good.scale);
+                    let value = value_cents(&quantity, good.cost);
+                    ScenarioTxn::new(ts, &format!("Receipt: {quantity} {}", good.name))
+                        .priced_posting(INVENTORY, quantity, good.name, good.cost)
+                        .posting(BANK, -value)
+                } else {
+                    let qty = random_qty(&mut rng, SALE_UNITS, unit).min(stock[g]);
+                    stock[g] -= qty;
+                    let quantity = Amount::new(qty, good.scale);
+                    let revenue = value_cents(&quantity, good.price);
+                    let cost = value_cents(&quantity, good.cost);
+                    ScenarioTxn::new(ts, &format!("Sale: {quantity} {}", good.name))
+                        .posting(BANK, revenue)
+                        .posting(SALES, -revenue)
+                        .posting(COGS, cost)
+                        .priced_posting(INVENTORY, quantity.neg(), good.name, good.cost)
+                }
+            }
+        };
+        txns.push(txn);
+    }
+    Ok(txns)
+}
+
+/// Random quantity between `units`, in units of `unit`
+///
+/// Quantity of goods with decimals has also random decimals.
+fn random_qty(rng: &mut Prng, units: (i128, i128), unit: i128) -> i128 {
+    let (min, max) = (units.0 * unit, units.1 * unit);
+    min + rng.next_below((max - min + 1) as u128) as i128
+}
+
+/// Value of quantity with unit price, rounded to cents
+///
+/// Value is exact, when unit prices of goods with decimal quantities are in dimes.
+fn value_cents(quantity: &Amount, price_cents: i128) -> i128 {
+    quantity
+        .checked_mul(&Amount::new(price_cents, SCALE))
+        .map_or(0, |value| value.round(SCALE).units)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::Amount;
+    use crate::scenarios::inventory::{BANK, generate, value_cents};
+    use jiff::Zoned;
+
+    #[test]
+    fn test_value() {
+        assert_eq!(value_cents(&Amount::new(3, 0), 1250), 3750);
+        assert_eq!(value_cents(&Amount::new(15, 1), 1820), 2730);
+        assert_eq!(value_cents(&Amount::new(3, 1), 1825), 548);
+    }
+
+    #[test]
+    fn test_inventory() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2025-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let txns = generate(&start, &stop, 1000, 0).unwrap(/*:test:*/);
+
+        assert_eq!(txns.len(), 1000);
+        assert!(txns.iter().all(|txn| txn.is_balanced()));
+        assert!(txns.windows(2).all(|w| w[0].ts <= w[1].ts));
+        assert!(txns.iter().any(|txn| txn.desc.starts_with("Sale: ")));
+        assert!(txns.iter().any(|txn| txn.desc.starts_with("Stock count: ")));
+
+        // Stock of each good is never negative
+        for good in ["Widget A", "Coffee Beans kg"] {
+            let mut stock = 0;
+            for p in txns.iter().flat_map(|txn| txn.postings.iter()) {
+                if p.commodity == good {
+                    stock += p.amount.units;
+                    assert!(stock >= 0);
+                }
+            }
+        }
+        let bank: i128 = txns
+            .iter()
+            .flat_map(|txn| txn.postings.iter())
+            .filter(|p| p.account == BANK)
+            .map(|p| p.amount.units)
+            .sum();
+        assert!(bank > 0);
+    }
+}
@@ -0,0 +1,239 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::prng::Prng;
+use crate::scenarios::{ScenarioTxn, cents, monthly, spread};
+use jiff::Zoned;
+use std::error::Error;
+
+const CHECKING: &str = "Assets:Bank:Checking";
+const SAVINGS: &str = "Assets:Bank:Savings";
+const CREDIT_CARD: &str = "Liabilities:CreditCard";
+const LOAN: &str = "Liabilities:Loan";
+const OPENING: &str = "Equity:Opening-Balances";
+const SALARY: &str = "Income:Salary";
+const RENT: &str = "Expenses:Housing:Rent";
+const UTILITIES: &str = "Expenses:Housing:Utilities";
+const GROCERIES: &str = "Expenses:Food:Groceries";
+const RESTAURANTS: &str = "Expenses:Food:Restaurants";
+const TRANSPORT: &str = "Expenses:Transport";
+const INTEREST: &str = "Expenses:Interest:Loan";
+
+/// PRNG stream for personal scenario
+const STREAM: u64 = 4;
+
+/// Monthly salary, in cents
+const SALARY_CENTS: i128 = cents(3200);
+/// Monthly rent, in cents
+const RENT_CENTS: i128 = cents(1100);
+/// Monthly transfer to savings, in cents
+const SAVINGS_CENTS: i128 = cents(400);
+/// Monthly loan payment (annuity), in cents
+const LOAN_PAYMENT_CENTS: i128 = cents(350);
+/// Budget of everyday purchases per day, in cents
+const DAILY_BUDGET_CENTS: i128 = cents(30);
+/// Mean of everyday purchase, in cents
+const MEAN_PURCHASE_CENTS: i128 = 4050;
+/// Loan interest per month, in 1/10_000 (4.8% per year)
+const LOAN_MONTHLY_RATE: i128 = 40;
+
+const OPENING_CHECKING_CENTS: i128 = cents(3000);
+const OPENING_SAVINGS_CENTS: i128 = cents(10000);
+const OPENING_LOAN_CENTS: i128 = cents(12000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Opening,
+    Rent,
+    Utilities,
+    CardPayment,
+    LoanPayment,
+    Salary,
+    Savings,
+    Purchase(u32),
+}
+
+/// Household: salary income, rent and utilities, everyday purchases
+/// with debit and credit card, credit card payments, savings transfers
+/// and a loan with interest.
+///
+/// Everyday purchases fill the set up to `count` transactions, and the set
+/// has always `count` transactions: if there are more recurring transactions,
+/// the set ends at `count`, and a recurring transaction with nothing to pay
+/// (e.g. credit card payment without balance) is replaced by a purchase.
+/// If there are many purchases, their amounts are scaled down
+/// to keep the everyday spending within the daily budget.
+pub fn generate(
+    start: &Zoned,
+    stop: &Zoned,
+    count: u32,
+    seed: u64,
+) -> Result<Vec<ScenarioTxn>, Box<dyn Error>> {
+    let mut events = vec![(start.clone(), Event::Opening)];
+    for (day, event) in [
+        (1, Event::Rent),
+        (5, Event::Utilities),
+        (10, Event::CardPayment),
+        (15, Event::LoanPayment),
+        (25, Event::Salary),
+        (26, Event::Savings),
+    ] {
+        for ts in monthly(start, stop, day)? {
+            events.push((ts, event));
+        }
+    }
+    let purchases = count.saturating_sub(events.len() as u32);
+    for (n, ts) in spread(start, stop, purchases)?.into_iter().enumerate() {
+        events.push((ts, Event::Purchase(n as u32)));
+    }
+    let days = i128::from((stop.timestamp().as_second() - start.timestamp().as_second()) / 86_400);
+    let spending = i128::from(purchases) * MEAN_PURCHASE_CENTS;
+    let budget_ppm = if spending > 0 {
+        (days * DAILY_BUDGET_CENTS * 1_000_000 / spending).min(1_000_000)
+    } else {
+        1_000_000
+    };
+    events.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut card_balance: i128 = 0;
+    let mut loan_balance: i128 = OPENING_LOAN_CENTS;
+    let mut replacements = 0;
+    let mut txns = Vec::with_capacity(count as usize);
+    for (ts, event) in events {
+        if txns.len() == count as usize {
+            break;
+        }
+        let skipped = match event {
+            Event::CardPayment => card_balance == 0,
+            Event::LoanPayment => loan_balance == 0,
+            _ => false,
+        };
+        let event = if skipped {
+            replacements += 1;
+            Event::Purchase(purchases + replacements - 1)
+        } else {
+            event
+        };
+        let txn = match event {
+            Event::Opening => ScenarioTxn::new(ts, "Opening balances")
+                .posting(CHECKING, OPENING_CHECKING_CENTS)
+                .posting(SAVINGS, OPENING_SAVINGS_CENTS)
+                .posting(LOAN, -OPENING_LOAN_CENTS)
+                .posting(
+                    OPENING,
+                    OPENING_LOAN_CENTS - OPENING_CHECKING_CENTS - OPENING_SAVINGS_CENTS,
+                ),
+            Event::Rent => ScenarioTxn::new(ts, "Rent")
+                .posting(RENT, RENT_CENTS)
+                .posting(CHECKING, -RENT_CENTS),
+            Event::Utilities => {
+                let cents = random_cents(seed, &ts, cents(60), cents(140));
+                ScenarioTxn::new(ts, "Electricity and water")
+                    .posting(UTILITIES, cents)
+                    .posting(CHECKING, -cents)
+            }
+            Event::CardPayment => {
+                let cents = card_balance;
+                card_balance = 0;
+                ScenarioTxn::new(ts, "Credit card payment")
+                    .posting(CREDIT_CARD, cents)
+                    .posting(CHECKING, -cents)
+            }
+            Event::LoanPayment => {
+                let interest = (loan_balance * LOAN_MONTHLY_RATE + 5_000) / 10_000;
+                let principal = (LOAN_PAYMENT_CENTS - interest).min(loan_balance);
+                loan_balance -= principal;
+                ScenarioTxn::new(ts, "Loan payment")
+                    .posting(LOAN, principal)
+                    .posting(INTEREST, interest)
+                    .posting(CHECKING, -(principal + interest))
+            }
+            Event::Salary => ScenarioTxn::new(ts, "Salary")
+                .posting(CHECKING, SALARY_CENTS)
+                .posting(SALARY, -SALARY_CENTS),
+            Event::Savings => ScenarioTxn::new(ts, "Transfer to savings")
+                .posting(SAVINGS, SAVINGS_CENTS)
+                .posting(CHECKING, -SAVINGS_CENTS),
+            Event::Purchase(n) => {
+                let (desc, account, min, max, by_card) = match n % 4 {
+                    0 => ("Groceries", GROCERIES, cents(15), cents(120), true),
+                    1 => ("Groceries", GROCERIES, cents(5), cents(60), false),
+                    2 => ("Restaurant", RESTAURANTS, cents(12), cents(60), true),
+                    _ => ("Public transport", TRANSPORT, cents(2), cents(50), true),
+                };
+                let cents = (random_cents(seed, &ts, min, max) * budget_ppm / 1_000_000).max(1);
+                let source = if by_card {
+                    card_balance += cents;
+                    CREDIT_CARD
+                } else {
+                    CHECKING
+                };
+                ScenarioTxn::new(ts, desc)
+                    .posting(account, cents)
+                    .posting(source, -cents)
+            }
+        };
+        txns.push(txn);
+    }
+    Ok(txns)
+}
+
+/// Random amount in cents between `min` and `max`, based on timestamp
+fn random_cents(seed: u64, ts: &Zoned, min: i128, max: i128) -> i128 {
+    let mut rng = Prng::new(seed, STREAM, ts.timestamp().as_second() as u64);
+    min + rng.next_below((max - min + 1) as u128) as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenarios::cents;
+    use crate::scenarios::personal::{CHECKING, CREDIT_CARD, LOAN, generate};
+    use jiff::Zoned;
+
+    #[test]
+    fn test_personal() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2025-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let txns = generate(&start, &stop, 1000, 0).unwrap(/*:test:*/);
+
+        assert_eq!(txns.len(), 1000);
+        assert_eq!(txns[0].desc, "Opening balances");
+        assert!(txns.iter().all(|txn| txn.is_balanced()));
+        assert!(txns.windows(2).all(|w| w[0].ts <= w[1].ts));
+
+        let balance = |account: &str| -> i128 {
+            txns.iter()
+                .flat_map(|txn| txn.postings.iter())
+                .filter(|p| p.account == account)
+                .map(|p| p.amount.units)
+                .sum()
+        };
+        assert!(balance(CHECKING) > 0);
+        assert!(balance(CREDIT_CARD) < 0);
+        assert!(balance(LOAN) < 0);
+        assert!(balance(LOAN) > -cents(12_000));
+
+        // Set ends at count, if there are more recurring transactions
+        let txns = generate(&start, &stop, 10, 0).unwrap(/*:test:*/);
+        assert_eq!(txns.len(), 10);
+        assert_eq!(txns[9].ts.month(), 2);
+    }
+
+    #[test]
+    fn test_count() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        for (stop, count) in [
+            ("2024-01-02T00:00:00+00:00[UTC]", 10),
+            ("2025-01-01T00:00:00+00:00[UTC]", 73),
+            ("2025-01-01T00:00:00+00:00[UTC]", 100),
+            ("2034-01-01T00:00:00+00:00[UTC]", 1000),
+            ("2034-01-01T00:00:00+00:00[UTC]", 10_000),
+        ] {
+            let stop: Zoned = stop.parse().unwrap(/*:test:*/);
+            let txns = generate(&start, &stop, count, 0).unwrap(/*:test:*/);
+            assert_eq!(txns.len(), count as usize, "{stop} {count}");
+            assert!(txns.iter().all(|txn| txn.is_balanced()));
+        }
+    }
+}
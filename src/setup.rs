@@ -3,24 +3,54 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use jiff::Zoned;
+use crate::txn_uuid::{txn_uuid_of, uuid_bucket};
 use jiff::fmt::strtime;
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
+use template::ShardTemplate;
+
+pub mod template;
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub enum ShardType {
     #[default]
     Single,
+    Year,
     Month,
+    /// ISO week
+    Week,
+    Day,
     Txn,
+    /// User-defined shard path, see `ShardTemplate`
+    Template,
+    /// Fixed count of txns per journal
+    Count,
+    /// Maximum size of journal in bytes
+    Size,
+    /// Own file for each txn, in buckets by hash of txn uuid
+    Hash,
 }
 
 impl ShardType {
     pub const SINGLE: &'static str = "single";
+    pub const YEAR: &'static str = "year";
     pub const MONTH: &'static str = "month";
+    pub const WEEK: &'static str = "week";
+    pub const DAY: &'static str = "day";
     pub const TXN: &'static str = "txn";
+    pub const TEMPLATE: &'static str = "template";
+    pub const COUNT: &'static str = "count";
+    pub const SIZE: &'static str = "size";
+    pub const HASH: &'static str = "hash";
+
+    /// Each txn is in own file
+    pub fn txn_per_file(&self) -> bool {
+        matches!(self, ShardType::Txn | ShardType::Hash)
+    }
 }
 
 impl TryFrom<&str> for ShardType {
@@ -28,14 +58,28 @@ impl TryFrom<&str> for ShardType {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             Self::SINGLE => Ok(ShardType::Single),
+            Self::YEAR => Ok(ShardType::Year),
             Self::MONTH => Ok(ShardType::Month),
+            Self::WEEK => Ok(ShardType::Week),
+            Self::DAY => Ok(ShardType::Day),
             Self::TXN => Ok(ShardType::Txn),
+            Self::TEMPLATE => Ok(ShardType::Template),
+            Self::COUNT => Ok(ShardType::Count),
+            Self::SIZE => Ok(ShardType::Size),
+            Self::HASH => Ok(ShardType::Hash),
             _ => Err(format!(
-                "Unknown shard type: {}, supported types are: {}, {}, {}",
+                "Unknown shard type: {}, supported types are: {}, {}, {}, {}, {}, {}, {}, {}, {}, {}",
                 value,
                 Self::SINGLE,
+                Self::YEAR,
                 Self::MONTH,
-                Self::TXN
+                Self::WEEK,
+                Self::DAY,
+                Self::TXN,
+                Self::TEMPLATE,
+                Self::COUNT,
+                Self::SIZE,
+                Self::HASH
             )
             .into()),
         }
@@ -46,8 +90,15 @@ impl Display for ShardType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ShardType::Single => write!(f, "{}", Self::SINGLE),
+            ShardType::Year => write!(f, "{}", Self::YEAR),
             ShardType::Month => write!(f, "{}", Self::MONTH),
+            ShardType::Week => write!(f, "{}", Self::WEEK),
+            ShardType::Day => write!(f, "{}", Self::DAY),
             ShardType::Txn => write!(f, "{}", Self::TXN),
+            ShardType::Template => write!(f, "{}", Self::TEMPLATE),
+            ShardType::Count => write!(f, "{}", Self::COUNT),
+            ShardType::Size => write!(f, "{}", Self::SIZE),
+            ShardType::Hash => write!(f, "{}", Self::HASH),
         }
     }
 }
@@ -171,11 +222,376 @@ impl Display for JournalFlavor {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub const LF: &'static str = "lf";
+    pub const CRLF: &'static str = "crlf";
+}
+
+impl TryFrom<&str> for LineEnding {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::LF => Ok(LineEnding::Lf),
+            Self::CRLF => Ok(LineEnding::Crlf),
+            _ => Err(format!(
+                "Unknown line ending: {}, supported line endings are: {}, {}",
+                value,
+                Self::LF,
+                Self::CRLF
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineEnding::Lf => write!(f, "{}", Self::LF),
+            LineEnding::Crlf => write!(f, "{}", Self::CRLF),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum IndentStyle {
+    #[default]
+    Spaces,
+    Tabs,
+    Mixed,
+}
+
+impl IndentStyle {
+    pub const SPACES: &'static str = "spaces";
+    pub const TABS: &'static str = "tabs";
+    pub const MIXED: &'static str = "mixed";
+}
+
+impl TryFrom<&str> for IndentStyle {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::SPACES => Ok(IndentStyle::Spaces),
+            Self::TABS => Ok(IndentStyle::Tabs),
+            Self::MIXED => Ok(IndentStyle::Mixed),
+            _ => Err(format!(
+                "Unknown indent style: {}, supported styles are: {}, {}, {}",
+                value,
+                Self::SPACES,
+                Self::TABS,
+                Self::MIXED
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for IndentStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndentStyle::Spaces => write!(f, "{}", Self::SPACES),
+            IndentStyle::Tabs => write!(f, "{}", Self::TABS),
+            IndentStyle::Mixed => write!(f, "{}", Self::MIXED),
+        }
+    }
+}
+
+/// Text format of journal files
+///
+/// Generators produce text with LF line endings and two-space indentation,
+/// and that is reformatted with this format when journals are written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalFormat {
+    pub line_ending: LineEnding,
+    pub indent_style: IndentStyle,
+    /// Count of spaces for indentation with spaces,
+    /// indentation with tabs is always one tab
+    pub indent_width: usize,
+    pub bom: bool,
+}
+
+impl Default for JournalFormat {
+    fn default() -> Self {
+        JournalFormat {
+            line_ending: LineEnding::Lf,
+            indent_style: IndentStyle::Spaces,
+            indent_width: 2,
+            bom: false,
+        }
+    }
+}
+
+impl JournalFormat {
+    const GEN_INDENT: &'static str = "  ";
+
+    /// Reformat text produced by generators
+    ///
+    /// With `IndentStyle::Mixed`, every other indented line
+    /// of text is indented with tabs.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.line_ending == LineEnding::Lf
+            && self.indent_style == IndentStyle::Spaces
+            && self.indent_width == Self::GEN_INDENT.len()
+        {
+            return Cow::Borrowed(text);
+        }
+        let eol = match self.line_ending {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        };
+        let spaces = " ".repeat(self.indent_width);
+        let tabs = "\t".to_string();
+
+        let mut out = String::with_capacity(text.len() + text.len() / 8);
+        let mut indented = 0;
+        for line in text.split_inclusive('\n') {
+            let (content, newline) = match line.strip_suffix('\n') {
+                Some(c) => (c, true),
+                None => (line, false),
+            };
+            match content.strip_prefix(Self::GEN_INDENT) {
+                Some(rest) => {
+                    let indent = match self.indent_style {
+                        IndentStyle::Spaces => &spaces,
+                        IndentStyle::Tabs => &tabs,
+                        IndentStyle::Mixed if indented % 2 == 0 => &spaces,
+                        IndentStyle::Mixed => &tabs,
+                    };
+                    indented += 1;
+                    out.push_str(indent);
+                    out.push_str(rest);
+                }
+                None => out.push_str(content),
+            }
+            if newline {
+                out.push_str(eol);
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// What to do with an existing txn set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OutputPolicy {
+    /// Fail if the set exists
+    #[default]
+    Create,
+    /// Replace the set after the new set is complete
+    Overwrite,
+    /// Keep the existing set, and generate the set only if it's missing
+    SkipExisting,
+}
+
+/// Archive format of txn set
+#[derive(Debug, Clone, PartialEq)]
+pub enum Archive {
+    /// Set as tar archive
+    Tar,
+    /// Set as gzip-compressed tar archive
+    TarGz,
+    /// Set as zstd-compressed tar archive
+    TarZst,
+    /// Gzip-compressed journal of single shard set
+    Gz,
+}
+
+impl Archive {
+    pub const TAR: &'static str = "tar";
+    pub const TAR_GZ: &'static str = "tar.gz";
+    pub const TAR_ZST: &'static str = "tar.zst";
+    pub const GZ: &'static str = "gz";
+}
+
+impl TryFrom<&str> for Archive {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Archive::TAR => Ok(Archive::Tar),
+            Archive::TAR_GZ => Ok(Archive::TarGz),
+            Archive::TAR_ZST => Ok(Archive::TarZst),
+            Archive::GZ => Ok(Archive::Gz),
+            _ => Err(format!("Unknown archive format: '{value}'").into()),
+        }
+    }
+}
+
+impl Display for Archive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Archive::Tar => write!(f, "{}", Self::TAR),
+            Archive::TarGz => write!(f, "{}", Self::TAR_GZ),
+            Archive::TarZst => write!(f, "{}", Self::TAR_ZST),
+            Archive::Gz => write!(f, "{}", Self::GZ),
+        }
+    }
+}
+
+/// Parameters of shard types, each of them is used only with its own shard type
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShardOptions {
+    /// Shard paths of `ShardType::Template`
+    pub template: Option<ShardTemplate>,
+    /// Txns per journal of `ShardType::Count`
+    pub txns: Option<u32>,
+    /// Maximum size of journal in bytes of `ShardType::Size`
+    pub bytes: Option<u64>,
+    /// Count of buckets of `ShardType::Hash`
+    pub buckets: Option<u32>,
+}
+
+/// Output options of txn set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Output {
+    pub policy: OutputPolicy,
+    pub archive: Option<Archive>,
+    pub shards: ShardOptions,
+}
+
+impl From<OutputPolicy> for Output {
+    fn from(policy: OutputPolicy) -> Self {
+        Output {
+            policy,
+            archive: None,
+            shards: ShardOptions::default(),
+        }
+    }
+}
+
+/// Format of time tracking journal of (h)ledger
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TimeFormat {
+    #[default]
+    Timeclock,
+    Timedot,
+}
+
+impl TimeFormat {
+    pub const TIMECLOCK: &'static str = "timeclock";
+    pub const TIMEDOT: &'static str = "timedot";
+}
+
+impl TryFrom<&str> for TimeFormat {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::TIMECLOCK => Ok(TimeFormat::Timeclock),
+            Self::TIMEDOT => Ok(TimeFormat::Timedot),
+            _ => Err(format!(
+                "Unknown time format: {}, supported formats are: {}, {}",
+                value,
+                Self::TIMECLOCK,
+                Self::TIMEDOT
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for TimeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeFormat::Timeclock => write!(f, "{}", Self::TIMECLOCK),
+            TimeFormat::Timedot => write!(f, "{}", Self::TIMEDOT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    Unbalanced,
+    UnknownAccount,
+    UndeclaredCommodity,
+    MalformedDate,
+    DuplicateUuid,
+    BadIndentation,
+    TruncatedFile,
+}
+
+impl ErrorKind {
+    pub const UNBALANCED: &'static str = "unbalanced";
+    pub const UNKNOWN_ACCOUNT: &'static str = "unknown-account";
+    pub const UNDECLARED_COMMODITY: &'static str = "undeclared-commodity";
+    pub const MALFORMED_DATE: &'static str = "malformed-date";
+    pub const DUPLICATE_UUID: &'static str = "duplicate-uuid";
+    pub const BAD_INDENTATION: &'static str = "bad-indentation";
+    pub const TRUNCATED_FILE: &'static str = "truncated-file";
+
+    /// Error kinds which are supported by the flavor
+    pub fn supported(flavor: &JournalFlavor) -> Vec<ErrorKind> {
+        let mut kinds = vec![
+            ErrorKind::Unbalanced,
+            ErrorKind::UnknownAccount,
+            ErrorKind::UndeclaredCommodity,
+            ErrorKind::MalformedDate,
+        ];
+        if *flavor == JournalFlavor::Tackler {
+            kinds.push(ErrorKind::DuplicateUuid);
+        }
+        kinds.push(ErrorKind::BadIndentation);
+        kinds.push(ErrorKind::TruncatedFile);
+        kinds
+    }
+}
+
+impl TryFrom<&str> for ErrorKind {
+    type Error = Box<dyn Error>;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            Self::UNBALANCED => Ok(ErrorKind::Unbalanced),
+            Self::UNKNOWN_ACCOUNT => Ok(ErrorKind::UnknownAccount),
+            Self::UNDECLARED_COMMODITY => Ok(ErrorKind::UndeclaredCommodity),
+            Self::MALFORMED_DATE => Ok(ErrorKind::MalformedDate),
+            Self::DUPLICATE_UUID => Ok(ErrorKind::DuplicateUuid),
+            Self::BAD_INDENTATION => Ok(ErrorKind::BadIndentation),
+            Self::TRUNCATED_FILE => Ok(ErrorKind::TruncatedFile),
+            _ => Err(format!(
+                "Unknown error kind: {}, supported kinds are: {}, {}, {}, {}, {}, {}, {}",
+                value,
+                Self::UNBALANCED,
+                Self::UNKNOWN_ACCOUNT,
+                Self::UNDECLARED_COMMODITY,
+                Self::MALFORMED_DATE,
+                Self::DUPLICATE_UUID,
+                Self::BAD_INDENTATION,
+                Self::TRUNCATED_FILE
+            )
+            .into()),
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Unbalanced => write!(f, "{}", Self::UNBALANCED),
+            ErrorKind::UnknownAccount => write!(f, "{}", Self::UNKNOWN_ACCOUNT),
+            ErrorKind::UndeclaredCommodity => write!(f, "{}", Self::UNDECLARED_COMMODITY),
+            ErrorKind::MalformedDate => write!(f, "{}", Self::MALFORMED_DATE),
+            ErrorKind::DuplicateUuid => write!(f, "{}", Self::DUPLICATE_UUID),
+            ErrorKind::BadIndentation => write!(f, "{}", Self::BAD_INDENTATION),
+            ErrorKind::TruncatedFile => write!(f, "{}", Self::TRUNCATED_FILE),
+        }
+    }
+}
+
 pub struct JournalSetup {
     pub flavor: JournalFlavor,
     pub path: PathBuf,
     pub txn_set: SetSize,
     pub shard_type: ShardType,
+    /// Time tracking journal of (h)ledger, instead of regular journal
+    pub time_format: Option<TimeFormat>,
+    /// Set is written as archive or with compressed journal
+    pub archive: Option<Archive>,
+    pub shards: ShardOptions,
 }
 
 impl JournalSetup {
@@ -199,23 +615,43 @@ impl JournalSetup {
             txn_set: set,
             path: path.to_path_buf(),
             shard_type: shard_type.clone(),
+            time_format: None,
+            archive: None,
+            shards: ShardOptions::default(),
         })
     }
-    pub fn txn_set_dir(&self) -> String {
-        match self.shard_type {
-            ShardType::Single => {
-                // set-1e3-single
-                format!("set-{}-single", self.txn_set.str())
-            }
-            ShardType::Month => {
-                // set-1e3-month
-                format!("set-{}-month", self.txn_set.str())
+    /// Set parameters of shard types
+    ///
+    /// Shard type must have its parameter, and parameters of other types can't be used.
+    pub fn set_shard_options(&mut self, shards: ShardOptions) -> Result<(), Box<dyn Error>> {
+        let options = [
+            (
+                ShardType::Template,
+                shards.template.is_some(),
+                "--shard-template",
+            ),
+            (ShardType::Count, shards.txns.is_some(), "--shard-txns"),
+            (ShardType::Size, shards.bytes.is_some(), "--shard-bytes"),
+            (ShardType::Hash, shards.buckets.is_some(), "--shard-buckets"),
+        ];
+        for (shard_type, is_set, option) in options {
+            if self.shard_type == shard_type && !is_set {
+                let msg = format!("Shard type '{shard_type}' requires option '{option}'");
+                return Err(msg.into());
             }
-            ShardType::Txn => {
-                // set-1e3-txn
-                format!("set-{}-txn", self.txn_set.str())
+            if self.shard_type != shard_type && is_set {
+                let msg =
+                    format!("Option '{option}' can be used only with shard type '{shard_type}'");
+                return Err(msg.into());
             }
         }
+        self.shards = shards;
+        Ok(())
+    }
+
+    pub fn txn_set_dir(&self) -> String {
+        // set-1e3-single, set-1e3-month, set-1e3-txn, ...
+        format!("set-{}-{}", self.txn_set.str(), self.shard_type)
     }
 
     pub fn chart_of_accounts_path(&self) -> (PathBuf, PathBuf) {
@@ -237,6 +673,110 @@ impl JournalSetup {
         (chart_dir, chart_path)
     }
 
+    /// Path of document file, `name` is relative to the `docs` directory
+    pub fn document_path(&self, name: &str) -> (PathBuf, PathBuf) {
+        let doc_path = self.txn_set_path().join("docs").join(name);
+        let doc_dir = doc_path
+            .parent()
+            .expect("IE: missing parent for doc_path")
+            .to_path_buf();
+        (doc_dir, doc_path)
+    }
+
+    /// Path of expected errors file of invalid txn set
+    ///
+    /// Errors are specific to the flavor, so flavor is part of the file name:
+    /// `path/set-1e3-single/expected-errors-tackler.txt`
+    pub fn expected_errors_path(&self) -> (PathBuf, PathBuf) {
+        let set_dir = self.txn_set_path();
+        let errors_path = set_dir.join(format!("expected-errors-{}.txt", self.flavor));
+        (set_dir, errors_path)
+    }
+
+    /// Path of reference report of the set
+    ///
+    /// `path/set-1e3-single/reference/tackler-balance.txt`
+    pub fn reference_path(&self, name: &str) -> (PathBuf, PathBuf) {
+        let ref_dir = self.txn_set_path().join("reference");
+        let ref_path = ref_dir.join(name);
+        (ref_dir, ref_path)
+    }
+
+    /// Set exists for the flavor
+    ///
+    /// Flavors could share the same single shard set, so it exists
+    /// if the journal of the flavor exists.
+    /// The first existing path of the set, `None` if the set doesn't exist
+    ///
+    /// Sharded set exists, if its directory (or archive) exists. Single shard set
+    /// could be shared by flavors, so it exists, if any file of the flavor exists
+    /// (journal, compressed journal, charts, manifest or config).
+    pub fn existing_path(&self) -> Option<PathBuf> {
+        if let Some(archive_path) = self.archive_path() {
+            return archive_path.exists().then_some(archive_path);
+        }
+        if self.shard_type != ShardType::Single {
+            let set_path = self.txn_set_path();
+            return set_path.exists().then_some(set_path);
+        }
+        let ts = Timestamp::UNIX_EPOCH.to_zoned(TimeZone::UTC);
+        let journal_path = self.journal_path(&ts, 0).1;
+        let mut paths = vec![
+            gz_path(&journal_path),
+            journal_path,
+            self.chart_of_accounts_path().1,
+            self.chart_of_commodities_path().1,
+            self.manifest_path().1,
+        ];
+        if self.flavor == JournalFlavor::Tackler {
+            paths.push(self.config_path().1);
+        }
+        paths.into_iter().find(|path| path.exists())
+    }
+
+    /// Path of archive of the set, if the set is written as tar archive
+    ///
+    /// Flavors could share the same single shard set, so flavor is part of the file name:
+    /// `path/set-1e3-month-tackler.tar.gz`
+    pub fn archive_path(&self) -> Option<PathBuf> {
+        match self.archive {
+            Some(Archive::Gz) | None => None,
+            Some(ref archive) => Some(self.path.join(format!(
+                "{}-{}.{archive}",
+                self.txn_set_dir(),
+                self.flavor
+            ))),
+        }
+    }
+
+    /// Staging directory of the set, files are written here and moved into place
+    ///
+    /// Staging is under the data path, and it mirrors the data path:
+    /// `path/.set-1e3-month-tackler.tmp/set-1e3-month/txns/...`
+    pub fn staging_path(&self) -> PathBuf {
+        self.path
+            .join(format!(".{}-{}.tmp", self.txn_set_dir(), self.flavor))
+    }
+
+    /// Directory of the replaced set, while the set is replaced by commit
+    ///
+    /// Existing set is moved here before the new set is moved into place,
+    /// and it's removed after that: `path/.set-1e3-month-tackler.old`
+    pub fn replaced_path(&self) -> PathBuf {
+        self.path
+            .join(format!(".{}-{}.old", self.txn_set_dir(), self.flavor))
+    }
+
+    /// Path of manifest of the set
+    ///
+    /// Flavors could share the same single shard set, so flavor is part of the file name:
+    /// `path/set-1e3-single/manifest-tackler.toml`
+    pub fn manifest_path(&self) -> (PathBuf, PathBuf) {
+        let set_dir = self.txn_set_path();
+        let manifest_path = set_dir.join(format!("manifest-{}.toml", self.flavor));
+        (set_dir, manifest_path)
+    }
+
     pub fn config_path(&self) -> (PathBuf, PathBuf) {
         let set_dir = self.txn_set_path();
         let toml_dir = set_dir.parent().expect("IE: missing parent for set_dir");
@@ -255,23 +795,49 @@ impl JournalSetup {
     /// `ShardType::Single`
     /// - tackler:   path/set-1e3-single/txns/1e3.txn
     /// - hledger:   path/set-1e3-single/txns/1e3.journal
+    ///   - timeclock: path/set-1e3-single/txns/1e3.timeclock
+    ///   - timedot:   path/set-1e3-single/txns/1e3.timedot
     /// - beancount: path/set-1e3-single/txns/1e3.beancount
     ///
+    /// `ShardType::Year`
+    /// - tackler: path/set-1e3-year/txns/YYYY/YYYY.txn
+    ///
     /// `ShardType::Month`
     /// - tackler: path/set-1e3-month/txns/YYYY/MM/YYYY-MM.txn
-    /// - ledger: Not supported
-    /// - beancount: Not supported
+    ///
+    /// `ShardType::Week` (ISO week and its year)
+    /// - tackler: path/set-1e3-week/txns/YYYY/Www/YYYY-Www.txn
+    ///
+    /// `ShardType::Day`
+    /// - tackler: path/set-1e3-day/txns/YYYY/MM/DD/YYYY-MM-DD.txn
+    ///
+    /// Sharded sets are not supported by ledger and beancount.
     ///
     /// `ShardType::Txn`
     /// - tackler:  path/set-1e3-txn/YYYY/MM/DD/YYYYMMDDTHHMMSS-IDX.txn
-    /// - ledger: Not supported
-    /// - beancount: Not supported
-    pub fn journal_path(&mut self, ts: &Zoned, idx: u32) -> (PathBuf, PathBuf) {
+    ///
+    /// `ShardType::Template`
+    /// - tackler: path/set-1e3-template/TEMPLATE, template is rendered without txn,
+    ///   see `JournalSetup::txn_path`
+    ///
+    /// `ShardType::Count` (N is 1-based number of journal)
+    /// - tackler: path/set-1e3-count/txns/NNNNNN.txn
+    ///
+    /// `ShardType::Size` (the first journal, next journals are decided by `JournalWriter`)
+    /// - tackler: path/set-1e3-size/txns/NNNNNN.txn
+    ///
+    /// `ShardType::Hash` (B is bucket, uuid is not known without txn)
+    /// - tackler: path/set-1e3-hash/txns/B/UUID.txn, see `JournalSetup::txn_path`
+    pub fn journal_path(&self, ts: &Zoned, idx: u32) -> (PathBuf, PathBuf) {
         match self.shard_type {
             ShardType::Single => {
                 let journal = match self.flavor {
                     JournalFlavor::Tackler => format!("{}.txn", self.txn_set.str()),
-                    JournalFlavor::Ledger => format!("{}.journal", self.txn_set.str()),
+                    JournalFlavor::Ledger => match self.time_format {
+                        Some(TimeFormat::Timeclock) => format!("{}.timeclock", self.txn_set.str()),
+                        Some(TimeFormat::Timedot) => format!("{}.timedot", self.txn_set.str()),
+                        None => format!("{}.journal", self.txn_set.str()),
+                    },
                     JournalFlavor::Beancount => format!("{}.beancount", self.txn_set.str()),
                 };
 
@@ -280,6 +846,17 @@ impl JournalSetup {
 
                 (txn_dir, txn_path)
             }
+            ShardType::Year => {
+                let y = ts.year();
+
+                let dir = format!("txns/{}", y);
+                let journal = format!("{}.txn", y);
+
+                let txn_dir = self.txn_set_path().join(dir.as_str());
+                let txn_path = txn_dir.join(journal.as_str());
+
+                (txn_dir, txn_path)
+            }
             ShardType::Month => {
                 let y = ts.year();
                 let m = ts.month();
@@ -292,6 +869,39 @@ impl JournalSetup {
 
                 (txn_dir, txn_path)
             }
+            ShardType::Week => {
+                // the first days of year could belong to the last week of previous year
+                let iso = ts.date().iso_week_date();
+                let y = iso.year();
+                let w = iso.week();
+
+                let dir = format!("txns/{}/W{:0>2}", y, w);
+                let journal = format!("{}-W{:0>2}.txn", y, w);
+
+                let txn_dir = self.txn_set_path().join(dir.as_str());
+                let txn_path = txn_dir.join(journal.as_str());
+
+                (txn_dir, txn_path)
+            }
+            ShardType::Day => {
+                let y = ts.year();
+                let m = ts.month();
+                let d = ts.day();
+
+                let dir = format!("txns/{}/{:0>2}/{:0>2}", y, m, d);
+                let journal = format!("{}-{:0>2}-{:0>2}.txn", y, m, d);
+
+                let txn_dir = self.txn_set_path().join(dir.as_str());
+                let txn_path = txn_dir.join(journal.as_str());
+
+                (txn_dir, txn_path)
+            }
+            ShardType::Template | ShardType::Hash => self.rendered_path(ts, idx, ""),
+            ShardType::Count => {
+                let txns = self.shards.txns.unwrap_or(1);
+                self.numbered_path(idx.saturating_sub(1) / txns + 1)
+            }
+            ShardType::Size => self.numbered_path(1),
             ShardType::Txn => {
                 let y = ts.year();
                 let m = ts.month();
@@ -310,9 +920,88 @@ impl JournalSetup {
     }
 }
 
+impl JournalSetup {
+    /// Path of journal of the txn (dir and full path)
+    ///
+    /// With `ShardType::Template` and `ShardType::Hash`, path is based on the txn,
+    /// otherwise this is the same as `JournalSetup::journal_path`.
+    pub fn txn_path(&self, ts: &Zoned, idx: u32, txn: &str) -> (PathBuf, PathBuf) {
+        match self.shard_type {
+            ShardType::Template | ShardType::Hash => self.rendered_path(ts, idx, txn),
+            _ => self.journal_path(ts, idx),
+        }
+    }
+
+    fn rendered_path(&self, ts: &Zoned, idx: u32, txn: &str) -> (PathBuf, PathBuf) {
+        let rel_path = match &self.shards.template {
+            Some(template) => template.render(&self.flavor, &self.txn_set, ts, idx, txn),
+            None => {
+                let buckets = self.shards.buckets.unwrap_or(1);
+                let uuid = txn_uuid_of(txn, &self.txn_set, idx, ts.timestamp());
+                let width = (buckets - 1).to_string().len();
+                let bucket = uuid_bucket(&uuid, buckets);
+                // uuid of txn is a single path segment
+                let file = uuid.replace(['/', '\\', '.'], "_");
+                format!("txns/{bucket:0>width$}/{file}.txn")
+            }
+        };
+        let txn_path = self.txn_set_path().join(rel_path);
+        let txn_dir = txn_path
+            .parent()
+            .expect("IE: missing parent for txn_path")
+            .to_path_buf();
+        (txn_dir, txn_path)
+    }
+
+    /// Path of numbered journal of `ShardType::Count` and `ShardType::Size`
+    pub fn numbered_path(&self, number: u32) -> (PathBuf, PathBuf) {
+        let txn_dir = self.txn_set_path().join("txns");
+        let txn_path = txn_dir.join(format!("{number:0>6}.txn"));
+        (txn_dir, txn_path)
+    }
+}
+
+/// Path of gzip-compressed file: `1e3.journal` -> `1e3.journal.gz`
+pub fn gz_path(path: &Path) -> PathBuf {
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    PathBuf::from(gz)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::setup::SetSize;
+    use crate::setup::{
+        IndentStyle, JournalFlavor, JournalFormat, JournalSetup, LineEnding, SetSize, ShardOptions,
+        ShardType, TimeFormat,
+    };
+    use crate::txn_uuid::uuid_bucket;
+    use jiff::Zoned;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn journal_format() {
+        let txn = "2024-01-01 txn\n  e:a  1\n  a:b\n\n";
+        assert_eq!(JournalFormat::default().apply(txn), txn);
+
+        let fmt = JournalFormat {
+            line_ending: LineEnding::Crlf,
+            indent_style: IndentStyle::Tabs,
+            indent_width: 1,
+            bom: false,
+        };
+        assert_eq!(
+            fmt.apply(txn),
+            "2024-01-01 txn\r\n\te:a  1\r\n\ta:b\r\n\r\n"
+        );
+
+        let fmt = JournalFormat {
+            line_ending: LineEnding::Lf,
+            indent_style: IndentStyle::Mixed,
+            indent_width: 4,
+            bom: false,
+        };
+        assert_eq!(fmt.apply(txn), "2024-01-01 txn\n    e:a  1\n\ta:b\n\n");
+    }
 
     #[test]
     fn set_size_str() {
@@ -333,4 +1022,120 @@ mod tests {
         assert_eq!(SetSize::Sz1e5.size(), 100_000);
         assert_eq!(SetSize::Sz1e6.size(), 1_000_000);
     }
+
+    #[test]
+    fn journal_path_time_format() {
+        let ts: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let mut setup = JournalSetup::try_new(
+            JournalFlavor::Ledger,
+            Path::new("data"),
+            SetSize::Sz1e3,
+            ShardType::Single,
+        )
+        .unwrap(/*:test:*/);
+        assert!(setup.journal_path(&ts, 1).1.ends_with("txns/1e3.journal"));
+
+        setup.time_format = Some(TimeFormat::Timeclock);
+        assert!(setup.journal_path(&ts, 1).1.ends_with("txns/1e3.timeclock"));
+
+        setup.time_format = Some(TimeFormat::Timedot);
+        assert!(setup.journal_path(&ts, 1).1.ends_with("txns/1e3.timedot"));
+    }
+
+    #[test]
+    fn journal_path_shards() {
+        let path = |shard_type: ShardType, ts: &str| {
+            let ts: Zoned = ts.parse().unwrap(/*:test:*/);
+            let setup = JournalSetup::try_new(
+                JournalFlavor::Tackler,
+                Path::new("data"),
+                SetSize::Sz1e3,
+                shard_type,
+            )
+            .unwrap(/*:test:*/);
+            setup.journal_path(&ts, 1).1
+        };
+        let ts = "2024-12-30T12:00:00+00:00[UTC]";
+        assert_eq!(
+            path(ShardType::Year, ts),
+            Path::new("data/set-1e3-year/txns/2024/2024.txn")
+        );
+        assert_eq!(
+            path(ShardType::Month, ts),
+            Path::new("data/set-1e3-month/txns/2024/12/2024-12.txn")
+        );
+        // 2024-12-30 is on the first ISO week of 2025
+        assert_eq!(
+            path(ShardType::Week, ts),
+            Path::new("data/set-1e3-week/txns/2025/W01/2025-W01.txn")
+        );
+        assert_eq!(
+            path(ShardType::Week, "2024-12-29T12:00:00+00:00[UTC]"),
+            Path::new("data/set-1e3-week/txns/2024/W52/2024-W52.txn")
+        );
+        assert_eq!(
+            path(ShardType::Day, ts),
+            Path::new("data/set-1e3-day/txns/2024/12/30/2024-12-30.txn")
+        );
+        for st in ["single", "year", "month", "week", "day", "txn"] {
+            assert_eq!(ShardType::try_from(st).unwrap(/*:test:*/).to_string(), st);
+        }
+        assert!(ShardType::try_from("quarter").is_err());
+    }
+
+    #[test]
+    fn journal_path_count_and_hash() {
+        let ts: Zoned = "2024-12-30T12:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let setup = |shard_type: ShardType, shards: ShardOptions| {
+            let mut setup = JournalSetup::try_new(
+                JournalFlavor::Tackler,
+                Path::new("data"),
+                SetSize::Sz1e3,
+                shard_type,
+            )
+            .unwrap(/*:test:*/);
+            setup.set_shard_options(shards).map(|_| setup)
+        };
+        let count = setup(
+            ShardType::Count,
+            ShardOptions {
+                txns: Some(100),
+                ..ShardOptions::default()
+            },
+        )
+        .unwrap(/*:test:*/);
+        assert_eq!(
+            count.journal_path(&ts, 100).1,
+            Path::new("data/set-1e3-count/txns/000001.txn")
+        );
+        assert_eq!(
+            count.journal_path(&ts, 101).1,
+            Path::new("data/set-1e3-count/txns/000002.txn")
+        );
+
+        let hash = setup(
+            ShardType::Hash,
+            ShardOptions {
+                buckets: Some(16),
+                ..ShardOptions::default()
+            },
+        )
+        .unwrap(/*:test:*/);
+        let txn = "2024-12-30T12:00:00Z 'txn\n  # uuid: 0e6f1c7a-uuid\n  e:a  1\n  a:b\n";
+        let bucket = uuid_bucket("0e6f1c7a-uuid", 16);
+        assert_eq!(
+            hash.txn_path(&ts, 1, txn).1,
+            PathBuf::from(format!(
+                "data/set-1e3-hash/txns/{bucket:0>2}/0e6f1c7a-uuid.txn"
+            ))
+        );
+
+        assert!(setup(ShardType::Size, ShardOptions::default()).is_err());
+        let both = ShardOptions {
+            txns: Some(1),
+            bytes: Some(1),
+            ..ShardOptions::default()
+        };
+        assert!(setup(ShardType::Size, both).is_err());
+    }
 }
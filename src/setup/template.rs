@@ -0,0 +1,267 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! User-defined shard paths
+//!
+//! Template is a path relative to the txn set, e.g.
+//! `txns/{year}/Q{quarter}/{account_top}/{uuid}.txn`. Txns with the same
+//! path are written into the same journal.
+
+use crate::setup::{JournalFlavor, SetSize};
+use crate::txn_uuid::txn_uuid_of;
+use crate::verifier;
+use jiff::Zoned;
+use std::error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Year,
+    Quarter,
+    Month,
+    WeekYear,
+    Week,
+    Day,
+    Index,
+    Uuid,
+    Account,
+    AccountTop,
+    Commodity,
+}
+
+impl Part {
+    const PLACEHOLDERS: [(&'static str, Part); 11] = [
+        ("year", Part::Year),
+        ("quarter", Part::Quarter),
+        ("month", Part::Month),
+        ("week_year", Part::WeekYear),
+        ("week", Part::Week),
+        ("day", Part::Day),
+        ("index", Part::Index),
+        ("uuid", Part::Uuid),
+        ("account", Part::Account),
+        ("account_top", Part::AccountTop),
+        ("commodity", Part::Commodity),
+    ];
+
+    fn name(&self) -> &'static str {
+        Self::PLACEHOLDERS
+            .iter()
+            .find(|(_, p)| p == self)
+            .map_or("", |(name, _)| name)
+    }
+}
+
+/// Date parts, which must be used together with a coarser part,
+/// so that journals of different periods are not merged
+/// (e.g. January of different years).
+const REQUIRED: [(Part, Part); 4] = [
+    (Part::Quarter, Part::Year),
+    (Part::Month, Part::Year),
+    (Part::Day, Part::Month),
+    (Part::Week, Part::WeekYear),
+];
+
+/// Used value, if the txn doesn't have account or commodity
+const NONE: &str = "none";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardTemplate {
+    template: String,
+    parts: Vec<Part>,
+}
+
+impl TryFrom<&str> for ShardTemplate {
+    type Error = Box<dyn Error>;
+
+    /// Parse and validate template
+    ///
+    /// Template must be under `txns`, and it must end with `.txn`.
+    /// Date parts must be unique over the whole set, e.g. `{month}` requires `{year}`.
+    fn try_from(template: &str) -> Result<Self, Self::Error> {
+        let invalid = |msg: &str| format!("Invalid shard template ({msg}): '{template}'");
+        if !template.starts_with("txns/") || !template.ends_with(".txn") {
+            return Err(invalid("it must start with 'txns/' and end with '.txn'").into());
+        }
+        if template
+            .split('/')
+            .any(|s| s.is_empty() || s == "." || s == ".." || s.contains('\\'))
+        {
+            return Err(invalid("empty, '.' or '..' path segment").into());
+        }
+
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find(['{', '}']) {
+            let (text, tail) = rest.split_at(start);
+            let Some((name, tail)) = tail.strip_prefix('{').and_then(|t| t.split_once('}')) else {
+                return Err(invalid("unmatched brace").into());
+            };
+            let Some((_, part)) = Part::PLACEHOLDERS.iter().find(|(n, _)| *n == name) else {
+                return Err(invalid(&format!("unknown placeholder '{{{name}}}'")).into());
+            };
+            if !text.is_empty() {
+                parts.push(Part::Text(text.to_string()));
+            }
+            parts.push(part.clone());
+            rest = tail;
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+
+        for (part, required) in REQUIRED {
+            if parts.contains(&part) && !parts.contains(&required) {
+                let msg = format!(
+                    "placeholder '{{{}}}' requires '{{{}}}' to keep shards unique",
+                    part.name(),
+                    required.name()
+                );
+                return Err(invalid(&msg).into());
+            }
+        }
+        Ok(ShardTemplate {
+            template: template.to_string(),
+            parts,
+        })
+    }
+}
+
+impl ShardTemplate {
+    /// Relative path of the journal of the txn
+    ///
+    /// Uuid is the uuid of the txn (`# uuid: ...`), or if the txn doesn't have it,
+    /// it's a stable uuid based on set, index and timestamp. Account and commodity are
+    /// based on the first posting of the txn, account's sub-accounts are directories.
+    pub fn render(
+        &self,
+        flavor: &JournalFlavor,
+        set: &SetSize,
+        ts: &Zoned,
+        idx: u32,
+        txn: &str,
+    ) -> String {
+        let posting = || {
+            txn.lines()
+                .skip(1)
+                .map(str::trim)
+                .find(|l| !l.is_empty() && !l.starts_with(['#', ';']))
+                .and_then(|l| verifier::posting(flavor, l).ok())
+        };
+        let mut path = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => path.push_str(text),
+                Part::Year => path.push_str(&format!("{:0>4}", ts.year())),
+                Part::Quarter => path.push_str(&format!("{}", (ts.month() - 1) / 3 + 1)),
+                Part::Month => path.push_str(&format!("{:0>2}", ts.month())),
+                Part::WeekYear => {
+                    path.push_str(&format!("{:0>4}", ts.date().iso_week_date().year()))
+                }
+                Part::Week => path.push_str(&format!("{:0>2}", ts.date().iso_week_date().week())),
+                Part::Day => path.push_str(&format!("{:0>2}", ts.day())),
+                Part::Index => path.push_str(&idx.to_string()),
+                Part::Uuid => {
+                    let uuid = txn_uuid_of(txn, set, idx, ts.timestamp());
+                    path.push_str(&segment(&uuid));
+                }
+                Part::Account => {
+                    let account = posting().map_or(NONE.to_string(), |p| p.account);
+                    let dirs: Vec<_> = account.split(':').map(segment).collect();
+                    path.push_str(&dirs.join("/"));
+                }
+                Part::AccountTop => {
+                    let account = posting().map_or(NONE.to_string(), |p| p.account);
+                    path.push_str(&segment(account.split(':').next().unwrap_or(NONE)));
+                }
+                Part::Commodity => {
+                    let commodity = posting()
+                        .and_then(|p| p.commodities.into_iter().next())
+                        .unwrap_or(NONE.to_string());
+                    path.push_str(&segment(commodity.trim_matches('"')));
+                }
+            }
+        }
+        path
+    }
+}
+
+impl std::fmt::Display for ShardTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
+
+/// Value as a single path segment
+fn segment(value: &str) -> String {
+    match value {
+        "" => NONE.to_string(),
+        "." | ".." => value.replace('.', "_"),
+        _ => value.replace(['/', '\\'], "_"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::setup::template::ShardTemplate;
+    use crate::setup::{JournalFlavor, SetSize};
+    use jiff::Zoned;
+
+    #[test]
+    fn test_template() {
+        let ts: Zoned = "2024-12-30T12:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let txn = "2024-12-30T12:00:00Z 'txn\n  # uuid: 0e6f1c7a-uuid\n  e:ey2024:em12  1.5 EUR\n  a:ay2024\n";
+        let render = |template: &str, txn: &str| {
+            ShardTemplate::try_from(template)
+                .unwrap(/*:test:*/)
+                .render(&JournalFlavor::Tackler, &SetSize::Sz1e3, &ts, 7, txn)
+        };
+        assert_eq!(
+            render("txns/{year}/Q{quarter}/{account_top}/{uuid}.txn", txn),
+            "txns/2024/Q4/e/0e6f1c7a-uuid.txn"
+        );
+        assert_eq!(
+            render(
+                "txns/{week_year}-W{week}/{month}{day}-{year}-{index}.txn",
+                txn
+            ),
+            "txns/2025-W01/1230-2024-7.txn"
+        );
+        assert_eq!(
+            render("txns/{account}/{commodity}.txn", txn),
+            "txns/e/ey2024/em12/EUR.txn"
+        );
+
+        let txn = "2024-12-30T12:00:00Z 'txn\n  e:b  1\n  a:b\n";
+        assert_eq!(render("txns/{commodity}.txn", txn), "txns/none.txn");
+        assert_eq!(
+            render("txns/{uuid}.txn", txn),
+            render("txns/{uuid}.txn", &txn.replace("'txn", "'other"))
+        );
+    }
+
+    #[test]
+    fn test_template_validation() {
+        for template in [
+            "{year}.txn",
+            "txns/{year}.journal",
+            "txns/../{year}.txn",
+            "txns//{year}.txn",
+            "txns/{year.txn",
+            "txns/year}.txn",
+            "txns/{hour}.txn",
+            "txns/{month}.txn",
+            "txns/{year}/{day}.txn",
+            "txns/{year}/{week}.txn",
+        ] {
+            assert!(ShardTemplate::try_from(template).is_err(), "{template}");
+        }
+        let template = "txns/{year}/{month}/{day}.txn";
+        assert_eq!(
+            ShardTemplate::try_from(template).unwrap(/*:test:*/).to_string(),
+            template
+        );
+    }
+}
@@ -0,0 +1,149 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::amount::Amount;
+use crate::prng::Prng;
+use jiff::civil::Time;
+use jiff::{Span, Zoned};
+use std::error::Error;
+
+/// PRNG stream for time tracking sessions
+const STREAM: u64 = 7;
+
+/// Start of working day
+const DAY_START: Time = Time::constant(8, 0, 0, 0);
+/// Length of working day, in seconds (08:00 - 20:00)
+const DAY_SECS: i64 = 12 * 3600;
+/// Durations are multiples of this, so they are exact in hours (0.05 h)
+const DURATION_STEP_SECS: i64 = 180;
+/// Counter account of sessions, if the format needs one
+pub const TIME_ACCOUNT: &str = "Time:Logged";
+/// Maximum count of sessions per day (5 minute slots)
+pub const MAX_PER_DAY: u32 = 144;
+/// Scale of hours
+pub const HOURS_SCALE: u32 = 2;
+
+/// Time tracking session of a project
+pub struct Session {
+    pub index: u32,
+    pub project: String,
+    pub start: Zoned,
+    pub end: Zoned,
+    pub hours: Amount,
+    /// This is the first session of its day
+    pub first_of_day: bool,
+}
+
+/// Time tracking sessions between `start` and `stop`
+///
+/// There are `per_day` sessions on each day, and the days are spread evenly
+/// over the time range. By default, sessions per day is the smallest count
+/// which fits all sessions into the time range. The working day (08:00 - 20:00 in the timezone of `start`)
+/// is divided into slots, and each session is inside of its own slot,
+/// so sessions don't overlap. Sessions start at random sub-second times.
+pub fn sessions(
+    start: &Zoned,
+    stop: &Zoned,
+    count: u32,
+    per_day: Option<u32>,
+    projects: u32,
+    seed: u64,
+) -> Result<Vec<Session>, Box<dyn Error>> {
+    let span_days = i64::from((stop.date() - start.date()).get_days());
+    let per_day = match per_day {
+        Some(per_day) => per_day,
+        None => {
+            let span_days = span_days.max(1);
+            let per_day = (i64::from(count) + span_days - 1) / span_days;
+            u32::try_from(per_day)
+                .unwrap_or(MAX_PER_DAY)
+                .clamp(1, MAX_PER_DAY)
+        }
+    };
+    let days = i64::from(count.div_ceil(per_day));
+    if days > span_days {
+        let msg = format!(
+            "Too many sessions for the time range: {count} sessions with {per_day} sessions per day \
+             needs {days} days, but there are only {span_days} days"
+        );
+        return Err(msg.into());
+    }
+    let slot_secs = DAY_SECS / i64::from(per_day);
+    let steps = (slot_secs * 2 / 3 / DURATION_STEP_SECS).max(1);
+
+    let mut sessions = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        let day = i64::from(i / per_day);
+        let slot = i64::from(i % per_day);
+        let date = start
+            .date()
+            .checked_add(Span::new().days(day * span_days / days))?;
+        let day_start = date
+            .to_datetime(DAY_START)
+            .to_zoned(start.time_zone().clone())?;
+
+        let index = i + 1;
+        let mut rng = Prng::new(seed, STREAM, u64::from(index));
+        let offset_secs = slot * slot_secs + rng.next_below((slot_secs / 3) as u128) as i64;
+        let nanos = rng.next_below(1_000_000_000) as i64;
+        let duration_secs = DURATION_STEP_SECS * (1 + rng.next_below(steps as u128) as i64);
+        let project = 1 + rng.next_below(u128::from(projects));
+
+        let ts = day_start.checked_add(Span::new().seconds(offset_secs).nanoseconds(nanos))?;
+        let end = ts.checked_add(Span::new().seconds(duration_secs))?;
+        sessions.push(Session {
+            index,
+            project: format!("Projects:Project-{project:0>2}"),
+            start: ts,
+            end,
+            hours: Amount::new(
+                i128::from(duration_secs) * 10i128.pow(HOURS_SCALE) / 3600,
+                HOURS_SCALE,
+            ),
+            first_of_day: slot == 0,
+        });
+    }
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timetrack::sessions;
+    use jiff::Zoned;
+
+    #[test]
+    fn test_sessions() {
+        let start: Zoned = "2024-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let stop: Zoned = "2025-01-01T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let s = sessions(&start, &stop, 100, Some(4), 3, 0).unwrap(/*:test:*/);
+
+        assert_eq!(s.len(), 100);
+        assert_eq!(s[0].index, 1);
+        assert!(s[0].first_of_day);
+        assert!(!s[1].first_of_day);
+        assert_eq!(s[0].start.date(), s[3].start.date());
+        assert_ne!(s[3].start.date(), s[4].start.date());
+        // Sessions don't overlap
+        assert!(s.windows(2).all(|w| w[0].end <= w[1].start));
+        for session in &s {
+            let secs = (&session.end - &session.start)
+                .total(jiff::Unit::Second)
+                .unwrap(/*:test:*/);
+            assert_eq!(session.hours.units as f64, secs / 36.0);
+            assert!(session.project.starts_with("Projects:Project-0"));
+        }
+
+        // Sessions per day is based on the time range
+        let s = sessions(&start, &stop, 1000, None, 3, 0).unwrap(/*:test:*/);
+        assert_eq!(s.len(), 1000);
+        assert!(s.windows(2).all(|w| w[0].end <= w[1].start));
+        assert_eq!(s[2].start.date(), s[0].start.date());
+        assert_ne!(s[3].start.date(), s[0].start.date());
+
+        // Too many sessions for one day
+        let stop: Zoned = "2024-01-02T00:00:00+00:00[UTC]".parse().unwrap(/*:test:*/);
+        assert!(sessions(&start, &stop, 10, Some(4), 3, 0).is_err());
+        assert!(sessions(&start, &stop, 1000, None, 3, 0).is_err());
+    }
+}
@@ -27,10 +27,32 @@ pub fn get_txn_uuid(set: &SetSize, number: u32, ts: Timestamp, spec: Option<&str
     Uuid::new_v5(&Uuid::NAMESPACE_URL, data.as_bytes()).to_string()
 }
 
+/// Uuid of txn (`# uuid: ...`), if the txn has it
+pub fn uuid_of(txn: &str) -> Option<String> {
+    txn.lines()
+        .find_map(|l| l.trim().strip_prefix("# uuid:"))
+        .map(|u| u.trim().to_string())
+}
+
+/// Uuid of txn (`# uuid: ...`)
+///
+/// If the txn doesn't have uuid, this is a stable uuid based on set, index and timestamp.
+pub fn txn_uuid_of(txn: &str, set: &SetSize, number: u32, ts: Timestamp) -> String {
+    uuid_of(txn).unwrap_or_else(|| get_txn_uuid(set, number, ts, None))
+}
+
+/// Bucket of uuid, based on stable hash (64-bit FNV-1a) of uuid
+pub fn uuid_bucket(uuid: &str, buckets: u32) -> u32 {
+    let hash = uuid.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    (hash % u64::from(buckets)) as u32
+}
+
 #[cfg(test)]
 mod tests {
     use crate::setup::SetSize;
-    use crate::txn_uuid::get_txn_uuid;
+    use crate::txn_uuid::{get_txn_uuid, txn_uuid_of, uuid_bucket};
     use uuid::Uuid;
 
     #[test]
@@ -89,4 +111,29 @@ mod tests {
             assert_eq!(test.0, &uuid);
         }
     }
+
+    #[test]
+    fn test_uuid_bucket() {
+        let ts = "2024-01-02T00:01:02Z".parse().unwrap(/*:test:*/);
+        let txn = "2024-01-02 'txn\n  # uuid: 94942e79-0153-53d1-9b12-a13f788cbaf1\n  e:b  1\n";
+        let uuid = txn_uuid_of(txn, &SetSize::Sz1e3, 1, ts);
+        assert_eq!(uuid, "94942e79-0153-53d1-9b12-a13f788cbaf1");
+        assert_eq!(
+            txn_uuid_of("2024-01-02 'txn\n  e:b  1\n", &SetSize::Sz1e3, 1, ts),
+            uuid
+        );
+
+        // FNV-1a of empty input is the offset basis
+        assert_eq!(
+            uuid_bucket("", 1_000_000_007),
+            (0xcbf2_9ce4_8422_2325_u64 % 1_000_000_007) as u32
+        );
+        assert_eq!(uuid_bucket(&uuid, 16), uuid_bucket(&uuid, 16));
+        let mut counts = [0; 4];
+        for n in 1..=1000 {
+            let uuid = get_txn_uuid(&SetSize::Sz1e3, n, ts, None);
+            counts[uuid_bucket(&uuid, 4) as usize] += 1;
+        }
+        assert!(counts.iter().all(|c| *c > 200), "{counts:?}");
+    }
 }
@@ -0,0 +1,796 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::account;
+use crate::amount::Amount;
+use crate::setup::{ErrorKind, JournalFlavor};
+use crate::writers::BOM;
+use jiff::civil::{Date, DateTime};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+pub mod beancount;
+pub mod ledger;
+pub mod tackler;
+
+/// Line is not valid for the flavor
+pub const SYNTAX_ERROR: &str = "syntax-error";
+/// Account name doesn't follow the naming rules of the flavor
+pub const INVALID_ACCOUNT: &str = "invalid-account";
+/// Included file (or input of tackler config) doesn't exist
+pub const UNRESOLVED_INCLUDE: &str = "unresolved-include";
+/// Account is used after its close date
+pub const POST_CLOSE: &str = "post-close";
+
+/// Maximum count of digits of amount (integer and decimal digits)
+const MAX_DIGITS: usize = 38;
+
+/// Kind and detail of a problem on a single line
+pub type Issue = (&'static str, String);
+
+/// Use of account by posting: path, line, account, commodities and date of txn
+type Use = (PathBuf, usize, String, Vec<String>, Option<Date>);
+
+/// Problem found in journal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub path: PathBuf,
+    pub line: usize,
+    pub kind: &'static str,
+    pub detail: String,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.kind,
+            self.detail
+        )
+    }
+}
+
+/// Top-level (non-indented) entry of journal
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    /// Txn header, postings follow
+    ///
+    /// Date of txn is known only for flavors which close accounts (beancount).
+    Txn(Option<Date>),
+    /// Other directive with accounts it refers to
+    Directive(Vec<String>),
+    /// Declaration of account
+    Account(String),
+    /// Close of account at date
+    Close(Date, String),
+    /// Declaration of commodity
+    Commodity(String),
+    /// Include of other journal file
+    Include(String),
+    /// Timeclock check-in with account
+    ClockIn(DateTime, String),
+    /// Timeclock check-out
+    ClockOut(DateTime),
+    /// Timedot entry of account
+    TimeLog(String),
+    Comment,
+}
+
+/// Posting of txn
+#[derive(Debug, Clone, PartialEq)]
+pub struct Posting {
+    pub account: String,
+    /// Commodities of amount and price
+    pub commodities: Vec<String>,
+    /// Amount of posting, `None` if it's elided
+    pub amount: Option<Amount>,
+    /// Weight of posting for balancing, `None` if amount is elided
+    pub weight: Option<(Amount, String)>,
+    /// Weight is based on price
+    pub priced: bool,
+}
+
+/// File extensions of journals of the flavor
+pub fn journal_extensions(flavor: &JournalFlavor) -> &'static [&'static str] {
+    match flavor {
+        JournalFlavor::Tackler => &["txn"],
+        JournalFlavor::Ledger => &["journal", "timeclock", "timedot"],
+        JournalFlavor::Beancount => &["beancount"],
+    }
+}
+
+/// Journal files of the flavor under `dir`, sorted by path
+pub fn journal_files(dir: &Path, flavor: &JournalFlavor) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    fn walk(dir: &Path, exts: &[&str], files: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, exts, files)?;
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| exts.contains(&e))
+            {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+    let mut files = Vec::new();
+    if dir.is_dir() {
+        walk(dir, journal_extensions(flavor), &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Lightweight checker of journals
+///
+/// Checks dates, indentation, account names, balancing of txns and
+/// that includes resolve. If the journal declares accounts or commodities,
+/// then postings must use only declared ones (beancount accounts must
+/// always be opened). Closed accounts must not be used after their close date.
+/// Tackler txn UUIDs must be unique.
+pub struct Verifier {
+    pub flavor: JournalFlavor,
+    /// Findings are reported relative to this path
+    root: PathBuf,
+    pub findings: Vec<Finding>,
+    pub files: usize,
+    pub txns: usize,
+    visited: BTreeSet<PathBuf>,
+    accounts: Option<BTreeSet<String>>,
+    commodities: Option<BTreeSet<String>>,
+    uses: Vec<Use>,
+    /// Closed accounts with their close date
+    closed: BTreeMap<String, Date>,
+    uuids: BTreeSet<String>,
+}
+
+/// Txn or directive, which is being parsed
+enum Block {
+    None,
+    Txn(usize, Option<Date>, Vec<Posting>),
+    Directive,
+}
+
+impl Verifier {
+    pub fn new(flavor: JournalFlavor, root: &Path) -> Verifier {
+        Verifier {
+            flavor,
+            root: root.to_path_buf(),
+            findings: Vec::new(),
+            files: 0,
+            txns: 0,
+            visited: BTreeSet::new(),
+            accounts: None,
+            commodities: None,
+            uses: Vec::new(),
+            closed: BTreeMap::new(),
+            uuids: BTreeSet::new(),
+        }
+    }
+
+    pub fn declare_account(&mut self, account: &str) {
+        self.accounts
+            .get_or_insert_default()
+            .insert(account.to_string());
+    }
+
+    pub fn declare_commodity(&mut self, commodity: &str) {
+        self.commodities
+            .get_or_insert_default()
+            .insert(commodity.to_string());
+    }
+
+    pub fn report(&mut self, path: &Path, line: usize, issue: Issue) {
+        let path = path.strip_prefix(&self.root).unwrap_or(path).to_path_buf();
+        self.findings.push(Finding {
+            path,
+            line,
+            kind: issue.0,
+            detail: issue.1,
+        });
+    }
+
+    /// Verify journal file and files included by it
+    pub fn verify_file(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if !self.visited.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+        let bytes = std::fs::read(path).map_err(|err| format!("{err}: '{}'", path.display()))?;
+        match String::from_utf8(bytes) {
+            Ok(content) => self.verify_text(path, &content),
+            Err(_) => self.report(path, 1, (SYNTAX_ERROR, "invalid UTF-8".to_string())),
+        }
+        Ok(())
+    }
+
+    /// Verify content of journal at `path`
+    pub fn verify_text(&mut self, path: &Path, content: &str) {
+        self.files += 1;
+        let timedot = path.extension().is_some_and(|e| e == "timedot");
+        let content = content.strip_prefix(BOM).unwrap_or(content);
+
+        let mut lines: Vec<&str> = content
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .collect();
+        let truncated = !content.is_empty() && !content.ends_with('\n');
+        if !truncated {
+            // Content after the last newline
+            lines.pop();
+        }
+
+        let mut block = Block::None;
+        let mut clock_in: Option<DateTime> = None;
+        for (n, line) in lines.iter().enumerate() {
+            let line_nr = n + 1;
+            if line.trim().is_empty() {
+                self.end_block(path, std::mem::replace(&mut block, Block::None));
+                continue;
+            }
+            if line.starts_with([' ', '\t']) {
+                match &mut block {
+                    Block::Txn(_, date, postings) => {
+                        self.txn_line(path, line_nr, line, *date, postings)
+                    }
+                    Block::Directive => {}
+                    Block::None => {
+                        let msg = "indented line outside of txn".to_string();
+                        self.report(path, line_nr, (ErrorKind::BAD_INDENTATION, msg));
+                    }
+                }
+                continue;
+            }
+
+            let entry = match self.flavor {
+                JournalFlavor::Tackler => tackler::entry(line),
+                JournalFlavor::Ledger if timedot => ledger::timedot_entry(line),
+                JournalFlavor::Ledger => ledger::entry(line),
+                JournalFlavor::Beancount => beancount::entry(line),
+            };
+            if let (Err(_), Block::Txn(_, date, postings)) = (&entry, &mut block) {
+                if !line.starts_with(|c: char| c.is_ascii_digit()) {
+                    // Posting without indentation
+                    let msg = "non-indented line inside txn".to_string();
+                    self.report(path, line_nr, (ErrorKind::BAD_INDENTATION, msg));
+                    self.txn_line(path, line_nr, line, *date, postings);
+                    continue;
+                }
+            }
+            self.end_block(path, std::mem::replace(&mut block, Block::None));
+            match entry {
+                Ok(Entry::Txn(date)) => {
+                    self.txns += 1;
+                    block = Block::Txn(line_nr, date, Vec::new());
+                }
+                Ok(Entry::Directive(accounts)) => {
+                    for a in accounts {
+                        self.check_account(path, line_nr, &a);
+                    }
+                    block = Block::Directive;
+                }
+                Ok(Entry::Account(a)) => {
+                    self.check_account(path, line_nr, &a);
+                    self.declare_account(&a);
+                    block = Block::Directive;
+                }
+                Ok(Entry::Close(date, a)) => {
+                    self.check_account(path, line_nr, &a);
+                    self.closed.insert(a, date);
+                    block = Block::Directive;
+                }
+                Ok(Entry::Commodity(c)) => {
+                    self.declare_commodity(&c);
+                    block = Block::Directive;
+                }
+                Ok(Entry::Include(include)) => self.include(path, line_nr, &include),
+                Ok(Entry::ClockIn(ts, a)) => {
+                    self.check_account(path, line_nr, &a);
+                    if clock_in.replace(ts).is_some() {
+                        let msg = "check-in without check-out".to_string();
+                        self.report(path, line_nr, (SYNTAX_ERROR, msg));
+                    }
+                }
+                Ok(Entry::ClockOut(ts)) => match clock_in.take() {
+                    Some(ts_in) if ts_in <= ts => {}
+                    Some(_) => {
+                        let msg = "check-out before check-in".to_string();
+                        self.report(path, line_nr, (SYNTAX_ERROR, msg));
+                    }
+                    None => {
+                        let msg = "check-out without check-in".to_string();
+                        self.report(path, line_nr, (SYNTAX_ERROR, msg));
+                    }
+                },
+                Ok(Entry::TimeLog(a)) => self.check_account(path, line_nr, &a),
+                Ok(Entry::Comment) => {}
+                Err(issue) => {
+                    // Postings of txn with malformed date are still verified
+                    let txn = issue.0 == ErrorKind::MALFORMED_DATE;
+                    self.report(path, line_nr, issue);
+                    if txn {
+                        block = Block::Txn(line_nr, None, Vec::new());
+                    }
+                }
+            }
+        }
+        self.end_block(path, block);
+
+        if truncated {
+            let msg = "missing newline at end of file".to_string();
+            self.report(path, lines.len(), (ErrorKind::TRUNCATED_FILE, msg));
+        }
+    }
+
+    /// Check that accounts and commodities of postings are declared and
+    /// that closed accounts are not used, and sort findings by path and line
+    ///
+    /// This must be called after all files have been verified.
+    pub fn finish(&mut self) {
+        let always = self.flavor == JournalFlavor::Beancount;
+        let accounts = match &self.accounts {
+            Some(accs) => Some(accs.clone()),
+            None if always => Some(BTreeSet::new()),
+            None => None,
+        };
+        let commodities = self.commodities.clone();
+        for (path, line, account, comms, date) in std::mem::take(&mut self.uses) {
+            if let Some(accs) = &accounts {
+                if !accs.contains(&account) {
+                    let msg = format!("account '{account}' is not declared");
+                    self.report(&path, line, (ErrorKind::UNKNOWN_ACCOUNT, msg));
+                }
+            }
+            if let Some(known) = &commodities {
+                for c in comms
+                    .iter()
+                    .filter(|c| !c.is_empty() && !known.contains(*c))
+                {
+                    let msg = format!("commodity '{c}' is not declared");
+                    self.report(&path, line, (ErrorKind::UNDECLARED_COMMODITY, msg));
+                }
+            }
+            if let (Some(date), Some(close)) = (date, self.closed.get(&account)) {
+                if date > *close {
+                    let msg = format!("account '{account}' is used after its close at {close}");
+                    self.report(&path, line, (POST_CLOSE, msg));
+                }
+            }
+        }
+        self.findings
+            .sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    }
+
+    /// Posting, comment or metadata line of txn
+    fn txn_line(
+        &mut self,
+        path: &Path,
+        line_nr: usize,
+        line: &str,
+        date: Option<Date>,
+        postings: &mut Vec<Posting>,
+    ) {
+        match self.indented(line) {
+            Ok(Some(p)) => {
+                self.check_account(path, line_nr, &p.account);
+                self.uses.push((
+                    path.to_path_buf(),
+                    line_nr,
+                    p.account.clone(),
+                    p.commodities.clone(),
+                    date,
+                ));
+                postings.push(p);
+            }
+            Ok(None) => self.check_uuid(path, line_nr, line),
+            Err(issue) => self.report(path, line_nr, issue),
+        }
+    }
+
+    fn end_block(&mut self, path: &Path, block: Block) {
+        if let Block::Txn(line, _, postings) = block {
+            match is_balanced(&self.flavor, &postings) {
+                Ok(true) => {}
+                Ok(false) => {
+                    let msg = "txn doesn't balance".to_string();
+                    self.report(path, line, (ErrorKind::UNBALANCED, msg));
+                }
+                Err(issue) => self.report(path, line, issue),
+            }
+        }
+    }
+
+    /// Posting, or `None` for comment and metadata lines
+    fn indented(&self, line: &str) -> Result<Option<Posting>, Issue> {
+        let line = line.trim();
+        if line.starts_with([';', '#']) {
+            return Ok(None);
+        }
+        if self.flavor == JournalFlavor::Beancount {
+            let key = line.split_whitespace().next().unwrap_or_default();
+            if key.ends_with(':') && key.starts_with(|c: char| c.is_ascii_lowercase()) {
+                return Ok(None);
+            }
+        }
+        posting(&self.flavor, line).map(Some)
+    }
+
+    fn check_account(&mut self, path: &Path, line: usize, account: &str) {
+        if let Err(err) = account::validate(&self.flavor, account) {
+            self.report(path, line, (INVALID_ACCOUNT, err.to_string()));
+        }
+    }
+
+    fn check_uuid(&mut self, path: &Path, line: usize, text: &str) {
+        if let Some(uuid) = text.trim().strip_prefix("# uuid:") {
+            let uuid = uuid.trim().to_string();
+            if !self.uuids.insert(uuid.clone()) {
+                let msg = format!("uuid '{uuid}' is not unique");
+                self.report(path, line, (ErrorKind::DUPLICATE_UUID, msg));
+            }
+        }
+    }
+
+    fn include(&mut self, path: &Path, line: usize, include: &str) {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let inc_path = dir.join(include);
+        if !inc_path.is_file() {
+            let msg = format!("included file '{include}' not found");
+            self.report(path, line, (UNRESOLVED_INCLUDE, msg));
+            return;
+        }
+        if let Err(err) = self.verify_file(&inc_path) {
+            self.report(path, line, (UNRESOLVED_INCLUDE, err.to_string()));
+        }
+    }
+}
+
+/// Parse decimal number, e.g. `-12.340`
+pub fn number(token: &str) -> Option<Amount> {
+    let (neg, digits) = match token.strip_prefix('-') {
+        Some(d) => (true, d),
+        None => (false, token),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    let valid = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.is_empty() || !valid(int) || !valid(frac) || int.len() + frac.len() > MAX_DIGITS {
+        return None;
+    }
+    if digits.ends_with('.') {
+        return None;
+    }
+    let units: i128 = format!("{int}{frac}").parse().ok()?;
+    Some(Amount::new(
+        if neg { -units } else { units },
+        frac.len() as u32,
+    ))
+}
+
+/// Split commodity from the start of `text`, commodity may be quoted
+fn commodity(text: &str) -> Result<(String, &str), Issue> {
+    if let Some(quoted) = text.strip_prefix('"') {
+        return match quoted.split_once('"') {
+            Some((name, rest)) => Ok((name.to_string(), rest.trim_start())),
+            None => Err((SYNTAX_ERROR, "unterminated quoted commodity".to_string())),
+        };
+    }
+    match text.split_once(char::is_whitespace) {
+        Some((name, rest)) => Ok((name.to_string(), rest.trim_start())),
+        None => Ok((text.to_string(), "")),
+    }
+}
+
+/// Parse posting line (without indentation)
+///
+/// Posting is `account  amount [commodity] [@ price commodity]`,
+/// where price could also be total price (`@@` or tackler's `=`).
+pub fn posting(flavor: &JournalFlavor, line: &str) -> Result<Posting, Issue> {
+    let line = match flavor {
+        JournalFlavor::Beancount => line.strip_prefix("! ").unwrap_or(line),
+        _ => line,
+    };
+    let line = line.split_once(';').map_or(line, |(l, _)| l).trim_end();
+    // Account ends at double space or tab, so that names with spaces
+    // are reported as invalid names, instead of invalid amounts.
+    // (h)ledger account names may have single spaces.
+    let end = [line.find("  "), line.find('\t')]
+        .into_iter()
+        .flatten()
+        .min()
+        .or_else(|| match flavor {
+            JournalFlavor::Ledger => None,
+            _ => line.find(char::is_whitespace),
+        })
+        .unwrap_or(line.len());
+    let (account, rest) = line.split_at(end);
+    let rest = rest.trim();
+    let mut p = Posting {
+        account: account.to_string(),
+        commodities: Vec::new(),
+        amount: None,
+        weight: None,
+        priced: false,
+    };
+    if rest.is_empty() {
+        return Ok(p);
+    }
+
+    let invalid = |what: &str| (SYNTAX_ERROR, format!("invalid {what}: '{line}'"));
+    let (num, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let amount = number(num).ok_or_else(|| invalid("amount"))?;
+    let rest = rest.trim_start();
+    let (comm, rest) = match rest.split_whitespace().next() {
+        None | Some("@" | "@@" | "=") => (String::new(), rest),
+        Some(_) => commodity(rest)?,
+    };
+    if comm.is_empty() && *flavor == JournalFlavor::Beancount {
+        return Err(invalid("amount without commodity"));
+    }
+    p.commodities.push(comm.clone());
+    p.amount = Some(amount);
+
+    let (op, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    p.weight = match op {
+        "" => Some((amount, comm)),
+        "@" | "@@" | "=" => {
+            if op == "=" && *flavor != JournalFlavor::Tackler {
+                return Err(invalid("price"));
+            }
+            let rest = rest.trim_start();
+            let (num, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let price = number(num).ok_or_else(|| invalid("price"))?;
+            let (price_comm, rest) = commodity(rest.trim_start())?;
+            if !rest.is_empty() {
+                return Err(invalid("posting"));
+            }
+            p.priced = true;
+            p.commodities.push(price_comm.clone());
+            let weight = if op == "@" {
+                amount
+                    .checked_mul(&price)
+                    .filter(|w| w.scale as usize <= MAX_DIGITS)
+                    .ok_or_else(|| invalid("price"))?
+            } else {
+                Amount::new(price.units.abs() * amount.units.signum(), price.scale)
+            };
+            Some((weight, price_comm))
+        }
+        _ => return Err(invalid("posting")),
+    };
+    Ok(p)
+}
+
+/// Is txn balanced
+///
+/// Tackler must balance exactly. (h)ledger and beancount have tolerance,
+/// which is half of the last digit of the commodity's amounts in the txn.
+/// Amounts which are based on price don't affect the tolerance.
+pub fn is_balanced(flavor: &JournalFlavor, postings: &[Posting]) -> Result<bool, Issue> {
+    let elided = postings.iter().filter(|p| p.weight.is_none()).count();
+    if elided > 0 {
+        return Ok(elided == 1);
+    }
+    let overflow = || (SYNTAX_ERROR, "amounts are too large".to_string());
+
+    // commodity -> (weights, scale of tolerance)
+    let mut sums: BTreeMap<&str, (Vec<Amount>, Option<u32>)> = BTreeMap::new();
+    for p in postings {
+        let Some((w, c)) = &p.weight else { continue };
+        let sum = sums.entry(c.as_str()).or_default();
+        sum.0.push(*w);
+        if !p.priced && *flavor != JournalFlavor::Tackler {
+            sum.1 = Some(sum.1.map_or(w.scale, |s| s.min(w.scale)));
+        }
+    }
+    for (weights, tol_scale) in sums.values() {
+        let scale = weights.iter().map(|w| w.scale).max().unwrap_or(0);
+        let mut sum = 0i128;
+        for w in weights {
+            let units = 10i128
+                .checked_pow(scale - w.scale)
+                .and_then(|s| s.checked_mul(w.units))
+                .ok_or_else(overflow)?;
+            sum = sum.checked_add(units).ok_or_else(overflow)?;
+        }
+        let balanced = match tol_scale {
+            // |sum| * 10^-scale <= 0.5 * 10^-tol_scale
+            Some(tol_scale) => 10i128
+                .checked_pow(*tol_scale)
+                .and_then(|t| sum.checked_abs()?.checked_mul(2 * t))
+                .zip(10i128.checked_pow(scale))
+                .is_some_and(|(lhs, rhs)| lhs <= rhs),
+            None => sum == 0,
+        };
+        if !balanced {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::Amount;
+    use crate::setup::{ErrorKind, JournalFlavor};
+    use crate::verifier::{
+        INVALID_ACCOUNT, POST_CLOSE, SYNTAX_ERROR, UNRESOLVED_INCLUDE, Verifier, number, posting,
+    };
+    use std::path::Path;
+
+    fn findings(flavor: JournalFlavor, name: &str, journal: &str) -> Vec<String> {
+        let mut verifier = Verifier::new(flavor, Path::new("data"));
+        verifier.verify_text(Path::new(name), journal);
+        verifier.finish();
+        verifier
+            .findings
+            .iter()
+            .map(|f| format!("{}:{}: {}", f.path.display(), f.line, f.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number("12.340"), Some(Amount::new(12340, 3)));
+        assert_eq!(number("-0.05"), Some(Amount::new(-5, 2)));
+        assert_eq!(number("7"), Some(Amount::new(7, 0)));
+        assert_eq!(number(".5"), None);
+        assert_eq!(number("5."), None);
+        assert_eq!(number("1,000"), None);
+        assert_eq!(number("EUR"), None);
+    }
+
+    #[test]
+    fn test_posting() {
+        let p = posting(&JournalFlavor::Ledger, r#"Assets:Inventory  1.5 "Coffee kg" @ 18.20 EUR"#)
+            .unwrap(/*:test:*/);
+        assert_eq!(p.account, "Assets:Inventory");
+        assert_eq!(p.commodities, vec!["Coffee kg", "EUR"]);
+        assert_eq!(p.weight, Some((Amount::new(27300, 3), "EUR".to_string())));
+
+        let p = posting(&JournalFlavor::Ledger, "Assets:Bank Account  -4.00 EUR ; comment")
+            .unwrap(/*:test:*/);
+        assert_eq!(p.account, "Assets:Bank Account");
+        assert_eq!(p.weight, Some((Amount::new(-400, 2), "EUR".to_string())));
+
+        let p = posting(&JournalFlavor::Tackler, "e:a  -2 GBP = 2.40 EUR").unwrap(/*:test:*/);
+        assert_eq!(p.weight, Some((Amount::new(-240, 2), "EUR".to_string())));
+
+        let p = posting(&JournalFlavor::Tackler, "a:b").unwrap(/*:test:*/);
+        assert_eq!(p.weight, None);
+
+        assert!(posting(&JournalFlavor::Beancount, "Assets:A  1.00").is_err());
+        assert!(posting(&JournalFlavor::Ledger, "Assets:A  1 X = 2 EUR").is_err());
+        assert!(posting(&JournalFlavor::Ledger, "Assets:A  1.0.0 EUR").is_err());
+    }
+
+    #[test]
+    fn test_tackler() {
+        let journal = "\
+2024-01-01T10:00:00.123+02:00 (#001) 'txn 1
+  # uuid: 608abef1-71d0-544e-aedc-fdb36a566067
+  e:ey2024  1.00
+  a:ay2024
+
+2024-01-02 'txn 2
+  # uuid: 608abef1-71d0-544e-aedc-fdb36a566067
+  Expenses:Food  1.00 EUR
+  Assets:Cash  -1.001 EUR
+
+2024-02-30 'txn 3
+  Expenses:Food  1.00 EUR
+Assets:Cash
+
+2024-01-04 'txn 4
+  Expenses:Bad Name  1.00
+  Assets:Cash
+";
+        assert_eq!(
+            findings(JournalFlavor::Tackler, "data/txns/a.txn", journal),
+            vec![
+                format!("txns/a.txn:6: {}", ErrorKind::UNBALANCED),
+                format!("txns/a.txn:7: {}", ErrorKind::DUPLICATE_UUID),
+                format!("txns/a.txn:11: {}", ErrorKind::MALFORMED_DATE),
+                format!("txns/a.txn:13: {}", ErrorKind::BAD_INDENTATION),
+                format!("txns/a.txn:16: {}", INVALID_ACCOUNT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ledger() {
+        let journal = "\u{FEFF}account Assets:Bank Account\r
+account Expenses:Food\r
+commodity \"Widget A\"\r
+commodity EUR\r
+\r
+P 2024/01/01 USD 0.90 EUR\r
+\r
+2024/01/01 (#001) txn 1\r
+  Expenses:Food  8.0000001 USD @ 0.006091 EUR\r
+  Assets:Bank Account  -0.05 EUR\r
+\r
+2024/01/02 txn 2\r
+  Expenses:Food  1 \"Widget A\" @@ 2.00 EUR\r
+  Assets:Unknown  -2.00 EUR\r
+\r
+2024/01/03 txn 3\r
+  Expenses:Food  1.00 EUR\r
+  Assets:Bank Account  -1.01 EUR\r
+\r
+i 2024/01/03 10:00:00 Projects:A  session\r
+o 2024/01/03 09:00:00\r
+include missing.journal\r
+2024/01/04 txn 4\r
+  Expenses:Food  1.00 EUR\r
+  Assets:Bank Acc";
+        assert_eq!(
+            findings(JournalFlavor::Ledger, "data/txns/a.journal", journal),
+            vec![
+                format!("txns/a.journal:9: {}", ErrorKind::UNDECLARED_COMMODITY),
+                format!("txns/a.journal:14: {}", ErrorKind::UNKNOWN_ACCOUNT),
+                format!("txns/a.journal:16: {}", ErrorKind::UNBALANCED),
+                format!("txns/a.journal:21: {}", SYNTAX_ERROR),
+                format!("txns/a.journal:22: {}", UNRESOLVED_INCLUDE),
+                format!("txns/a.journal:25: {}", ErrorKind::TRUNCATED_FILE),
+                format!("txns/a.journal:25: {}", ErrorKind::UNKNOWN_ACCOUNT),
+            ]
+        );
+
+        let timedot = "2024-01-01\nProjects:A  .... ..\nProjects:B  1.5\nProjects:C  x\n";
+        assert_eq!(
+            findings(JournalFlavor::Ledger, "data/txns/a.timedot", timedot),
+            vec![format!("txns/a.timedot:4: {}", SYNTAX_ERROR)]
+        );
+    }
+
+    #[test]
+    fn test_beancount() {
+        let journal = r#"option "title" "Test"
+2024-01-01 open Assets:Cash
+2024-01-01 open Expenses:Food
+2024-01-01 open Assets:bad
+
+2024-01-01 * "txn 1"
+  id: "1"
+  Expenses:Food  1.00 EUR
+  Assets:Cash
+
+2024-01-02 * "txn 2"
+  Expenses:Food  1.00 EUR
+  Assets:Unknown  -1.00 EUR
+
+2024-01-03 balance Assets:Cash  -1.00 EUR
+2024-01-03 unknown Assets:Cash
+2024/13/01 * "txn 3"
+  Expenses:Food  1.00 EUR
+  Assets:Cash
+
+2024-01-05 close Expenses:Food
+2024-01-05 * "txn 4"
+  Expenses:Food  1.00 EUR
+  Assets:Cash
+
+2024-01-06 * "txn 5"
+  Expenses:Food  1.00 EUR
+  Assets:Cash
+"#;
+        assert_eq!(
+            findings(JournalFlavor::Beancount, "data/txns/a.beancount", journal),
+            vec![
+                format!("txns/a.beancount:4: {}", INVALID_ACCOUNT),
+                format!("txns/a.beancount:13: {}", ErrorKind::UNKNOWN_ACCOUNT),
+                format!("txns/a.beancount:16: {}", SYNTAX_ERROR),
+                format!("txns/a.beancount:17: {}", ErrorKind::MALFORMED_DATE),
+                format!("txns/a.beancount:27: {}", POST_CLOSE),
+            ]
+        );
+    }
+}
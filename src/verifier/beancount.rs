@@ -0,0 +1,93 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::setup::ErrorKind;
+use crate::verifier::{Entry, Issue, SYNTAX_ERROR};
+use jiff::civil::Date;
+
+/// Top-level line of beancount journal
+///
+/// Entries start with date, except options, plugins and includes.
+pub fn entry(line: &str) -> Result<Entry, Issue> {
+    if line.starts_with([';', '*']) {
+        return Ok(Entry::Comment);
+    }
+    let mut tokens = line.split_whitespace();
+    let first = tokens.next().unwrap_or_default();
+    match first {
+        "option" | "plugin" | "pushtag" | "poptag" | "pushmeta" | "popmeta" => {
+            return Ok(Entry::Directive(Vec::new()));
+        }
+        "include" => {
+            let path = line[first.len()..].trim().trim_matches('"');
+            return Ok(Entry::Include(path.to_string()));
+        }
+        _ => {}
+    }
+    if !first.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err((SYNTAX_ERROR, format!("unknown directive: '{line}'")));
+    }
+    let Ok(date) = first.replace('/', "-").parse::<Date>() else {
+        let msg = format!("invalid date: '{first}'");
+        return Err((ErrorKind::MALFORMED_DATE, msg));
+    };
+
+    let keyword = tokens.next().unwrap_or_default();
+    let mut account = || tokens.next().unwrap_or_default().to_string();
+    match keyword {
+        "*" | "!" | "txn" => Ok(Entry::Txn(Some(date))),
+        "open" => Ok(Entry::Account(account())),
+        "close" => Ok(Entry::Close(date, account())),
+        "balance" | "note" | "document" => Ok(Entry::Directive(vec![account()])),
+        "pad" => Ok(Entry::Directive(vec![account(), account()])),
+        "commodity" => Ok(Entry::Commodity(account())),
+        "price" | "event" | "query" | "custom" => Ok(Entry::Directive(Vec::new())),
+        _ => Err((SYNTAX_ERROR, format!("unknown directive: '{line}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::setup::ErrorKind;
+    use crate::verifier::Entry;
+    use crate::verifier::beancount::entry;
+    use jiff::civil::date;
+
+    #[test]
+    fn test_entry() {
+        assert_eq!(
+            entry("2024-01-01 * \"(#1) txn\""),
+            Ok(Entry::Txn(Some(date(2024, 1, 1))))
+        );
+        assert_eq!(
+            entry("2024-01-02 close Assets:Cash"),
+            Ok(Entry::Close(date(2024, 1, 2), "Assets:Cash".to_string()))
+        );
+        assert_eq!(
+            entry("2024-01-01 open Assets:Cash EUR"),
+            Ok(Entry::Account("Assets:Cash".to_string()))
+        );
+        assert_eq!(
+            entry("2024-01-01 pad Assets:Cash Equity:Opening-Balances"),
+            Ok(Entry::Directive(vec![
+                "Assets:Cash".to_string(),
+                "Equity:Opening-Balances".to_string()
+            ]))
+        );
+        assert_eq!(
+            entry("include \"../conf/accounts.beancount\""),
+            Ok(Entry::Include("../conf/accounts.beancount".to_string()))
+        );
+        assert_eq!(
+            entry("option \"title\" \"T\""),
+            Ok(Entry::Directive(vec![]))
+        );
+        assert_eq!(
+            entry("2024-02-30 * \"txn\"").map_err(|e| e.0),
+            Err(ErrorKind::MALFORMED_DATE)
+        );
+        assert!(entry("2024-01-01 foo").is_err());
+        assert!(entry("Assets:Cash  1.00 EUR").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::setup::ErrorKind;
+use crate::verifier::{Entry, Issue, SYNTAX_ERROR, number};
+use jiff::civil::{Date, DateTime, Time};
+
+/// Date with `/` or `-` separators
+fn date(token: &str) -> Result<Date, Issue> {
+    token.replace('/', "-").parse::<Date>().map_err(|_| {
+        (
+            ErrorKind::MALFORMED_DATE,
+            format!("invalid date: '{token}'"),
+        )
+    })
+}
+
+/// Date and time of timeclock entry, and the rest of line
+fn date_time(rest: &str) -> Result<(DateTime, &str), Issue> {
+    let mut parts = rest.trim_start().splitn(3, ' ');
+    let d = date(parts.next().unwrap_or_default())?;
+    let t = parts.next().unwrap_or_default();
+    let t = t
+        .parse::<Time>()
+        .map_err(|_| (ErrorKind::MALFORMED_DATE, format!("invalid time: '{t}'")))?;
+    Ok((d.to_datetime(t), parts.next().unwrap_or_default()))
+}
+
+/// Value without trailing comment and quotes
+fn name(value: &str) -> String {
+    let value = value.split_once(';').map_or(value, |(v, _)| v).trim();
+    value.trim_matches('"').to_string()
+}
+
+/// Top-level line of (h)ledger journal or timeclock file
+pub fn entry(line: &str) -> Result<Entry, Issue> {
+    if line.starts_with([';', '#', '*', '%', '|']) {
+        return Ok(Entry::Comment);
+    }
+    let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+    match keyword {
+        "include" => Ok(Entry::Include(name(rest))),
+        "account" => Ok(Entry::Account(name(rest))),
+        "commodity" => Ok(Entry::Commodity(name(rest))),
+        "P" => {
+            date(rest.split_whitespace().next().unwrap_or_default())?;
+            Ok(Entry::Directive(Vec::new()))
+        }
+        "i" => {
+            let (ts, rest) = date_time(rest)?;
+            let account = rest.split("  ").next().unwrap_or_default().trim_end();
+            Ok(Entry::ClockIn(ts, account.to_string()))
+        }
+        "o" => Ok(Entry::ClockOut(date_time(rest)?.0)),
+        "apply" | "end" | "alias" | "year" | "Y" | "D" | "tag" | "payee" | "define" => {
+            Ok(Entry::Directive(Vec::new()))
+        }
+        _ if keyword.starts_with(|c: char| c.is_ascii_digit()) => {
+            // Primary date, with optional auxiliary date
+            date(keyword.split('=').next().unwrap_or_default())?;
+            Ok(Entry::Txn(None))
+        }
+        _ => Err((SYNTAX_ERROR, format!("unknown directive: '{line}'"))),
+    }
+}
+
+/// Top-level line of timedot file
+///
+/// Day starts with date, and it's followed by entries of accounts,
+/// with time as dots (quarters of hour) or as hours.
+pub fn timedot_entry(line: &str) -> Result<Entry, Issue> {
+    if line.starts_with([';', '#', '*']) {
+        return Ok(Entry::Comment);
+    }
+    if line.starts_with(|c: char| c.is_ascii_digit()) {
+        date(line.split_whitespace().next().unwrap_or_default())?;
+        return Ok(Entry::Directive(Vec::new()));
+    }
+    let (account, time) = line.split_once("  ").unwrap_or((line, ""));
+    let time = time.trim();
+    let dots = !time.is_empty() && time.chars().all(|c| c == '.' || c == ' ');
+    let hours = number(time.trim_end_matches(['h', 'm'])).is_some();
+    if dots || hours {
+        Ok(Entry::TimeLog(account.to_string()))
+    } else {
+        Err((SYNTAX_ERROR, format!("invalid time: '{line}'")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::setup::ErrorKind;
+    use crate::verifier::Entry;
+    use crate::verifier::ledger::{entry, timedot_entry};
+    use jiff::civil::date;
+
+    #[test]
+    fn test_entry() {
+        assert_eq!(entry("2024/01/01 (#1) txn"), Ok(Entry::Txn(None)));
+        assert_eq!(entry("2024-01-01=2024-01-02 * txn"), Ok(Entry::Txn(None)));
+        assert_eq!(
+            entry("include ../conf/accounts.ledger"),
+            Ok(Entry::Include("../conf/accounts.ledger".to_string()))
+        );
+        assert_eq!(
+            entry("commodity \"Widget A\""),
+            Ok(Entry::Commodity("Widget A".to_string()))
+        );
+        assert_eq!(
+            entry("i 2024/01/01 10:58:48 Projects:A  session 1"),
+            Ok(Entry::ClockIn(
+                date(2024, 1, 1).at(10, 58, 48, 0),
+                "Projects:A".to_string()
+            ))
+        );
+        assert_eq!(
+            entry("o 2024/01/01 11:16:48"),
+            Ok(Entry::ClockOut(date(2024, 1, 1).at(11, 16, 48, 0)))
+        );
+        assert_eq!(
+            entry("2024/13/01 txn").map_err(|e| e.0),
+            Err(ErrorKind::MALFORMED_DATE)
+        );
+        assert!(entry("Expenses:A  1 EUR").is_err());
+    }
+
+    #[test]
+    fn test_timedot() {
+        assert_eq!(timedot_entry("2024-01-01"), Ok(Entry::Directive(vec![])));
+        assert_eq!(
+            timedot_entry("Projects:A  .... .."),
+            Ok(Entry::TimeLog("Projects:A".to_string()))
+        );
+        assert!(timedot_entry("Projects:A  0.75").is_ok());
+        assert!(timedot_entry("Projects:A").is_err());
+    }
+}
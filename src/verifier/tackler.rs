@@ -0,0 +1,141 @@
+/*
+ * PTA-Generator 2025
+ * SPDX-License-Identifier: Apache-2.0
+ */
+use crate::setup::ErrorKind;
+use crate::verifier::{Entry, Issue, SYNTAX_ERROR};
+use jiff::civil::{Date, DateTime};
+use jiff::{Timestamp, Zoned};
+use std::path::{Path, PathBuf};
+
+/// Top-level line of tackler journal
+///
+/// Txn header starts with date or timestamp, which may have offset or zone.
+pub fn entry(line: &str) -> Result<Entry, Issue> {
+    if line.starts_with(['#', ';']) {
+        return Ok(Entry::Comment);
+    }
+    let token = line.split_whitespace().next().unwrap_or_default();
+    if !token.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err((SYNTAX_ERROR, format!("expected txn header: '{line}'")));
+    }
+    let valid = if token.contains('T') {
+        token.parse::<Timestamp>().is_ok()
+            || token.parse::<Zoned>().is_ok()
+            || token.parse::<DateTime>().is_ok()
+    } else {
+        token.parse::<Date>().is_ok()
+    };
+    if valid {
+        Ok(Entry::Txn(None))
+    } else {
+        let msg = format!("invalid date: '{token}'");
+        Err((ErrorKind::MALFORMED_DATE, msg))
+    }
+}
+
+/// Paths of tackler config, relative to the directory of config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub strict: bool,
+    /// Input directory of txns
+    pub input: Option<PathBuf>,
+    pub accounts: Option<PathBuf>,
+    pub commodities: Option<PathBuf>,
+}
+
+/// Quoted value of `key` on the line, e.g. `path = "value"`
+fn value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(&format!("{key} = \""))? + key.len() + 4;
+    let len = line[start..].find('"')?;
+    Some(&line[start..start + len])
+}
+
+/// Parse paths of generated tackler config
+pub fn config(toml: &str) -> Config {
+    let mut config = Config::default();
+    for line in toml.lines().map(str::trim) {
+        let key = line.split([' ', '=']).next().unwrap_or_default();
+        let path = value(line, "path").filter(|p| *p != "none").map(Path::new);
+        if !line.contains('{') {
+            // Settings of the config are inline tables
+            if key == "strict" {
+                config.strict = line.ends_with("true");
+            }
+            continue;
+        }
+        match key {
+            "fs" => {
+                config.input = path.zip(value(line, "dir")).map(|(p, d)| p.join(d));
+            }
+            "accounts" => config.accounts = path.map(Path::to_path_buf),
+            "commodities" => config.commodities = path.map(Path::to_path_buf),
+            _ => {}
+        }
+    }
+    config
+}
+
+/// Names of chart of accounts or commodities (`accounts = [ "A:B", ... ]`)
+pub fn names(toml: &str) -> Vec<String> {
+    toml.lines()
+        .map(str::trim)
+        .filter_map(|l| l.strip_prefix('"'))
+        .filter_map(|l| l.trim_end_matches(',').strip_suffix('"'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::setup::ErrorKind;
+    use crate::verifier::Entry;
+    use crate::verifier::tackler::{config, entry, names};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_entry() {
+        assert_eq!(entry("2024-01-01 'txn"), Ok(Entry::Txn(None)));
+        assert_eq!(
+            entry("2024-01-01T10:00:00.5+02:00 (#1) 'txn"),
+            Ok(Entry::Txn(None))
+        );
+        assert_eq!(
+            entry("2024-01-01T10:00:00[Europe/Helsinki]"),
+            Ok(Entry::Txn(None))
+        );
+        assert_eq!(entry("2024-01-01T10:00:00"), Ok(Entry::Txn(None)));
+        assert_eq!(entry("# comment"), Ok(Entry::Comment));
+        assert_eq!(
+            entry("2024-13-01 'txn").map_err(|e| e.0),
+            Err(ErrorKind::MALFORMED_DATE)
+        );
+        assert!(entry("e:a  1").is_err());
+    }
+
+    #[test]
+    fn test_config() {
+        let toml = r#"
+[kernel]
+strict = true
+[kernel.input]
+fs  = { path = "set-1e2-single",      dir = "txns", ext = "txn" }
+[transaction]
+accounts    = { path = "set-1e2-single/conf/accounts.toml" }
+commodities = { path = "none" }
+[report]
+accounts = [ ]
+"#;
+        let config = config(toml);
+        assert!(config.strict);
+        assert_eq!(config.input, Some(PathBuf::from("set-1e2-single/txns")));
+        assert_eq!(
+            config.accounts,
+            Some(PathBuf::from("set-1e2-single/conf/accounts.toml"))
+        );
+        assert_eq!(config.commodities, None);
+
+        let names = names("accounts = [\n   \"a:b\",\n   \"c\",\n]\n");
+        assert_eq!(names, vec!["a:b", "c"]);
+    }
+}
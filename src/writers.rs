@@ -3,46 +3,185 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::setup::JournalSetup;
-use crate::setup::{JournalFlavor, SetSize, ShardType};
-use jiff::Zoned;
+use crate::archive::{Compressor, Shared, TarWriter};
+use crate::manifest::Manifest;
+use crate::reference::{self, Reference};
+use crate::setup::{Archive, JournalSetup, Output, ShardOptions, gz_path};
+use crate::setup::{JournalFlavor, JournalFormat, OutputPolicy, SetSize, ShardType};
+use crate::verifier;
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
 use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
-use std::fs::File;
-use std::io::BufWriter;
-use std::path::Path;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::{fs, io};
 
 type RefWriter = Rc<RefCell<Box<dyn io::Write>>>;
 
+/// Byte order mark of UTF-8, written at the start of journal with `--bom`
+pub const BOM: &str = "\u{FEFF}";
+
+/// Maximum count of open journals, other journals are closed when this is reached
+///
+/// Txns are written in time order, so closed journals are rarely continued.
+const MAX_OPEN_JOURNALS: usize = 64;
+
+/// Journal of the last write of `ShardType::Size`
+#[derive(Debug, Clone)]
+struct SizeShard {
+    /// Number of journal, see `JournalSetup::numbered_path`
+    shard: u32,
+    /// Size of journal with the last write
+    bytes: u64,
+    /// Count of writes (txns, directives and headers) of journal
+    txns: u32,
+}
+
 pub struct JournalWriter {
     pub setup: JournalSetup,
-    writers: Vec<Option<RefWriter>>,
+    /// Open journals by their target path
+    writers: HashMap<PathBuf, RefWriter>,
+    lines: Option<HashMap<PathBuf, usize>>,
+    reference: Option<Reference>,
+    format: JournalFormat,
+    /// Existing journals are appended, and other files are replaced
+    append: bool,
+    /// Files are written here, and moved into place by commit
+    staging: Option<PathBuf>,
+    output: Output,
+    /// Archive of the set, which is written into staging directory
+    tar: Option<TarWriter<Compressor>>,
+    /// Unfinished journals of the archive, which are kept in memory until they are complete
+    buffers: BTreeMap<PathBuf, Rc<RefCell<Vec<u8>>>>,
+    /// Compressed journal of single shard set, which is finished by commit
+    compressed: Option<Rc<RefCell<Compressor>>>,
+    size_shard: Option<SizeShard>,
 }
 
 impl JournalWriter {
-    /// Fails if target path exists
+    /// Message for existing set, which is kept with `OutputPolicy::SkipExisting`
+    pub const SET_KEPT: &'static str = "Test set exists already, and it is kept";
+
+    /// Writer for new txn set, files are written into staging directory
+    ///
+    /// With `OutputPolicy::Create`, fails if the set exists, and with
+    /// `OutputPolicy::SkipExisting`, there is no writer (`None`) if the set exists.
+    /// Staging directory of an interrupted run is removed, and the set
+    /// of an interrupted commit is restored (see `JournalWriter::commit`).
+    /// Files are moved into place by `JournalWriter::commit`.
+    ///
+    /// With archive, the set is written directly into the archive,
+    /// which is the only file in the staging directory.
     pub fn try_new(
         flavor: JournalFlavor,
         path: &Path,
         set: SetSize,
         shard_type: ShardType,
-    ) -> Result<JournalWriter, Box<dyn Error>> {
-        let setup = JournalSetup::try_new(flavor, path, set, shard_type.clone())?;
-        let w = JournalWriter {
-            setup,
-            writers: vec![None; 12],
+        output: Output,
+    ) -> Result<Option<JournalWriter>, Box<dyn Error>> {
+        if output.archive == Some(Archive::Gz) && shard_type != ShardType::Single {
+            let msg = format!(
+                "Archive format '{}' is supported only with shard type '{}'",
+                Archive::Gz,
+                ShardType::Single
+            );
+            return Err(msg.into());
+        }
+        let mut setup = JournalSetup::try_new(flavor, path, set, shard_type)?;
+        setup.set_shard_options(output.shards.clone())?;
+        setup.archive = output.archive.clone();
+        let replaced = setup.replaced_path();
+        if replaced.exists() {
+            if setup.txn_set_path().exists() {
+                fs::remove_dir_all(&replaced)?;
+            } else {
+                fs::rename(&replaced, setup.txn_set_path())?;
+            }
+        }
+        if let Some(existing) = setup.existing_path() {
+            match output.policy {
+                OutputPolicy::Create => {
+                    let msg = format!(
+                        "Target path already exists: '{}', use '--overwrite' to replace it or '--skip-existing' to keep it",
+                        existing.display()
+                    );
+                    return Err(msg.into());
+                }
+                OutputPolicy::SkipExisting => return Ok(None),
+                OutputPolicy::Overwrite => {}
+            }
+        }
+        let staging = setup.staging_path();
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        let tar = match (&output.archive, setup.archive_path()) {
+            (Some(archive), Some(archive_path)) => {
+                fs::create_dir_all(&staging)?;
+                let file_name = archive_path.file_name().expect("IE: missing archive name");
+                let out = Compressor::create(&staging.join(file_name), archive)?;
+                Some(TarWriter::new(out))
+            }
+            _ => None,
         };
+        Ok(Some(JournalWriter {
+            setup,
+            writers: HashMap::new(),
+            lines: None,
+            reference: None,
+            format: JournalFormat::default(),
+            append: false,
+            staging: Some(staging),
+            output,
+            tar,
+            buffers: BTreeMap::new(),
+            compressed: None,
+            size_shard: None,
+        }))
+    }
 
-        if shard_type != ShardType::Single && w.setup.txn_set_path().exists() {
+    /// Writer for existing txn set, fails if target path doesn't exist
+    ///
+    /// Journals are appended (shards are created if they are missing),
+    /// and other files (config, charts, references and manifest) are replaced.
+    pub fn try_append(
+        flavor: JournalFlavor,
+        path: &Path,
+        set: SetSize,
+        shard_type: ShardType,
+        shards: ShardOptions,
+    ) -> Result<JournalWriter, Box<dyn Error>> {
+        let mut setup = JournalSetup::try_new(flavor, path, set, shard_type)?;
+        setup.set_shard_options(shards)?;
+        if !setup.txn_set_path().exists() {
             let msg = format!(
-                "Target path already exists: '{}'",
-                w.setup.txn_set_path().display()
+                "Target path doesn't exist: '{}'",
+                setup.txn_set_path().display()
             );
             return Err(msg.into());
         }
-        Ok(w)
+        let size_shard = match setup.shard_type {
+            ShardType::Size => last_size_shard(&setup)?,
+            _ => None,
+        };
+        Ok(JournalWriter {
+            setup,
+            writers: HashMap::new(),
+            lines: None,
+            reference: None,
+            format: JournalFormat::default(),
+            append: true,
+            staging: None,
+            output: Output::default(),
+            tar: None,
+            buffers: BTreeMap::new(),
+            compressed: None,
+            size_shard,
+        })
     }
 
     /// Makes writer for path and creates any missing directories
@@ -60,66 +199,562 @@ impl JournalWriter {
         Ok(Box::new(bw))
     }
 
-    fn journal_writer(&mut self, ts: &Zoned, idx: u32) -> Result<RefWriter, Box<dyn Error>> {
-        match self.setup.shard_type {
-            ShardType::Single => {
-                if let Some(w) = &self.writers[0] {
-                    Ok(w.clone())
-                } else {
-                    let txn_path = self.setup.journal_path(ts, idx);
-                    let w = Self::make_writer(&txn_path.0, &txn_path.1)?;
-                    let rcw = Rc::new(RefCell::new(w));
-                    self.writers[0] = Some(rcw.clone());
+    /// Staged path of target path, target path if there is no staging
+    fn staged(&self, path: &Path) -> PathBuf {
+        match &self.staging {
+            Some(staging) => path
+                .strip_prefix(&self.setup.path)
+                .map_or(path.to_path_buf(), |rel| staging.join(rel)),
+            None => path.to_path_buf(),
+        }
+    }
 
-                    Ok(rcw)
-                }
+    /// Makes writer for other file than journal, the file is replaced in append mode
+    fn make_file_writer(
+        &self,
+        dir: &Path,
+        path: &Path,
+    ) -> Result<Box<dyn io::Write>, Box<dyn Error>> {
+        let (dir, path) = (&self.staged(dir), &self.staged(path));
+        if !self.append {
+            return Self::make_writer(dir, path);
+        }
+        fs::create_dir_all(dir)?;
+        let f = File::create(path).map_err(|err| format!("{}: '{}'", err, path.display()))?;
+        Ok(Box::new(BufWriter::new(f)))
+    }
+
+    /// Write other file than journal
+    ///
+    /// With archive, the file is appended into the archive.
+    fn write_file(&mut self, dir: &Path, path: &Path, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        if let Some(tar) = self.tar.as_mut() {
+            let rel_path = path
+                .strip_prefix(&self.setup.path)
+                .expect("IE: file is outside of data path");
+            return tar.append(rel_path, data);
+        }
+        let mut w = self.make_file_writer(dir, path)?;
+        Ok(w.write_all(data)?)
+    }
+
+    /// Makes writer for journal file, with BOM if it is used by the format
+    ///
+    /// Existing journal (in append mode, or journal which was closed
+    /// by this writer) is appended without BOM.
+    /// Compressed journal is written through the encoder. With archive,
+    /// the journal is kept in memory, and it's written into the archive
+    /// when it's complete (see `JournalWriter::archive_journals`).
+    fn make_journal_writer(
+        &mut self,
+        dir: &Path,
+        txn_path: &Path,
+    ) -> Result<Box<dyn io::Write>, Box<dyn Error>> {
+        if let Some(w) = &self.compressed {
+            return Ok(Box::new(Shared(w.clone())));
+        }
+        if self.tar.is_some() {
+            if let Some(buf) = self.buffers.get(txn_path) {
+                return Ok(Box::new(Shared(buf.clone())));
             }
-            ShardType::Month => {
-                let m = ts.month() - 1;
-                if let Some(w) = &self.writers[m as usize] {
-                    Ok(w.clone())
-                } else {
-                    let txn_path = self.setup.journal_path(ts, idx);
-                    let w = Self::make_writer(&txn_path.0, &txn_path.1)?;
+            let buf = Rc::new(RefCell::new(Vec::new()));
+            if self.format.bom {
+                buf.borrow_mut().extend_from_slice(BOM.as_bytes());
+            }
+            self.buffers.insert(txn_path.to_path_buf(), buf.clone());
+            return Ok(Box::new(Shared(buf)));
+        }
+        let (dir, txn_path) = (&self.staged(dir), &self.staged(txn_path));
+        if self.output.archive == Some(Archive::Gz) {
+            fs::create_dir_all(dir)?;
+            let w = Rc::new(RefCell::new(Compressor::create(
+                &gz_path(txn_path),
+                &Archive::Gz,
+            )?));
+            if self.format.bom {
+                w.borrow_mut().write_all(BOM.as_bytes())?;
+            }
+            self.compressed = Some(w.clone());
+            return Ok(Box::new(Shared(w)));
+        }
+        if txn_path.exists() {
+            let f = OpenOptions::new()
+                .append(true)
+                .open(txn_path)
+                .map_err(|err| format!("{}: '{}'", err, txn_path.display()))?;
+            return Ok(Box::new(BufWriter::new(f)));
+        }
+        let mut w = Self::make_writer(dir, txn_path)?;
+        if self.format.bom {
+            w.write_all(BOM.as_bytes())?;
+        }
+        Ok(w)
+    }
 
-                    let rcw = Rc::new(RefCell::new(w));
-                    self.writers[m as usize] = Some(rcw.clone());
+    fn journal_writer(
+        &mut self,
+        txn_dir: &Path,
+        txn_path: &Path,
+    ) -> Result<RefWriter, Box<dyn Error>> {
+        if self.setup.shard_type.txn_per_file() {
+            let w = self.make_journal_writer(txn_dir, txn_path)?;
+            return Ok(Rc::new(RefCell::new(w)));
+        }
+        if let Some(w) = self.writers.get(txn_path) {
+            return Ok(w.clone());
+        }
+        if self.tar.is_some() && self.setup.shard_type == ShardType::Size {
+            // size shards are written in order, so the previous journal is complete
+            self.archive_journals()?;
+        }
+        if self.writers.len() >= MAX_OPEN_JOURNALS {
+            self.close_journals()?;
+        }
+        let w = self.make_journal_writer(txn_dir, txn_path)?;
+        let rcw = Rc::new(RefCell::new(w));
+        self.writers.insert(txn_path.to_path_buf(), rcw.clone());
 
-                    Ok(rcw)
-                }
+        Ok(rcw)
+    }
+
+    /// Path of journal of the txn, see `JournalSetup::txn_path`
+    ///
+    /// With `ShardType::Size`, this is the journal of `JournalWriter::next_size_shard`.
+    fn txn_path(&self, ts: &Zoned, idx: u32, txn: &str) -> (PathBuf, PathBuf) {
+        if self.setup.shard_type != ShardType::Size {
+            return self.setup.txn_path(ts, idx, txn);
+        }
+        self.setup.numbered_path(self.next_size_shard(txn).shard)
+    }
+
+    /// Journal of `ShardType::Size`, after the txn is written
+    ///
+    /// The txn is in the same journal as the previous write (txn, directives or header),
+    /// if the journal stays within the maximum size, otherwise it's in the next journal.
+    /// Journal has always at least one write, so a single write could exceed the maximum size.
+    fn next_size_shard(&self, txn: &str) -> SizeShard {
+        let bom = if self.format.bom { 3 } else { 0 };
+        let last = self.size_shard.clone().unwrap_or(SizeShard {
+            shard: 1,
+            bytes: bom,
+            txns: 0,
+        });
+        let max = self.setup.shards.bytes.unwrap_or(u64::MAX);
+        let len = self.format.apply(txn).len() as u64;
+        if last.txns > 0 && last.bytes + len > max {
+            SizeShard {
+                shard: last.shard + 1,
+                bytes: bom + len,
+                txns: 1,
+            }
+        } else {
+            SizeShard {
+                bytes: last.bytes + len,
+                txns: last.txns + 1,
+                ..last
             }
-            ShardType::Txn => {
-                let txn_path = self.setup.journal_path(ts, idx);
-                let w = Self::make_writer(&txn_path.0, &txn_path.1)?;
+        }
+    }
+
+    /// Flush and close open journals
+    ///
+    /// Journals of the archive stay in memory, and they are continued
+    /// if there are more txns for them.
+    fn close_journals(&mut self) -> Result<(), Box<dyn Error>> {
+        for w in self.writers.values() {
+            w.borrow_mut().flush()?;
+        }
+        self.writers.clear();
+        Ok(())
+    }
+
+    /// Write journals, which are kept in memory, into the archive (sorted by path)
+    ///
+    /// Journals must be complete, i.e. there are no more txns for them.
+    fn archive_journals(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(tar) = self.tar.as_mut() else {
+            return Ok(());
+        };
+        self.writers.clear();
+        for (txn_path, buf) in std::mem::take(&mut self.buffers) {
+            let rel_path = txn_path
+                .strip_prefix(&self.setup.path)
+                .expect("IE: journal is outside of data path");
+            tar.append(rel_path, &buf.take())?;
+        }
+        Ok(())
+    }
+
+    /// Set text format of journal files
+    ///
+    /// This must be set before any txn is written.
+    /// Tackler's configuration and charts (TOML) are not affected by the format.
+    pub fn set_format(&mut self, format: JournalFormat) {
+        self.format = format;
+    }
 
-                let rcw = Rc::new(RefCell::new(w));
-                Ok(rcw)
+    /// Track line counts of written journals
+    ///
+    /// This must be activated before any txn is written,
+    /// see `JournalWriter::journal_position`.
+    pub fn track_lines(&mut self) {
+        self.lines = Some(HashMap::new());
+    }
+
+    /// Track postings of written txns for reference reports
+    ///
+    /// This must be activated before any txn is written,
+    /// see `JournalWriter::write_references`.
+    pub fn track_postings(&mut self) {
+        self.reference = Some(Reference::new(self.setup.flavor.clone()));
+    }
+
+    /// Add txns of existing journals of the set to tracked postings
+    ///
+    /// This is used in append mode, so that references cover the whole set.
+    /// Entries of journals are separated by blank lines.
+    pub fn read_journals(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(reference) = self.reference.as_mut() else {
+            return Ok(());
+        };
+        let txns_dir = self.setup.txn_set_path().join("txns");
+        for path in verifier::journal_files(&txns_dir, &self.setup.flavor)? {
+            let text = fs::read_to_string(&path)
+                .map_err(|err| format!("{}: '{}'", err, path.display()))?;
+            let mut entry = String::new();
+            for line in text.trim_start_matches(BOM).lines() {
+                if line.trim().is_empty() {
+                    reference.add_txn(&entry)?;
+                    entry.clear();
+                } else {
+                    entry.push_str(line);
+                    entry.push('\n');
+                }
             }
+            reference.add_txn(&entry)?;
         }
+        Ok(())
     }
+
+    /// Tracked postings, if they are tracked
+    pub fn reference(&self) -> Option<&Reference> {
+        self.reference.as_ref()
+    }
+
+    /// Journal path and line number (1-based) of the next txn
+    ///
+    /// Line numbers are available only if line tracking is active,
+    /// otherwise line number is always 1.
+    pub fn journal_position(&self, ts: &Zoned, idx: u32, txn: &str) -> (PathBuf, usize) {
+        let (_, txn_path) = self.txn_path(ts, idx, txn);
+        let line = self
+            .lines
+            .as_ref()
+            .and_then(|lines| lines.get(&txn_path))
+            .map_or(1, |l| l + 1);
+        (txn_path, line)
+    }
+
     pub fn write_txn(&mut self, ts: &Zoned, idx: u32, txn: &str) -> Result<(), Box<dyn Error>> {
-        let w = self.journal_writer(ts, idx)?;
-        Ok(write!(w.borrow_mut(), "{}", txn)?)
+        let (txn_dir, txn_path) = self.txn_path(ts, idx, txn);
+        if let Some(lines) = self.lines.as_mut() {
+            *lines.entry(txn_path.clone()).or_insert(0) += txn.matches('\n').count();
+        }
+        if let Some(reference) = self.reference.as_mut() {
+            reference.add_txn(txn)?;
+        }
+        if self.setup.shard_type == ShardType::Size {
+            self.size_shard = Some(self.next_size_shard(txn));
+        }
+        if self.setup.shard_type.txn_per_file() && self.tar.is_some() {
+            // txn shard is complete, so it goes directly into the archive
+            let bom = if self.format.bom { BOM } else { "" };
+            let data = format!("{bom}{}", self.format.apply(txn));
+            return self.write_file(&txn_dir, &txn_path, data.as_bytes());
+        }
+        let w = self.journal_writer(&txn_dir, &txn_path)?;
+        Ok(write!(w.borrow_mut(), "{}", self.format.apply(txn))?)
+    }
+
+    /// Write chart, it's a journal for all other flavors than tackler
+    ///
+    /// Chart is always replaced in append mode.
+    fn write_chart(&mut self, dir: &Path, path: &Path, chart: &str) -> Result<(), Box<dyn Error>> {
+        if self.setup.flavor == JournalFlavor::Tackler {
+            self.write_file(dir, path, chart.as_bytes())
+        } else {
+            let bom = if self.format.bom { BOM } else { "" };
+            let data = format!("{bom}{}", self.format.apply(chart));
+            self.write_file(dir, path, data.as_bytes())
+        }
     }
     pub fn write_chart_of_accounts(&mut self, chart: &str) -> Result<(), Box<dyn Error>> {
         let (chart_dir, chart_path) = self.setup.chart_of_accounts_path();
-        let mut w = Self::make_writer(&chart_dir, &chart_path)?;
-
-        Ok(write!(w, "{}", chart)?)
+        self.write_chart(&chart_dir, &chart_path, chart)
     }
 
     pub fn write_chart_of_commodities(&mut self, chart: &str) -> Result<(), Box<dyn Error>> {
         let (chart_dir, chart_path) = self.setup.chart_of_commodities_path();
-        let mut w = Self::make_writer(&chart_dir, &chart_path)?;
+        self.write_chart(&chart_dir, &chart_path, chart)
+    }
 
-        Ok(write!(w, "{}", chart)?)
+    pub fn write_document(&mut self, name: &str, content: &str) -> Result<(), Box<dyn Error>> {
+        let (doc_dir, doc_path) = self.setup.document_path(name);
+        self.write_file(&doc_dir, &doc_path, content.as_bytes())
+    }
+
+    pub fn write_expected_errors(&mut self, errors: &str) -> Result<(), Box<dyn Error>> {
+        let (set_dir, errors_path) = self.setup.expected_errors_path();
+        self.write_file(&set_dir, &errors_path, errors.as_bytes())
+    }
+
+    /// Write reference reports of tracked postings
+    ///
+    /// `scale` is the maximum scale of tackler reports.
+    pub fn write_references(&mut self, scale: u32) -> Result<(), Box<dyn Error>> {
+        self.write_reference_reports(scale, false)
+    }
+
+    /// Write reference reports of tracked postings for tackler audit mode
+    ///
+    /// Tackler reports have the txn set checksum as metadata.
+    pub fn write_audit_references(&mut self, scale: u32) -> Result<(), Box<dyn Error>> {
+        self.write_reference_reports(scale, true)
+    }
+
+    fn write_reference_reports(&mut self, scale: u32, audit: bool) -> Result<(), Box<dyn Error>> {
+        let Some(reference) = &self.reference else {
+            return Ok(());
+        };
+        let reports = match self.setup.flavor {
+            JournalFlavor::Tackler => {
+                reference::tackler::reports(reference, &self.setup.txn_set, scale, audit)?
+            }
+            JournalFlavor::Ledger | JournalFlavor::Beancount => {
+                reference::balances::reports(reference, &self.setup.flavor)?
+            }
+        };
+        for (name, report) in reports {
+            let (ref_dir, ref_path) = self.setup.reference_path(name);
+            self.write_file(&ref_dir, &ref_path, report.as_bytes())?;
+        }
+        Ok(())
     }
 
     pub fn write_config(&mut self, config: &str) -> Result<(), Box<dyn Error>> {
         let (toml_dir, toml_path) = self.setup.config_path();
-        let mut w = Self::make_writer(&toml_dir, &toml_path)?;
+        self.write_file(&toml_dir, &toml_path, config.as_bytes())
+    }
 
-        Ok(write!(w, "{}", config)?)
+    /// Flush journals and move staged files into place
+    ///
+    /// This must be called after all files are written. If the set exists,
+    /// it is replaced only with `OutputPolicy::Overwrite`: sharded set is
+    /// replaced as a whole (see `replace_set`), and files of single shard set
+    /// are replaced (other flavors of the set are kept).
+    ///
+    /// Journals in memory are written into the archive, compressed journal
+    /// is finished, and the archive is finished.
+    pub fn commit(&mut self) -> Result<(), Box<dyn Error>> {
+        self.close_journals()?;
+        self.archive_journals()?;
+        if let Some(w) = self.compressed.take() {
+            let w = Rc::try_unwrap(w).map_err(|_| "IE: compressed journal is still in use")?;
+            w.into_inner().finish()?;
+        }
+        if let Some(tar) = self.tar.take() {
+            tar.finish()?.finish()?;
+        }
+        let Some(staging) = self.staging.take() else {
+            return Ok(());
+        };
+        if !staging.exists() {
+            return Ok(());
+        }
+        if self.output.policy == OutputPolicy::Overwrite {
+            let set_path = self.setup.txn_set_path();
+            let sharded = self.setup.shard_type != ShardType::Single;
+            if sharded && self.output.archive.is_none() && set_path.exists() {
+                replace_set(
+                    &staging.join(self.setup.txn_set_dir()),
+                    &set_path,
+                    &self.setup.replaced_path(),
+                )?;
+            }
+        } else if let Some(existing) = existing_files(&staging, &self.setup.path)?.first() {
+            fs::remove_dir_all(&staging)?;
+            let msg = format!(
+                "Target path already exists: '{}', use '--overwrite' to replace it",
+                existing.display()
+            );
+            return Err(msg.into());
+        }
+        move_into(&staging, &self.setup.path)?;
+        Ok(fs::remove_dir_all(&staging)?)
+    }
+
+    /// Message with note about archive or compressed journal of the set
+    ///
+    /// Paths of the message are valid after the archive is extracted at the data path.
+    pub fn archived_msg(&mut self, mut msg: String) -> String {
+        let note = match (&self.output.archive, self.setup.archive_path()) {
+            (Some(Archive::Gz), _) => {
+                let ts = Timestamp::UNIX_EPOCH.to_zoned(TimeZone::UTC);
+                let journal = gz_path(&self.setup.journal_path(&ts, 0).1);
+                format!("Journal is compressed: {}", journal.display())
+            }
+            (Some(_), Some(archive_path)) => format!(
+                "Test set is archived at: {}, extract it at: {}",
+                archive_path.display(),
+                self.setup.path.display()
+            ),
+            _ => return msg,
+        };
+        msg.push_str(&note);
+        msg.push('\n');
+        msg
+    }
+
+    pub fn write_manifest(&mut self, manifest: &Manifest) -> Result<(), Box<dyn Error>> {
+        let (set_dir, manifest_path) = self.setup.manifest_path();
+        self.write_file(&set_dir, &manifest_path, manifest.to_toml()?.as_bytes())
+    }
+}
+
+impl Drop for JournalWriter {
+    /// Staged files are removed, if they are not committed (e.g. in case of error)
+    fn drop(&mut self) {
+        if let Some(staging) = self.staging.take() {
+            self.writers.clear();
+            self.buffers.clear();
+            self.tar = None;
+            self.compressed = None;
+            let _ = fs::remove_dir_all(staging);
+        }
+    }
+}
+
+/// The last existing journal of `ShardType::Size`, which is continued in append mode
+fn last_size_shard(setup: &JournalSetup) -> Result<Option<SizeShard>, Box<dyn Error>> {
+    let txns_dir = setup.txn_set_path().join("txns");
+    if !txns_dir.exists() {
+        return Ok(None);
+    }
+    let mut last: Option<(u32, PathBuf)> = None;
+    for entry in fs::read_dir(&txns_dir)? {
+        let path = entry?.path();
+        let number = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u32>().ok());
+        if let Some(number) = number {
+            if last.as_ref().is_none_or(|(n, _)| number > *n) {
+                last = Some((number, path));
+            }
+        }
+    }
+    let Some((shard, path)) = last else {
+        return Ok(None);
+    };
+    let bytes = fs::metadata(&path)?.len();
+    Ok(Some(SizeShard {
+        shard,
+        bytes,
+        txns: u32::from(bytes > 0),
+    }))
+}
+
+/// Replace existing set with the staged set
+///
+/// Existing set is renamed aside to `replaced`, the staged set is renamed
+/// into place, and only then the existing set is removed. If the staged set
+/// can't be moved into place, the existing set is restored, and if the process
+/// is interrupted, the set is restored by the next `JournalWriter::try_new`.
+fn replace_set(staged: &Path, set_path: &Path, replaced: &Path) -> Result<(), Box<dyn Error>> {
+    if replaced.exists() {
+        fs::remove_dir_all(replaced)?;
+    }
+    fs::rename(set_path, replaced).map_err(|err| format!("{}: '{}'", err, set_path.display()))?;
+    if let Err(err) = fs::rename(staged, set_path) {
+        fs::rename(replaced, set_path)?;
+        return Err(format!("{}: '{}'", err, staged.display()).into());
+    }
+    Ok(fs::remove_dir_all(replaced)?)
+}
+
+/// Move files from `from` into `to`, existing files are replaced
+///
+/// Directories, which don't exist in `to`, are moved as a whole.
+fn move_into(from: &Path, to: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let target = to.join(name);
+        if path.is_dir() && target.is_dir() {
+            move_into(&path, &target)?;
+        } else {
+            fs::rename(&path, &target).map_err(|err| format!("{}: '{}'", err, target.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Files under `from`, which exist also under `to`
+fn existing_files(from: &Path, to: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let target = to.join(name);
+        if path.is_dir() {
+            if target.is_dir() {
+                files.extend(existing_files(&path, &target)?);
+            }
+        } else if target.exists() {
+            files.push(target);
+        }
+    }
+    Ok(files)
+}
+
+/// Writer for fuzzing corpus
+///
+/// Corpus is stored under `path/fuzz/<flavor>`.
+pub struct CorpusWriter {
+    pub flavor: JournalFlavor,
+    pub corpus_path: PathBuf,
+}
+
+impl CorpusWriter {
+    /// Fails if target path exists
+    pub fn try_new(flavor: JournalFlavor, path: &Path) -> Result<CorpusWriter, Box<dyn Error>> {
+        let corpus_path = path.join("fuzz").join(flavor.to_string());
+        if corpus_path.exists() {
+            let msg = format!("Target path already exists: '{}'", corpus_path.display());
+            return Err(msg.into());
+        }
+        Ok(CorpusWriter {
+            flavor,
+            corpus_path,
+        })
+    }
+
+    /// Write corpus file, extension is based on flavor
+    pub fn write_case(&mut self, name: &str, content: &[u8]) -> Result<(), Box<dyn Error>> {
+        let ext = match self.flavor {
+            JournalFlavor::Tackler => "txn",
+            JournalFlavor::Ledger => "journal",
+            JournalFlavor::Beancount => "beancount",
+        };
+        let case_path = self.corpus_path.join(format!("{name}.{ext}"));
+        let mut w = JournalWriter::make_writer(&self.corpus_path, &case_path)?;
+
+        Ok(w.write_all(content)?)
     }
 }
 
@@ -134,21 +769,21 @@ mod tests {
         let base_path = Path::new("target/cbc1a015-a250-4041-ba81-21a26a414a4b");
 
         // Test for ShardType::Single
-        let writer_single = JournalWriter::try_new(JournalFlavor::Ledger, base_path, SetSize::Sz1e3, ShardType::Single).unwrap(/*:test:*/);
+        let writer_single = JournalWriter::try_new(JournalFlavor::Ledger, base_path, SetSize::Sz1e3, ShardType::Single, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
         assert_eq!(
             writer_single.setup.txn_set_path(),
             base_path.join("set-1e3-single")
         );
 
         // Test for ShardType::Month
-        let writer_month = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e5, ShardType::Month).unwrap(/*:test:*/);
+        let writer_month = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e5, ShardType::Month, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
         assert_eq!(
             writer_month.setup.txn_set_path(),
             base_path.join("set-1e5-month")
         );
 
         // Test for ShardType::Txn
-        let writer_txn = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e6, ShardType::Txn).unwrap(/*:test:*/);
+        let writer_txn = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e6, ShardType::Txn, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
         assert_eq!(
             writer_txn.setup.txn_set_path(),
             base_path.join("set-1e6-txn")
@@ -164,7 +799,7 @@ mod tests {
             .expect("Failed to parse timestamp");
 
         // Test for ShardType::Single
-        let mut writer_single = JournalWriter::try_new(JournalFlavor::Ledger, base_path, SetSize::Sz1e3, ShardType::Single).unwrap(/*:test:*/);
+        let writer_single = JournalWriter::try_new(JournalFlavor::Ledger, base_path, SetSize::Sz1e3, ShardType::Single, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
         let (dir_single, path_single) = writer_single.setup.journal_path(&ts, 0);
         assert_eq!(dir_single, base_path.join("set-1e3-single/txns"));
         assert_eq!(
@@ -173,7 +808,7 @@ mod tests {
         );
 
         // Test for ShardType::Month
-        let mut writer_month = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e5, ShardType::Month).unwrap(/*:test:*/);
+        let writer_month = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e5, ShardType::Month, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
         let (dir_month, path_month) = writer_month.setup.journal_path(&ts, 0);
         assert_eq!(dir_month, base_path.join("set-1e5-month/txns/2025/04"));
         assert_eq!(
@@ -182,7 +817,7 @@ mod tests {
         );
 
         // Test for ShardType::Txn
-        let mut writer_txn = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e6, ShardType::Txn).unwrap(/*:test:*/);
+        let writer_txn = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e6, ShardType::Txn, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
         let (dir_txn, path_txn) = writer_txn.setup.journal_path(&ts, 333);
         assert_eq!(dir_txn, base_path.join("set-1e6-txn/txns/2025/04/12"));
         assert_eq!(
@@ -190,4 +825,292 @@ mod tests {
             base_path.join("set-1e6-txn/txns/2025/04/12/20250412T123456-333.txn")
         );
     }
+
+    #[test]
+    fn test_journal_position() {
+        // UUID as part of path to make it unique, so JournalWriter::try_new won't fail
+        let base_path = Path::new("target/6a3e4a7e-0c8b-4d5c-9a63-1f0c0b1f6e0e");
+        // This test writes to the target path, so clean up results of previous run
+        let _ = fs::remove_dir_all(base_path);
+        let ts = ("2025-04-12T12:34:56+00:00[UTC]")
+            .parse()
+            .expect("Failed to parse timestamp");
+
+        let mut writer = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e1, ShardType::Month, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        writer.track_lines();
+
+        let (path, line) = writer.journal_position(&ts, 1, "");
+        assert_eq!(
+            path,
+            base_path.join("set-1e1-month/txns/2025/04/2025-04.txn")
+        );
+        assert_eq!(line, 1);
+
+        writer.write_txn(&ts, 1, "2025-04-12 txn\n  a  1\n  b\n\n").unwrap(/*:test:*/);
+        let (_, line) = writer.journal_position(&ts, 2, "");
+        assert_eq!(line, 5);
+    }
+
+    #[test]
+    fn test_commit() {
+        // UUID as part of path to make it unique, so JournalWriter::try_new won't fail
+        let base_path = Path::new("target/0d6f3c2e-5b0a-4e8e-8f5c-2a4b1c7d9e13");
+        // This test writes to the target path, so clean up results of previous run
+        let _ = fs::remove_dir_all(base_path);
+        let ts = ("2025-04-12T12:34:56+00:00[UTC]")
+            .parse()
+            .expect("Failed to parse timestamp");
+        let new_writer = |output: OutputPolicy| {
+            JournalWriter::try_new(
+                JournalFlavor::Tackler,
+                base_path,
+                SetSize::Sz1e1,
+                ShardType::Month,
+                output.into(),
+            )
+        };
+        let journal = base_path.join("set-1e1-month/txns/2025/04/2025-04.txn");
+
+        let mut writer = new_writer(OutputPolicy::Create).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        writer.write_txn(&ts, 1, "2025-04-12 txn-1\n").unwrap(/*:test:*/);
+        writer.write_config("config\n").unwrap(/*:test:*/);
+        assert!(!journal.exists());
+        writer.commit().unwrap(/*:test:*/);
+        assert_eq!(
+            fs::read_to_string(&journal).unwrap(/*:test:*/),
+            "2025-04-12 txn-1\n"
+        );
+        assert!(base_path.join("set-1e1-month.toml").exists());
+        assert!(!writer.setup.staging_path().exists());
+
+        assert!(new_writer(OutputPolicy::Create).is_err());
+        assert!(new_writer(OutputPolicy::SkipExisting).unwrap(/*:test:*/).is_none());
+
+        // Uncommitted files are removed, and the set is kept
+        let mut writer = new_writer(OutputPolicy::Overwrite).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        writer.write_txn(&ts, 1, "2025-04-12 txn-2\n").unwrap(/*:test:*/);
+        let staging = writer.setup.staging_path();
+        drop(writer);
+        assert!(!staging.exists());
+        assert_eq!(
+            fs::read_to_string(&journal).unwrap(/*:test:*/),
+            "2025-04-12 txn-1\n"
+        );
+
+        let mut writer = new_writer(OutputPolicy::Overwrite).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        writer.write_txn(&ts, 1, "2025-04-12 txn-3\n").unwrap(/*:test:*/);
+        writer.commit().unwrap(/*:test:*/);
+        assert_eq!(
+            fs::read_to_string(&journal).unwrap(/*:test:*/),
+            "2025-04-12 txn-3\n"
+        );
+        assert!(!writer.setup.replaced_path().exists());
+
+        // Failed commit keeps the existing set
+        let mut writer = new_writer(OutputPolicy::Overwrite).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        writer.write_txn(&ts, 1, "2025-04-12 txn-4\n").unwrap(/*:test:*/);
+        writer.close_journals().unwrap(/*:test:*/);
+        let staging = writer.setup.staging_path();
+        fs::remove_dir_all(staging.join("set-1e1-month")).unwrap(/*:test:*/);
+        assert!(writer.commit().is_err());
+        assert_eq!(
+            fs::read_to_string(&journal).unwrap(/*:test:*/),
+            "2025-04-12 txn-3\n"
+        );
+        assert!(!writer.setup.replaced_path().exists());
+
+        // Set of an interrupted commit is restored
+        fs::rename(base_path.join("set-1e1-month"), writer.setup.replaced_path()).unwrap(/*:test:*/);
+        assert!(new_writer(OutputPolicy::SkipExisting).unwrap(/*:test:*/).is_none());
+        assert_eq!(
+            fs::read_to_string(&journal).unwrap(/*:test:*/),
+            "2025-04-12 txn-3\n"
+        );
+
+        // Single shard set exists, if any file of the flavor exists
+        let new_single = |output: OutputPolicy| {
+            JournalWriter::try_new(
+                JournalFlavor::Ledger,
+                base_path,
+                SetSize::Sz1e1,
+                ShardType::Single,
+                output.into(),
+            )
+        };
+        let mut writer = new_single(OutputPolicy::Create).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        writer.write_chart_of_accounts("account a\n").unwrap(/*:test:*/);
+        writer.commit().unwrap(/*:test:*/);
+        let err = new_single(OutputPolicy::Create).err().unwrap(/*:test:*/);
+        assert!(err.to_string().contains("accounts.ledger"), "{err}");
+        assert!(new_single(OutputPolicy::SkipExisting).unwrap(/*:test:*/).is_none());
+        assert!(new_single(OutputPolicy::Overwrite).unwrap(/*:test:*/).is_some());
+    }
+
+    #[test]
+    fn test_archive() {
+        // UUID as part of path to make it unique, so JournalWriter::try_new won't fail
+        let base_path = Path::new("target/9b2e7f41-3c5d-4a8e-b1f6-7d0e2c4a5b98");
+        // This test writes to the target path, so clean up results of previous run
+        let _ = fs::remove_dir_all(base_path);
+        let ts = ("2025-04-12T12:34:56+00:00[UTC]")
+            .parse()
+            .expect("Failed to parse timestamp");
+        let write_set = |archive: Archive| {
+            let output = Output {
+                policy: OutputPolicy::Overwrite,
+                archive: Some(archive),
+                shards: ShardOptions::default(),
+            };
+            let mut writer = JournalWriter::try_new(JournalFlavor::Ledger, base_path, SetSize::Sz1e1, ShardType::Single, output).unwrap(/*:test:*/).unwrap(/*:test:*/);
+            writer.write_txn(&ts, 1, "2025-04-12 txn-1\n").unwrap(/*:test:*/);
+            writer.write_txn(&ts, 2, "2025-04-12 txn-2\n").unwrap(/*:test:*/);
+            writer.commit().unwrap(/*:test:*/);
+            writer.setup.archive_path()
+        };
+
+        let archive = write_set(Archive::Tar).unwrap(/*:test:*/);
+        assert_eq!(archive, base_path.join("set-1e1-single-ledger.tar"));
+        let tar = fs::read(&archive).unwrap(/*:test:*/);
+        // dirs: set/, set/txns/, journal: header and data, end: two blocks
+        assert_eq!(tar.len(), 6 * 512);
+        assert_eq!(
+            &tar[3 * 512..3 * 512 + 34],
+            b"2025-04-12 txn-1\n2025-04-12 txn-2\n"
+        );
+        assert!(!base_path.join("set-1e1-single").exists());
+
+        // Archive is the same when it's written again
+        let archive = write_set(Archive::TarGz).unwrap(/*:test:*/);
+        let gz = fs::read(&archive).unwrap(/*:test:*/);
+        write_set(Archive::TarGz);
+        assert_eq!(fs::read(&archive).unwrap(/*:test:*/), gz);
+
+        assert!(write_set(Archive::Gz).is_none());
+        let journal = base_path.join("set-1e1-single/txns/1e1.journal.gz");
+        let mut text = String::new();
+        let mut decoder = flate2::read::GzDecoder::new(File::open(journal).unwrap(/*:test:*/));
+        io::Read::read_to_string(&mut decoder, &mut text).unwrap(/*:test:*/);
+        assert_eq!(text, "2025-04-12 txn-1\n2025-04-12 txn-2\n");
+
+        // Closed journals are continued, and they are written into the archive at commit
+        let output = Output {
+            policy: OutputPolicy::Overwrite,
+            archive: Some(Archive::Tar),
+            shards: ShardOptions::default(),
+        };
+        let mut writer = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e2, ShardType::Day, output).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        let days = MAX_OPEN_JOURNALS as i64 + 2;
+        for day in 0..days {
+            let ts = ts.checked_add(jiff::Span::new().days(day)).unwrap(/*:test:*/);
+            writer.write_txn(&ts, 1, "txn\n").unwrap(/*:test:*/);
+        }
+        writer.write_txn(&ts, 2, "txn\n").unwrap(/*:test:*/);
+        assert!(!writer.staged(&writer.setup.txn_set_path()).exists());
+        writer.commit().unwrap(/*:test:*/);
+        let archive = writer.setup.archive_path().unwrap(/*:test:*/);
+        let mut archive = tar::Archive::new(File::open(archive).unwrap(/*:test:*/));
+        let journals: Vec<_> = archive
+            .entries()
+            .unwrap(/*:test:*/)
+            .map(|e| e.unwrap(/*:test:*/))
+            .filter(|e| e.header().entry_type().is_file())
+            .map(|mut e| {
+                let mut text = String::new();
+                io::Read::read_to_string(&mut e, &mut text).unwrap(/*:test:*/);
+                (e.path().unwrap(/*:test:*/).display().to_string(), text)
+            })
+            .collect();
+        assert_eq!(journals.len() as i64, days);
+        assert!(journals.is_sorted());
+        assert_eq!(journals[0].0, "set-1e2-day/txns/2025/04/12/2025-04-12.txn");
+        assert_eq!(journals[0].1, "txn\ntxn\n");
+
+        // Size shards are complete, when the next shard is started
+        let output = Output {
+            policy: OutputPolicy::Overwrite,
+            archive: Some(Archive::Tar),
+            shards: ShardOptions {
+                bytes: Some(8),
+                ..ShardOptions::default()
+            },
+        };
+        let mut writer = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e1, ShardType::Size, output).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        for idx in 1..=3 {
+            writer.write_txn(&ts, idx, "txn\n").unwrap(/*:test:*/);
+        }
+        assert_eq!(writer.buffers.len(), 1);
+        writer.commit().unwrap(/*:test:*/);
+        assert!(!base_path.join("set-1e2-day").exists());
+    }
+
+    #[test]
+    fn test_shards_of_years() {
+        // UUID as part of path to make it unique, so JournalWriter::try_new won't fail
+        let base_path = Path::new("target/5e8c1d2a-7f3b-4c6e-9a0d-b4e2f1c3a7d5");
+        // This test writes to the target path, so clean up results of previous run
+        let _ = fs::remove_dir_all(base_path);
+        let mut writer = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e1, ShardType::Month, OutputPolicy::Create.into()).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        for (idx, ts) in [
+            "2024-01-12T12:00:00+00:00[UTC]",
+            "2025-01-12T12:00:00+00:00[UTC]",
+            "2026-01-12T12:00:00+00:00[UTC]",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let ts = ts.parse().unwrap(/*:test:*/);
+            writer.write_txn(&ts, idx as u32, &format!("txn-{idx}\n")).unwrap(/*:test:*/);
+        }
+        writer.commit().unwrap(/*:test:*/);
+        for (idx, y) in ["2024", "2025", "2026"].iter().enumerate() {
+            let journal = base_path.join(format!("set-1e1-month/txns/{y}/01/{y}-01.txn"));
+            assert_eq!(
+                fs::read_to_string(journal).unwrap(/*:test:*/),
+                format!("txn-{idx}\n")
+            );
+        }
+    }
+
+    #[test]
+    fn test_size_shards() {
+        // UUID as part of path to make it unique, so JournalWriter::try_new won't fail
+        let base_path = Path::new("target/c4f1a7e2-6b3d-4e59-8a0c-2d7f9b1e5a36");
+        // This test writes to the target path, so clean up results of previous run
+        let _ = fs::remove_dir_all(base_path);
+        let output = Output {
+            policy: OutputPolicy::Create,
+            archive: None,
+            shards: ShardOptions {
+                bytes: Some(20),
+                ..ShardOptions::default()
+            },
+        };
+        let ts = "2025-04-12T12:34:56+00:00[UTC]".parse().unwrap(/*:test:*/);
+        let mut writer = JournalWriter::try_new(JournalFlavor::Tackler, base_path, SetSize::Sz1e1, ShardType::Size, output).unwrap(/*:test:*/).unwrap(/*:test:*/);
+        // all writes are counted (also directives with the same index as the txn),
+        // and the large txn is larger than maximum size, but it's still written
+        let large = "txn-4 ".repeat(5);
+        let writes = [
+            (1, "txn-1\n"),
+            (2, "dir-2\n"),
+            (2, "txn-2\n"),
+            (3, "txn-3\n"),
+            (4, large.as_str()),
+            (5, "txn-5\n"),
+        ];
+        for (idx, txn) in writes {
+            let (path, _) = writer.journal_position(&ts, idx, txn);
+            assert_eq!(writer.journal_position(&ts, idx, txn).0, path);
+            writer.write_txn(&ts, idx, txn).unwrap(/*:test:*/);
+        }
+        writer.commit().unwrap(/*:test:*/);
+        let journal = |n: u32| {
+            fs::read_to_string(base_path.join(format!("set-1e1-size/txns/{n:0>6}.txn")))
+                .unwrap(/*:test:*/)
+        };
+        assert_eq!(journal(1), "txn-1\ndir-2\ntxn-2\n");
+        assert_eq!(journal(2), "txn-3\n");
+        assert_eq!(journal(3), large);
+        assert_eq!(journal(4), "txn-5\n");
+    }
 }